@@ -1,5 +1,5 @@
 use crate::error::{AppError, CommandResult};
-use crate::services::downloader::{client, config, lifecycle, DownloadItem};
+use crate::services::downloader::{client, config, lifecycle, stats, DownloadItem};
 
 use tauri_plugin_opener::OpenerExt;
 
@@ -34,6 +34,11 @@ pub async fn get_tracked_downloads() -> CommandResult<Vec<DownloadItem>> {
     lifecycle::list_status_projection().await
 }
 
+#[tauri::command]
+pub async fn get_download_stats() -> CommandResult<stats::DownloadStats> {
+    lifecycle::usage_stats().await
+}
+
 #[tauri::command]
 pub async fn get_live_download_info() -> CommandResult<Vec<client::TorrentInfo>> {
     lifecycle::live_download_info().await
@@ -49,6 +54,26 @@ pub async fn resume_download(hash: String) -> CommandResult<()> {
     lifecycle::resume(&hash).await
 }
 
+#[tauri::command]
+pub async fn recheck_download(hash: String) -> CommandResult<()> {
+    lifecycle::recheck(&hash).await
+}
+
+#[tauri::command]
+pub async fn reannounce_download(hash: String) -> CommandResult<()> {
+    lifecycle::reannounce(&hash).await
+}
+
+#[tauri::command]
+pub async fn get_download_magnet(hash: String) -> CommandResult<String> {
+    lifecycle::magnet_link(&hash).await
+}
+
+#[tauri::command]
+pub async fn export_subject_magnets(subject_id: u32) -> CommandResult<Vec<String>> {
+    lifecycle::subject_magnet_links(subject_id).await
+}
+
 #[tauri::command]
 pub async fn delete_download(hash: String, delete_files: bool) -> CommandResult<()> {
     lifecycle::delete(hash, delete_files).await
@@ -1,6 +1,224 @@
-use crate::{error::CommandResult, models::mikan::MikanResourcesResponse, services::mikan};
+use crate::{
+    error::{AppError, CommandResult},
+    models::mikan::{
+        EpisodeReleaseNotice, MappingConflictPolicy, MappingImportResult, MikanCatalogItem,
+        MikanLink, MikanMapAttempt, MikanMapEntry, MikanMapSuggestion, MikanResourceItem,
+        MikanResourcesResponse, MikanSubgroup, MyBangumiImportResult, MyBangumiPreviewItem,
+        ReleaseCluster, ResourceQuery, ResourceWatch, ResourceWatchInput,
+    },
+    services::mikan::{self, config},
+    services::sources::archive,
+};
+use std::path::PathBuf;
+use tauri_plugin_dialog::{DialogExt, FilePath};
+use tokio::sync::oneshot;
+
+const MAX_MAPPING_PACK_SIZE: u64 = 20 * 1024 * 1024;
+const MAPPING_PACK_FILE: &str = "animefun-mikan-mappings.json";
+
+fn selected_path(file_path: Option<FilePath>) -> Result<PathBuf, AppError> {
+    match file_path.ok_or(AppError::FileSelectionCancelled)? {
+        FilePath::Path(p) => Ok(p),
+        FilePath::Url(url) => url
+            .to_file_path()
+            .map_err(|_| AppError::FileSelectionFailed),
+    }
+}
 
 #[tauri::command]
 pub async fn get_mikan_resources(subject_id: u32) -> CommandResult<MikanResourcesResponse> {
     mikan::get_mikan_resources(subject_id).await
 }
+
+#[tauri::command]
+pub async fn get_mikan_release_clusters(subject_id: u32) -> CommandResult<Vec<ReleaseCluster>> {
+    mikan::get_release_clusters(subject_id).await
+}
+
+/// 查询资源存档，包括已滚出 RSS 的旧资源
+#[tauri::command]
+pub async fn query_resources(query: ResourceQuery) -> CommandResult<Vec<MikanResourceItem>> {
+    archive::query(query).await
+}
+
+#[tauri::command]
+pub async fn get_mikan_subgroups(subject_id: u32) -> CommandResult<Vec<MikanSubgroup>> {
+    mikan::list_subgroups(subject_id).await
+}
+
+#[tauri::command]
+pub async fn list_mikan_mappings() -> CommandResult<Vec<MikanMapEntry>> {
+    mikan::list_mappings().await
+}
+
+/// 待映射列表：尚未映射的订阅及最近一次自动解析失败的原因
+#[tauri::command]
+pub async fn list_mikan_unmapped() -> CommandResult<Vec<MikanMapAttempt>> {
+    mikan::list_unmapped().await
+}
+
+#[tauri::command]
+pub async fn bind_mikan_mapping(subject_id: u32, mikan_bangumi_id: u32) -> CommandResult<()> {
+    mikan::bind_manual(subject_id, mikan_bangumi_id).await
+}
+
+#[tauri::command]
+pub async fn unbind_mikan_mapping(subject_id: u32) -> CommandResult<()> {
+    mikan::unbind(subject_id).await
+}
+
+#[tauri::command]
+pub async fn reresolve_mikan_mapping(subject_id: u32) -> CommandResult<Option<u32>> {
+    mikan::re_resolve(subject_id).await
+}
+
+/// 条目映射到的全部 Mikan 番组，主映射在前
+#[tauri::command]
+pub async fn list_mikan_links(subject_id: u32) -> CommandResult<Vec<MikanLink>> {
+    mikan::links::list(subject_id).await
+}
+
+#[tauri::command]
+pub async fn add_mikan_link(link: MikanLink) -> CommandResult<()> {
+    mikan::links::add(link).await
+}
+
+#[tauri::command]
+pub async fn remove_mikan_link(subject_id: u32, mikan_bangumi_id: u32) -> CommandResult<()> {
+    mikan::links::remove(subject_id, mikan_bangumi_id).await
+}
+
+#[tauri::command]
+pub async fn set_mikan_link_range(
+    subject_id: u32,
+    mikan_bangumi_id: u32,
+    episode_start: Option<u32>,
+    episode_end: Option<u32>,
+) -> CommandResult<()> {
+    mikan::links::set_range(subject_id, mikan_bangumi_id, episode_start, episode_end).await
+}
+
+/// 把映射、集数范围、附加番组与订阅偏移导出为映射包，返回保存路径
+#[tauri::command]
+pub async fn export_mikan_mappings(app: tauri::AppHandle) -> CommandResult<String> {
+    let pack = mikan::map_pack::export().await?;
+
+    let (tx, rx) = oneshot::channel();
+    app.dialog()
+        .file()
+        .add_filter("JSON", &["json"])
+        .set_file_name(MAPPING_PACK_FILE)
+        .save_file(move |file_path: Option<FilePath>| {
+            let _ = tx.send(file_path);
+        });
+    let path = selected_path(rx.await.map_err(|_| AppError::FileSelectionFailed)?)?;
+
+    tokio::fs::write(&path, serde_json::to_string_pretty(&pack)?).await?;
+    tracing::info!(count = pack.mappings.len(), path = %path.display(), "exported mikan mappings");
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 导入映射包，与本地冲突时按 `policy` 处理
+#[tauri::command]
+pub async fn import_mikan_mappings(
+    app: tauri::AppHandle,
+    policy: MappingConflictPolicy,
+) -> CommandResult<MappingImportResult> {
+    let (tx, rx) = oneshot::channel();
+    app.dialog().file().add_filter("JSON", &["json"]).pick_file(
+        move |file_path: Option<FilePath>| {
+            let _ = tx.send(file_path);
+        },
+    );
+    let path = selected_path(rx.await.map_err(|_| AppError::FileSelectionFailed)?)?;
+
+    if tokio::fs::metadata(&path).await?.len() > MAX_MAPPING_PACK_SIZE {
+        return Err(AppError::Any("mapping pack exceeds the size limit".into()));
+    }
+    let pack = serde_json::from_str(&tokio::fs::read_to_string(&path).await?)?;
+    mikan::map_pack::import(pack, policy).await
+}
+
+#[tauri::command]
+pub async fn list_mikan_suggestions(
+    subject_id: Option<u32>,
+) -> CommandResult<Vec<MikanMapSuggestion>> {
+    mikan::list_suggestions(subject_id).await
+}
+
+#[tauri::command]
+pub async fn search_mikan(keyword: String) -> CommandResult<Vec<MikanCatalogItem>> {
+    mikan::catalog::search(&keyword).await
+}
+
+#[tauri::command]
+pub async fn get_mikan_season(year: u32, season: String) -> CommandResult<Vec<MikanCatalogItem>> {
+    mikan::catalog::season_index(year, &season).await
+}
+
+#[tauri::command]
+pub async fn subscribe_mikan_bangumi(mikan_bangumi_id: u32) -> CommandResult<u32> {
+    mikan::catalog::subscribe(mikan_bangumi_id).await
+}
+
+#[tauri::command]
+pub async fn get_mikan_config() -> CommandResult<config::MikanConfig> {
+    config::get_config().await
+}
+
+#[tauri::command]
+pub async fn set_mikan_config(config: config::MikanConfig) -> CommandResult<()> {
+    config::save_config(config).await
+}
+
+#[tauri::command]
+pub async fn get_release_preferences() -> CommandResult<config::ReleasePreferences> {
+    mikan::get_release_preferences().await
+}
+
+#[tauri::command]
+pub async fn set_release_preferences(prefs: config::ReleasePreferences) -> CommandResult<()> {
+    mikan::set_release_preferences(prefs).await
+}
+
+#[tauri::command]
+pub async fn preview_mikan_my_bangumi(token: String) -> CommandResult<Vec<MyBangumiPreviewItem>> {
+    mikan::my_bangumi::preview(&token).await
+}
+
+#[tauri::command]
+pub async fn import_mikan_my_bangumi(
+    token: String,
+    mikan_bangumi_ids: Option<Vec<u32>>,
+) -> CommandResult<MyBangumiImportResult> {
+    mikan::my_bangumi::import(&token, mikan_bangumi_ids).await
+}
+
+#[tauri::command]
+pub async fn list_resource_watches() -> CommandResult<Vec<ResourceWatch>> {
+    mikan::watch::list().await
+}
+
+#[tauri::command]
+pub async fn add_resource_watch(watch: ResourceWatchInput) -> CommandResult<ResourceWatch> {
+    mikan::watch::add(watch).await
+}
+
+#[tauri::command]
+pub async fn update_resource_watch(
+    id: u32,
+    watch: ResourceWatchInput,
+) -> CommandResult<ResourceWatch> {
+    mikan::watch::update(id, watch).await
+}
+
+#[tauri::command]
+pub async fn delete_resource_watch(id: u32) -> CommandResult<()> {
+    mikan::watch::delete(id).await
+}
+
+/// 下载更新通知中推荐的资源
+#[tauri::command]
+pub async fn download_episode_release(notice: EpisodeReleaseNotice) -> CommandResult<()> {
+    mikan::release_notice::download(notice).await
+}
@@ -1,6 +1,6 @@
 use crate::{
     error::CommandResult,
-    models::bangumi::{SubjectResponse, SubjectStatusCode},
+    models::bangumi::{EpisodeAvailability, SubjectResponse, SubjectStatusCode},
     services::subscriptions,
 };
 use std::time::Instant;
@@ -60,6 +60,37 @@ pub async fn sub_set_notify(id: u32, notify: bool) -> CommandResult<()> {
     subscriptions::set_notify(id, notify).await
 }
 
+#[tauri::command]
+pub async fn sub_get_subgroups(id: u32) -> CommandResult<Vec<u32>> {
+    subscriptions::get_subgroups(id).await
+}
+
+#[tauri::command]
+pub async fn sub_set_subgroups(id: u32, subgroups: Vec<u32>) -> CommandResult<()> {
+    subscriptions::set_subgroups(id, subgroups).await
+}
+
+#[tauri::command]
+pub async fn sub_get_episode_offset(id: u32) -> CommandResult<Option<i32>> {
+    subscriptions::get_episode_offset(id).await
+}
+
+#[tauri::command]
+pub async fn sub_set_episode_offset(id: u32, offset: Option<i32>) -> CommandResult<()> {
+    subscriptions::set_episode_offset(id, offset).await
+}
+
+/// 每一集的播出、资源、下载与观看状态
+#[tauri::command]
+pub async fn sub_get_episode_availability(id: u32) -> CommandResult<Vec<EpisodeAvailability>> {
+    subscriptions::availability::episode_availability(id).await
+}
+
+#[tauri::command]
+pub async fn sub_set_episode_watched(id: u32, episode: u32, watched: bool) -> CommandResult<()> {
+    subscriptions::set_watched(id, episode, watched).await
+}
+
 #[tauri::command]
 pub fn send_test_notification() -> CommandResult<()> {
     crate::infra::notification::notify_test()
@@ -35,6 +35,8 @@ pub enum AppError {
     PlayableFileNotFound,
     #[error("external downloader rejected the request: {0}")]
     DownloaderRejected(String),
+    #[error("external downloader banned this IP after too many failed logins")]
+    DownloaderBanned,
     #[error("failed to open path: {0}")]
     OpenPath(String),
     #[error("invalid profile: {0}")]
@@ -74,6 +76,7 @@ impl AppError {
             AppError::DownloadNotFound => "download_not_found",
             AppError::PlayableFileNotFound => "playable_file_not_found",
             AppError::DownloaderRejected(_) => "downloader_rejected",
+            AppError::DownloaderBanned => "downloader_banned",
             AppError::OpenPath(_) => "open_path",
             AppError::ProfileInvalid(_) => "profile_invalid",
             AppError::ProfileUnavailable(_) => "profile_unavailable",
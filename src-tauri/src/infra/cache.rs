@@ -5,41 +5,68 @@ use crate::infra::time::now_secs;
 use tracing::{debug, info};
 
 type CacheResult<T = ()> = Result<T, rusqlite::Error>;
-type CacheValue = (String, Option<String>, Option<String>);
 
+/// 过期但带有 ETag/Last-Modified 的条目保留这么久，供条件请求复用
+const REVALIDATE_KEEP_SECS: i64 = 7 * 24 * 3600;
+
+/// 缓存条目；`fresh` 为 false 表示已过期，只能用于条件请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedEntry {
+    pub value: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fresh: bool,
+}
+
+impl CachedEntry {
+    pub fn has_validator(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+/// 只返回未过期的条目
 pub async fn get_entry(
     key: &str,
 ) -> Result<Option<(String, Option<String>, Option<String>)>, AppError> {
+    Ok(get_with_stale(key)
+        .await?
+        .filter(|e| e.fresh)
+        .map(|e| (e.value, e.etag, e.last_modified)))
+}
+
+/// 返回条目（包括已过期的），用于发起条件请求
+pub async fn get_with_stale(key: &str) -> Result<Option<CachedEntry>, AppError> {
     let pool = crate::infra::db::cache_pool()?;
     let key = key.to_string();
     let conn = pool.get().await?;
     let out = conn
-        .interact(move |conn| -> CacheResult<Option<CacheValue>> {
-            let mut stmt = conn.prepare(
-                "SELECT value, expires_at, etag, last_modified FROM cache WHERE key = ?1",
-            )?;
-            let mut rows = stmt.query(params![key.clone()])?;
-            if let Some(row) = rows.next()? {
-                let expires_at: i64 = row.get(1)?;
-                if now_secs() <= expires_at {
-                    let value: String = row.get(0)?;
-                    let etag: Option<String> = row.get(2)?;
-                    let last_modified: Option<String> = row.get(3)?;
-                    debug!(key, "cache hit");
-                    Ok(Some((value, etag, last_modified)))
-                } else {
-                    let _ = conn.execute("DELETE FROM cache WHERE key = ?1", params![key]);
-                    debug!("cache expired and deleted");
-                    Ok(None)
-                }
-            } else {
-                Ok(None)
-            }
-        })
+        .interact(move |conn| get_row(conn, &key, now_secs()))
         .await??;
     Ok(out)
 }
 
+fn get_row(conn: &rusqlite::Connection, key: &str, now: i64) -> CacheResult<Option<CachedEntry>> {
+    let mut stmt =
+        conn.prepare("SELECT value, expires_at, etag, last_modified FROM cache WHERE key = ?1")?;
+    let mut rows = stmt.query(params![key])?;
+    let Some(row) = rows.next()? else {
+        return Ok(None);
+    };
+    let expires_at: i64 = row.get(1)?;
+    let fresh = now <= expires_at;
+    if fresh {
+        debug!(key, "cache hit");
+    } else {
+        debug!(key, "cache expired, kept for revalidation");
+    }
+    Ok(Some(CachedEntry {
+        value: row.get(0)?,
+        etag: row.get(2)?,
+        last_modified: row.get(3)?,
+        fresh,
+    }))
+}
+
 pub async fn set_entry(
     key: &str,
     value: String,
@@ -47,27 +74,112 @@ pub async fn set_entry(
     last_modified: Option<String>,
     ttl_secs: i64,
 ) -> Result<(), AppError> {
+    let pool = crate::infra::db::cache_pool()?;
+    let key = key.to_string();
+    let conn = pool.get().await?;
+    conn.interact(move |conn| {
+        set_row(
+            conn,
+            &key,
+            &value,
+            etag,
+            last_modified,
+            ttl_secs,
+            now_secs(),
+        )
+    })
+    .await??;
+    info!("cache upsert and cleanup");
+    Ok(())
+}
+
+fn set_row(
+    conn: &rusqlite::Connection,
+    key: &str,
+    value: &str,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    ttl_secs: i64,
+    now: i64,
+) -> CacheResult {
+    let ttl = if ttl_secs <= 0 { 1 } else { ttl_secs };
+    let expires = now + ttl;
+    conn.execute(
+        "INSERT INTO cache(key, value, updated_at, expires_at, etag, last_modified) VALUES(?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(key) DO UPDATE SET
+            value=excluded.value,
+            updated_at=excluded.updated_at,
+            expires_at=excluded.expires_at,
+            etag=excluded.etag,
+            last_modified=excluded.last_modified",
+        params![key, value, now, expires, etag, last_modified],
+    )?;
+    // 无法重新验证的过期条目立即清理；可验证的保留一段时间
+    let _ = conn.execute(
+        "DELETE FROM cache WHERE expires_at < ?1
+            AND ((etag IS NULL AND last_modified IS NULL) OR expires_at < ?2)",
+        params![now, now - REVALIDATE_KEEP_SECS],
+    );
+    Ok(())
+}
+
+/// 收到 304 后延长条目的有效期，内容与验证器保持不变
+pub async fn touch_entry(key: &str, ttl_secs: i64) -> Result<(), AppError> {
+    let pool = crate::infra::db::cache_pool()?;
+    let key = key.to_string();
+    let conn = pool.get().await?;
+    conn.interact(move |conn| touch_row(conn, &key, ttl_secs, now_secs()))
+        .await??;
+    Ok(())
+}
+
+fn touch_row(conn: &rusqlite::Connection, key: &str, ttl_secs: i64, now: i64) -> CacheResult {
+    let ttl = if ttl_secs <= 0 { 1 } else { ttl_secs };
+    conn.execute(
+        "UPDATE cache SET updated_at = ?2, expires_at = ?3 WHERE key = ?1",
+        params![key, now, now + ttl],
+    )?;
+    Ok(())
+}
+
+pub async fn delete_entry(key: &str) -> Result<(), AppError> {
     let pool = crate::infra::db::cache_pool()?;
     let key = key.to_string();
     let conn = pool.get().await?;
     conn.interact(move |conn| -> Result<(), rusqlite::Error> {
-        let now = now_secs();
-        let ttl = if ttl_secs <= 0 { 1 } else { ttl_secs };
-        let expires = now + ttl;
-        conn.execute(
-            "INSERT INTO cache(key, value, updated_at, expires_at, etag, last_modified) VALUES(?1, ?2, ?3, ?4, ?5, ?6)
-                ON CONFLICT(key) DO UPDATE SET
-                value=excluded.value,
-                updated_at=excluded.updated_at,
-                expires_at=excluded.expires_at,
-                etag=excluded.etag,
-                last_modified=excluded.last_modified",
-            params![key, value, now, expires, etag, last_modified],
-        )?;
-        let _ = conn.execute("DELETE FROM cache WHERE expires_at < ?1", params![now]);
-        info!("cache upsert and cleanup");
+        conn.execute("DELETE FROM cache WHERE key = ?1", params![key])?;
         Ok(())
     })
     .await??;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_entries_with_validators_survive_for_revalidation() {
+        let conn = crate::infra::db::test_cache_connection();
+        set_row(&conn, "etag", "a", Some("\"v1\"".into()), None, 10, 0).unwrap();
+        set_row(&conn, "plain", "b", None, None, 10, 0).unwrap();
+
+        // 过期后写入其他条目会触发清理
+        set_row(&conn, "other", "c", None, None, 10, 100).unwrap();
+        assert!(get_row(&conn, "plain", 100).unwrap().is_none());
+        let stale = get_row(&conn, "etag", 100).unwrap().unwrap();
+        assert!(!stale.fresh);
+        assert!(stale.has_validator());
+
+        touch_row(&conn, "etag", 10, 100).unwrap();
+        let revalidated = get_row(&conn, "etag", 105).unwrap().unwrap();
+        assert!(revalidated.fresh);
+        assert_eq!(revalidated.value, "a");
+        assert_eq!(revalidated.etag.as_deref(), Some("\"v1\""));
+
+        // 验证器也救不回太久以前的条目
+        let later = 111 + REVALIDATE_KEEP_SECS;
+        set_row(&conn, "other", "c", None, None, 10, later).unwrap();
+        assert!(get_row(&conn, "etag", later).unwrap().is_none());
+    }
+}
@@ -1,5 +1,6 @@
 pub const BGM_API_HOST: &str = "https://api.bgm.tv";
-pub const MIKAN_HOST: &str = "https://mikanani.me";
+/// 内置的 Mikan 镜像，未配置时按顺序尝试
+pub const MIKAN_HOSTS: &[&str] = &["https://mikanani.me", "https://mikanime.tv"];
 pub const USER_AGENT: &str = "animefun/0.1";
 pub const HTTP_TIMEOUT_SECS: u64 = 30;
 pub const API_RATE_LIMIT: u32 = 10;
@@ -21,7 +21,8 @@ const CACHE_MIGRATIONS: &[&str] = &[r#"
     );
 "#];
 
-const DATA_MIGRATIONS: &[&str] = &[r#"
+const DATA_MIGRATIONS: &[&str] = &[
+    r#"
     CREATE TABLE IF NOT EXISTS subscriptions (
         subject_id  INTEGER PRIMARY KEY,
         added_at    INTEGER NOT NULL,
@@ -68,7 +69,146 @@ const DATA_MIGRATIONS: &[&str] = &[r#"
         created_at INTEGER NOT NULL,
         updated_at INTEGER NOT NULL
     );
-"#];
+"#,
+    r#"
+    ALTER TABLE tracked_downloads ADD COLUMN display_name TEXT;
+    ALTER TABLE tracked_downloads ADD COLUMN trackers_json TEXT;
+"#,
+    r#"
+    CREATE TABLE IF NOT EXISTS download_stats (
+        hash          TEXT PRIMARY KEY,
+        subject_id    INTEGER NOT NULL,
+        episode       INTEGER,
+        episode_range TEXT,
+        group_name    TEXT,
+        resolution    INTEGER,
+        size_bytes    INTEGER NOT NULL DEFAULT 0,
+        added_on      INTEGER NOT NULL DEFAULT 0,
+        completed_on  INTEGER,
+        updated_at    INTEGER NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_download_stats_subject ON download_stats(subject_id);
+"#,
+    r#"
+    CREATE TABLE IF NOT EXISTS mikan_map_suggestions (
+        bgm_subject_id   INTEGER NOT NULL,
+        mikan_bangumi_id INTEGER NOT NULL,
+        score            REAL    NOT NULL,
+        title            TEXT    NOT NULL,
+        created_at       INTEGER NOT NULL,
+        PRIMARY KEY (bgm_subject_id, mikan_bangumi_id)
+    );
+"#,
+    r#"
+    ALTER TABLE subscriptions ADD COLUMN mikan_subgroups TEXT;
+"#,
+    r#"
+    ALTER TABLE subscriptions ADD COLUMN episode_offset INTEGER;
+"#,
+    r#"
+    CREATE TABLE IF NOT EXISTS resources (
+        subject_id    INTEGER NOT NULL,
+        resource_key  TEXT    NOT NULL,
+        source        TEXT    NOT NULL,
+        title         TEXT    NOT NULL,
+        page_url      TEXT    NOT NULL,
+        torrent_url   TEXT,
+        magnet        TEXT,
+        pub_date      TEXT,
+        size_bytes    INTEGER,
+        group_name    TEXT,
+        episode       INTEGER,
+        range_start   INTEGER,
+        range_end     INTEGER,
+        resolution    INTEGER,
+        subtitle_lang TEXT,
+        subtitle_type TEXT,
+        first_seen    INTEGER NOT NULL,
+        last_seen     INTEGER NOT NULL,
+        PRIMARY KEY (subject_id, resource_key)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_resources_subject_episode ON resources(subject_id, episode);
+"#,
+    r#"
+    CREATE TABLE IF NOT EXISTS seen_episodes (
+        subject_id  INTEGER NOT NULL,
+        episode_key TEXT    NOT NULL,
+        first_seen  INTEGER NOT NULL,
+        PRIMARY KEY (subject_id, episode_key)
+    );
+
+    ALTER TABLE subscriptions ADD COLUMN seen_initialized INTEGER NOT NULL DEFAULT 0;
+"#,
+    r#"
+    CREATE TABLE IF NOT EXISTS watched_episodes (
+        subject_id INTEGER NOT NULL,
+        episode    INTEGER NOT NULL,
+        watched_at INTEGER NOT NULL,
+        PRIMARY KEY (subject_id, episode)
+    );
+"#,
+    r#"
+    CREATE TABLE IF NOT EXISTS resource_watches (
+        id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+        name                TEXT    NOT NULL,
+        keyword             TEXT    NOT NULL,
+        pattern             TEXT,
+        download_subject_id INTEGER,
+        enabled             INTEGER NOT NULL DEFAULT 1,
+        initialized         INTEGER NOT NULL DEFAULT 0,
+        created_at          INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS resource_watch_hits (
+        watch_id     INTEGER NOT NULL,
+        resource_key TEXT    NOT NULL,
+        first_seen   INTEGER NOT NULL,
+        PRIMARY KEY (watch_id, resource_key)
+    );
+"#,
+    r#"
+    CREATE TABLE IF NOT EXISTS mikan_map_attempts (
+        bgm_subject_id INTEGER PRIMARY KEY,
+        attempts       INTEGER NOT NULL,
+        search_terms   TEXT    NOT NULL,
+        candidates     TEXT    NOT NULL,
+        reason         TEXT    NOT NULL,
+        error          TEXT,
+        last_attempt   INTEGER NOT NULL,
+        next_retry     INTEGER NOT NULL
+    );
+"#,
+    r#"
+    ALTER TABLE mikan_bangumi_map ADD COLUMN episode_start INTEGER;
+    ALTER TABLE mikan_bangumi_map ADD COLUMN episode_end INTEGER;
+
+    CREATE TABLE IF NOT EXISTS mikan_bangumi_links (
+        bgm_subject_id   INTEGER NOT NULL,
+        mikan_bangumi_id INTEGER NOT NULL,
+        role             TEXT    NOT NULL,
+        episode_start    INTEGER,
+        episode_end      INTEGER,
+        created_at       INTEGER NOT NULL,
+        PRIMARY KEY (bgm_subject_id, mikan_bangumi_id)
+    );
+"#,
+    r#"
+    CREATE TABLE IF NOT EXISTS mikan_my_bangumi_seen (
+        mikan_bangumi_id INTEGER PRIMARY KEY,
+        seen_at          INTEGER NOT NULL
+    );
+"#,
+    r#"
+    ALTER TABLE resources ADD COLUMN mikan_bangumi_id INTEGER;
+
+    UPDATE resources SET mikan_bangumi_id = (
+        SELECT m.mikan_bangumi_id FROM mikan_bangumi_map m
+        WHERE m.bgm_subject_id = resources.subject_id
+    ) WHERE source = 'mikan';
+"#,
+];
 
 const DATA_LEGACY_COLUMNS: &[(&str, &str, &str)] = &[
     (
@@ -194,6 +334,21 @@ fn init_db(
     Ok(())
 }
 
+/// 已执行全部迁移的内存数据库，供仓储层单元测试使用
+#[cfg(test)]
+pub(crate) fn test_cache_connection() -> rusqlite::Connection {
+    let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+    migrate_connection(&mut conn, CACHE_MIGRATIONS, &[]).unwrap();
+    conn
+}
+
+#[cfg(test)]
+pub(crate) fn test_data_connection() -> rusqlite::Connection {
+    let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+    migrate_connection(&mut conn, DATA_MIGRATIONS, DATA_LEGACY_COLUMNS).unwrap();
+    conn
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,6 +395,9 @@ mod tests {
             )
             .unwrap();
 
-        assert_eq!((version, last_seen_ep, episode_range), (1, 1, 1));
+        assert_eq!(
+            (version, last_seen_ep, episode_range),
+            (DATA_MIGRATIONS.len(), 1, 1)
+        );
     }
 }
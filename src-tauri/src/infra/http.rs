@@ -32,3 +32,19 @@ pub static LIMITER: Lazy<DefaultDirectRateLimiter> = Lazy::new(|| {
 pub async fn wait_api_limit() {
     LIMITER.until_ready().await;
 }
+
+/// 为条件请求附加 `If-None-Match` / `If-Modified-Since`
+pub fn with_validators(
+    req: reqwest::RequestBuilder,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> reqwest::RequestBuilder {
+    let mut req = req;
+    if let Some(etag) = etag {
+        req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(lm) = last_modified {
+        req = req.header(reqwest::header::IF_MODIFIED_SINCE, lm);
+    }
+    req
+}
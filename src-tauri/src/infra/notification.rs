@@ -1,11 +1,16 @@
 use crate::error::AppError;
-use once_cell::sync::OnceCell;
-use tauri::AppHandle;
+use crate::models::mikan::EpisodeReleaseNotice;
+use once_cell::sync::{Lazy, OnceCell};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_notification::NotificationExt;
 use tracing::error;
 
 static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
 
+/// Latest release announced while the main window was in the background
+static PENDING_RELEASE: Lazy<Mutex<Option<EpisodeReleaseNotice>>> = Lazy::new(|| Mutex::new(None));
+
 /// Initialize the notification module with the AppHandle
 pub fn init(handle: AppHandle) {
     if APP_HANDLE.set(handle).is_err() {
@@ -29,20 +34,90 @@ fn send_notification(title: &str, body: &str) -> Result<(), AppError> {
         .map_err(|error| AppError::Any(format!("notification delivery failed: {error}")))
 }
 
-/// Send a desktop notification for new episode
-pub fn notify_new_episode(anime_name: &str, episode: u32) -> Result<(), AppError> {
+/// Event carrying the recommended release for a new episode
+pub const EPISODE_RELEASE_EVENT: &str = "episode-release";
+
+/// Send a desktop notification for newly available episodes.
+/// `releases` are summary lines of the recommended release for each episode.
+pub fn notify_new_episodes(
+    anime_name: &str,
+    labels: &[String],
+    releases: &[String],
+) -> Result<(), AppError> {
     let title = format!("{} 更新提醒", anime_name);
-    let body = format!("第 {} 话资源已发布", episode);
+    let mut body = format!("{} 资源已发布", labels.join("、"));
+    for line in releases {
+        body.push('\n');
+        body.push_str(line);
+    }
 
     tracing::info!(
         anime = anime_name,
-        episode,
+        episodes = %labels.join(","),
         "sending new episode notification"
     );
 
     send_notification(&title, &body)
 }
 
+/// Event asking the frontend to open the resource list of a pending release
+pub const EPISODE_RELEASE_OPEN_EVENT: &str = "episode-release-open";
+
+fn main_window_focused(handle: &AppHandle) -> bool {
+    handle
+        .get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+/// Emit the recommended release to the frontend, which offers the download action.
+/// When the main window is in the background the notice is kept until it is focused,
+/// since desktop notifications carry no click callback.
+pub fn emit_episode_release(notice: &EpisodeReleaseNotice) -> Result<(), AppError> {
+    let handle = APP_HANDLE
+        .get()
+        .ok_or_else(|| AppError::Any("notification app handle not initialized".to_string()))?;
+    if !main_window_focused(handle) {
+        if let Ok(mut pending) = PENDING_RELEASE.lock() {
+            *pending = Some(notice.clone());
+        }
+    }
+    handle
+        .emit(EPISODE_RELEASE_EVENT, notice)
+        .map_err(|error| AppError::Any(format!("episode release event failed: {error}")))
+}
+
+/// Called when the main window gains focus, usually after the notification was clicked:
+/// asks the frontend to open the resource list of the pending release
+pub fn open_pending_release(handle: &AppHandle) {
+    let Some(notice) = PENDING_RELEASE.lock().ok().and_then(|mut g| g.take()) else {
+        return;
+    };
+    if let Err(error) = handle.emit(EPISODE_RELEASE_OPEN_EVENT, &notice) {
+        error!(error = %error, "episode release open event failed");
+    }
+}
+
+/// Send a desktop notification for new resources matching a keyword watch
+pub fn notify_watch_match(watch_name: &str, titles: &[String]) -> Result<(), AppError> {
+    let Some(first) = titles.first() else {
+        return Ok(());
+    };
+    let title = format!("{} 有新资源", watch_name);
+    let body = match titles.len() {
+        1 => first.clone(),
+        n => format!("{} 等 {} 个资源", first, n),
+    };
+
+    tracing::info!(
+        watch = watch_name,
+        count = titles.len(),
+        "sending resource watch notification"
+    );
+
+    send_notification(&title, &body)
+}
+
 /// Send a test notification
 pub fn notify_test() -> Result<(), AppError> {
     tracing::info!("sending test notification");
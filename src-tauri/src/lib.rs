@@ -5,6 +5,8 @@ mod models;
 pub mod services;
 pub mod utils;
 
+use tauri::Manager;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -22,10 +24,18 @@ pub fn run() {
             ));
             crate::services::subscriptions::spawn_refresh_worker();
             crate::services::mikan::spawn_preheat_worker();
+            crate::services::mikan::spawn_sync_worker();
             crate::services::downloader::monitor::spawn_status_monitor(app.handle().clone());
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Focused(true) = event {
+                if window.label() == "main" {
+                    crate::infra::notification::open_pending_release(window.app_handle());
+                }
+            }
+        })
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_process::init())
@@ -44,17 +54,57 @@ pub fn run() {
             commands::subscriptions::sub_has,
             commands::subscriptions::sub_clear,
             commands::subscriptions::sub_set_notify,
+            commands::subscriptions::sub_get_subgroups,
+            commands::subscriptions::sub_set_subgroups,
+            commands::subscriptions::sub_get_episode_offset,
+            commands::subscriptions::sub_set_episode_offset,
+            commands::subscriptions::sub_get_episode_availability,
+            commands::subscriptions::sub_set_episode_watched,
             commands::subscriptions::send_test_notification,
             commands::subscriptions::sub_query,
             commands::mikan::get_mikan_resources,
+            commands::mikan::get_mikan_subgroups,
+            commands::mikan::get_mikan_release_clusters,
+            commands::mikan::query_resources,
+            commands::mikan::list_mikan_mappings,
+            commands::mikan::list_mikan_unmapped,
+            commands::mikan::bind_mikan_mapping,
+            commands::mikan::unbind_mikan_mapping,
+            commands::mikan::reresolve_mikan_mapping,
+            commands::mikan::list_mikan_suggestions,
+            commands::mikan::export_mikan_mappings,
+            commands::mikan::import_mikan_mappings,
+            commands::mikan::list_mikan_links,
+            commands::mikan::add_mikan_link,
+            commands::mikan::remove_mikan_link,
+            commands::mikan::set_mikan_link_range,
+            commands::mikan::search_mikan,
+            commands::mikan::get_mikan_season,
+            commands::mikan::subscribe_mikan_bangumi,
+            commands::mikan::get_mikan_config,
+            commands::mikan::set_mikan_config,
+            commands::mikan::get_release_preferences,
+            commands::mikan::set_release_preferences,
+            commands::mikan::preview_mikan_my_bangumi,
+            commands::mikan::import_mikan_my_bangumi,
+            commands::mikan::list_resource_watches,
+            commands::mikan::add_resource_watch,
+            commands::mikan::update_resource_watch,
+            commands::mikan::delete_resource_watch,
+            commands::mikan::download_episode_release,
             commands::downloader::get_downloader_config,
             commands::downloader::set_downloader_config,
             commands::downloader::test_downloader_connection,
             commands::downloader::add_torrent_and_track,
             commands::downloader::get_tracked_downloads,
             commands::downloader::get_live_download_info,
+            commands::downloader::get_download_stats,
             commands::downloader::pause_download,
             commands::downloader::resume_download,
+            commands::downloader::recheck_download,
+            commands::downloader::reannounce_download,
+            commands::downloader::get_download_magnet,
+            commands::downloader::export_subject_magnets,
             commands::downloader::delete_download,
             commands::downloader::open_download_folder,
             commands::downloader::play_video,
@@ -226,6 +226,24 @@ pub struct Episode {
     pub subject_id: Option<u32>,
 }
 
+/// 订阅条目中一集的播出、资源、下载与观看状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/bangumi.ts")]
+pub struct EpisodeAvailability {
+    pub episode_id: u32,
+    pub sort: f32,
+    pub name: String,
+    pub name_cn: String,
+    pub airdate: String,
+    pub aired: bool,
+    /// 已发布该集的字幕组，包括覆盖该集的合集
+    pub groups: Vec<String>,
+    pub resolutions: Vec<u32>,
+    pub tracked: bool,
+    pub downloaded: bool,
+    pub watched: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/gen/bangumi.ts")]
 pub struct PagedEpisode {
@@ -1,6 +1,18 @@
+use crate::models::release::ReleaseInfo;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+/// 资源来自哪个站点
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub enum ResourceSource {
+    #[default]
+    Mikan,
+    Dmhy,
+    Nyaa,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/gen/mikan.ts")]
 pub struct MikanResourceItem {
@@ -26,6 +38,15 @@ pub struct MikanResourceItem {
     pub subtitle_lang: Option<String>,
     #[ts(optional)]
     pub subtitle_type: Option<String>,
+    /// 来自哪个 Mikan 番组的 feed；其他站点的资源为空
+    #[ts(optional)]
+    #[serde(default)]
+    pub mikan_bangumi_id: Option<u32>,
+    #[serde(default)]
+    pub source: ResourceSource,
+    /// 完整的标题解析结果；上面的扁平字段保留给现有界面使用
+    #[serde(default)]
+    pub release: ReleaseInfo,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -36,3 +57,243 @@ pub struct MikanResourcesResponse {
     pub mikan_bangumi_id: Option<u32>,
     pub items: Vec<MikanResourceItem>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct MikanMapEntry {
+    pub subject_id: u32,
+    pub mikan_bangumi_id: u32,
+    pub confidence: f64,
+    pub source: String,
+    pub locked: bool,
+    pub updated_at: i64,
+    pub name: String,
+    pub name_cn: String,
+}
+
+/// 条目与某个 Mikan 番组的关系
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub enum MikanLinkRole {
+    /// 主映射：自动解析或手动绑定
+    Primary,
+    /// Mikan 单独拆出的部分，如第二部分或剧场剪辑版
+    Split,
+    /// Mikan 把多季合在一个番组里，通常配合集数范围使用
+    Merged,
+    /// 其他补充番组
+    Extra,
+}
+
+/// 条目映射到的一个 Mikan 番组；集数范围按 feed 中的原始编号过滤资源
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct MikanLink {
+    pub subject_id: u32,
+    pub mikan_bangumi_id: u32,
+    pub role: MikanLinkRole,
+    #[ts(optional)]
+    pub episode_start: Option<u32>,
+    #[ts(optional)]
+    pub episode_end: Option<u32>,
+}
+
+/// 导入映射包时与本地映射冲突的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub enum MappingConflictPolicy {
+    KeepLocal,
+    TakeImported,
+    /// 置信度更高的一方胜出，相同时保留本地
+    HigherConfidence,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct MappingImportResult {
+    pub added: u32,
+    pub replaced: u32,
+    /// 与本地完全一致的映射
+    pub unchanged: u32,
+    /// 因冲突策略保留本地的映射
+    pub skipped: u32,
+    /// 写入了集数偏移的订阅数
+    pub offsets: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct MikanMapSuggestion {
+    pub subject_id: u32,
+    pub mikan_bangumi_id: u32,
+    pub score: f64,
+    pub title: String,
+    pub name: String,
+    pub name_cn: String,
+}
+
+/// 尚未映射到 Mikan 的订阅及最近一次自动解析的结果；从未尝试过时各字段为空
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct MikanMapAttempt {
+    pub subject_id: u32,
+    pub name: String,
+    pub name_cn: String,
+    pub attempts: u32,
+    pub search_terms: Vec<String>,
+    /// 搜索到的 Mikan 番组 ID
+    pub candidates: Vec<u32>,
+    /// `no_results` / `no_match` / `low_confidence` / `error`
+    #[ts(optional)]
+    pub reason: Option<String>,
+    #[ts(optional)]
+    pub error: Option<String>,
+    #[ts(optional)]
+    pub last_attempt: Option<i64>,
+    #[ts(optional)]
+    pub next_retry: Option<i64>,
+}
+
+/// Mikan 番组页上列出的字幕组
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct MikanSubgroup {
+    pub id: u32,
+    pub name: String,
+}
+
+/// 「我的番组」导入预览中的一项
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct MyBangumiPreviewItem {
+    pub mikan_bangumi_id: u32,
+    pub title: String,
+    /// Mikan 页面上链接的 Bangumi 条目，缺失时无法导入
+    #[ts(optional)]
+    pub subject_id: Option<u32>,
+    pub subscribed: bool,
+    /// 该条目当前已有的映射
+    #[ts(optional)]
+    pub mapped_mikan_id: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct MyBangumiImportResult {
+    pub subscribed: u32,
+    pub mapped: u32,
+    /// 无法对应到 Bangumi 条目的 Mikan 番组 id
+    pub unresolved: Vec<u32>,
+    /// 已手动绑定到其他番组而跳过的 Bangumi 条目 id
+    pub conflicts: Vec<u32>,
+}
+
+/// 资源及其按偏好计算的分数
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct RankedRelease {
+    pub score: f64,
+    pub item: MikanResourceItem,
+}
+
+/// 同一集（或同一合集范围）的资源，`best` 为首选
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct ReleaseCluster {
+    #[ts(optional)]
+    pub episode: Option<u32>,
+    #[ts(optional)]
+    pub episode_range: Option<String>,
+    pub best: RankedRelease,
+    pub alternatives: Vec<RankedRelease>,
+}
+
+/// Mikan 搜索结果或季度番组表中的一个番组
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct MikanCatalogItem {
+    pub mikan_bangumi_id: u32,
+    pub title: String,
+    #[ts(optional)]
+    pub cover_url: Option<String>,
+    /// 季度番组表中的放送日：0 为周日，7 为剧场版，8 为 OVA
+    #[ts(optional)]
+    pub day_of_week: Option<u8>,
+}
+
+/// 资源存档查询条件；集数为换算偏移后的 Bangumi 编号
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct ResourceQuery {
+    pub subject_id: u32,
+    #[ts(optional)]
+    pub episode: Option<u32>,
+    /// 字幕组名中包含该文本即匹配，忽略大小写
+    #[ts(optional)]
+    pub group: Option<String>,
+    #[ts(optional)]
+    pub resolution: Option<u32>,
+    #[ts(optional)]
+    pub limit: Option<u32>,
+}
+
+/// 关键词订阅：轮询 Mikan 搜索 feed，标题匹配的新资源会提醒
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct ResourceWatch {
+    pub id: u32,
+    pub name: String,
+    /// Mikan 搜索关键词
+    pub keyword: String,
+    /// 标题还需匹配的正则；为空时只按关键词
+    #[ts(optional)]
+    pub pattern: Option<String>,
+    /// 新资源自动下载并归入该条目
+    #[ts(optional)]
+    pub download_subject_id: Option<u32>,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+/// 新建或修改关键词订阅
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct ResourceWatchInput {
+    pub name: String,
+    pub keyword: String,
+    #[ts(optional)]
+    pub pattern: Option<String>,
+    #[ts(optional)]
+    pub download_subject_id: Option<u32>,
+    pub enabled: bool,
+}
+
+/// 预热发现新一集时推荐的资源，随 `episode-release` 事件发给前端。
+/// 集数已换算为 Bangumi 编号，可直接用于下载。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/mikan.ts")]
+pub struct EpisodeReleaseNotice {
+    pub subject_id: u32,
+    pub anime_name: String,
+    /// 通知中的集名，如 `第 5 话`
+    pub label: String,
+    pub title: String,
+    #[ts(optional)]
+    pub group: Option<String>,
+    #[ts(optional)]
+    pub resolution: Option<u32>,
+    #[ts(optional)]
+    pub subtitle_lang: Option<String>,
+    #[ts(optional)]
+    pub size_bytes: Option<u64>,
+    /// 种子或磁力链接
+    #[ts(optional)]
+    pub url: Option<String>,
+    #[ts(optional)]
+    pub episode: Option<u32>,
+    #[ts(optional)]
+    pub episode_range: Option<String>,
+}
@@ -3,3 +3,4 @@
 pub mod bangumi;
 pub mod mikan;
 pub mod profile;
+pub mod release;
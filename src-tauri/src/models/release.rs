@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// 发布来源：压制类型或 Web 平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/types/gen/release.ts")]
+pub enum ReleaseSource {
+    BdRip,
+    DvdRip,
+    Baha,
+    BGlobal,
+    Bilibili,
+    Crunchyroll,
+    WebDl,
+    WebRip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/types/gen/release.ts")]
+pub enum VideoCodec {
+    Avc,
+    Hevc,
+    Av1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/types/gen/release.ts")]
+pub enum AudioCodec {
+    Flac,
+    Eac3,
+    Ac3,
+    Dts,
+    Opus,
+    Aac,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/types/gen/release.ts")]
+pub enum Container {
+    Mkv,
+    Mp4,
+}
+
+/// 合集的集数范围（闭区间）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/release.ts")]
+pub struct EpisodeRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// 从发布标题中解析出的结构化信息，见 `utils::parser::parse_release`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/release.ts")]
+pub struct ReleaseInfo {
+    #[ts(optional)]
+    pub group: Option<String>,
+    /// 标题中以 `/` 分隔的各语言名称
+    pub titles: Vec<String>,
+    #[ts(optional)]
+    pub season: Option<u32>,
+    #[ts(optional)]
+    pub episode: Option<u32>,
+    #[ts(optional)]
+    pub episode_range: Option<EpisodeRange>,
+    /// 修正版本号，如 `05v2` 中的 2
+    #[ts(optional)]
+    pub version: Option<u32>,
+    #[ts(optional)]
+    pub resolution: Option<u32>,
+    #[ts(optional)]
+    pub source: Option<ReleaseSource>,
+    #[ts(optional)]
+    pub video_codec: Option<VideoCodec>,
+    #[ts(optional)]
+    pub bit_depth: Option<u32>,
+    #[ts(optional)]
+    pub audio_codec: Option<AudioCodec>,
+    #[ts(optional)]
+    pub container: Option<Container>,
+    /// 合集 / 全集发布
+    pub batch: bool,
+    /// 特别篇：OVA、OAD、SP 等，集数不属于正片编号
+    pub special: bool,
+    /// 字幕语言，如 `简繁`、`简日`
+    #[ts(optional)]
+    pub language: Option<String>,
+    /// 字幕形式：外挂、内封或硬字幕
+    #[ts(optional)]
+    pub subtitle_type: Option<String>,
+}
@@ -2,12 +2,12 @@ use crate::error::AppError;
 use crate::infra::cache;
 use crate::models::bangumi::{CalendarResponse, PagedEpisode, SearchResponse, SubjectResponse};
 use reqwest::header::{ETAG, LAST_MODIFIED};
-use reqwest::RequestBuilder;
+use reqwest::{RequestBuilder, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
 use tracing::debug;
 
 use crate::infra::config::BGM_API_HOST;
-use crate::infra::http::CLIENT;
+use crate::infra::http::{with_validators, CLIENT};
 
 /// TODO: 后边可以考虑做进setting可配置项中
 const TTL_CALENDAR_SECS: i64 = 3600;
@@ -150,15 +150,29 @@ async fn fetch_api<T>(
 where
     T: Serialize + DeserializeOwned,
 {
-    // 命中缓存则直接返回，不走网络
-    if let Some((cached_data, _etag, _last_modified)) = cache::get_entry(key).await? {
+    // 命中未过期缓存则直接返回，不走网络
+    let cached = cache::get_with_stale(key).await?;
+    if let Some(entry) = cached.as_ref().filter(|e| e.fresh) {
         debug!(key, "cache hit, returning cached data");
-        return serde_json::from_str::<T>(&cached_data).map_err(AppError::from);
+        return serde_json::from_str::<T>(&entry.value).map_err(AppError::from);
     }
 
-    // 未命中缓存，直接发起请求
+    // 过期条目带有验证器时发起条件请求
+    let stale = cached.filter(|e| e.has_validator());
+    let req_builder = match &stale {
+        Some(e) => with_validators(req_builder, e.etag.as_deref(), e.last_modified.as_deref()),
+        None => req_builder,
+    };
+
     crate::infra::http::wait_api_limit().await;
     let resp = req_builder.send().await?;
+    if resp.status() == StatusCode::NOT_MODIFIED {
+        if let Some(entry) = stale {
+            debug!(key, "not modified, extending cache ttl");
+            cache::touch_entry(key, cache_duration_secs).await?;
+            return serde_json::from_str::<T>(&entry.value).map_err(AppError::from);
+        }
+    }
     resp.error_for_status_ref()?;
 
     let headers = resp.headers().clone();
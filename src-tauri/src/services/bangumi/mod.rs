@@ -3,3 +3,23 @@ pub mod status;
 
 pub use api::{fetch_calendar, fetch_episodes, fetch_subject, search_subject};
 pub use status::calc_subject_status;
+
+/// 测试用正片：`ep` 与 `sort` 相同，没有放送日期
+#[cfg(test)]
+pub(crate) fn test_episode(sort: f32) -> crate::models::bangumi::Episode {
+    crate::models::bangumi::Episode {
+        id: sort as u32,
+        item_type: 0,
+        name: String::new(),
+        name_cn: String::new(),
+        sort,
+        ep: Some(sort),
+        airdate: String::new(),
+        comment: 0,
+        duration: String::new(),
+        desc: String::new(),
+        disc: 0,
+        duration_seconds: None,
+        subject_id: None,
+    }
+}
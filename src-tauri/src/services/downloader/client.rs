@@ -3,11 +3,13 @@ use crate::error::AppError;
 use once_cell::sync::Lazy;
 use reqwest::header::{COOKIE, ORIGIN, REFERER};
 use reqwest::multipart;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use tokio::sync::RwLock;
+use tracing::{debug, warn};
 use ts_rs::TS;
 
 // Video file extensions for identifying playable files
@@ -19,6 +21,7 @@ pub static VIDEO_EXTENSIONS: &[&str] = &[
 struct SessionState {
     cookie: String,
     config_hash: u64,
+    api_version: Option<WebApiVersion>,
 }
 
 // Use RwLock for concurrent reads: multiple readers can access session simultaneously
@@ -28,6 +31,99 @@ pub struct QbitClient {
     base_url: String,
     cookie: Option<String>,
     config: DownloaderConfig,
+    api_version: Option<WebApiVersion>,
+}
+
+/// `app/webapiVersion` 返回的版本号，如 `2.8.19`（qBittorrent 4.6）或 `2.11.2`（5.0）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WebApiVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl WebApiVersion {
+    /// qBittorrent 5.0 起 WebAPI 为 2.11.0，`pause`/`resume` 被 `stop`/`start` 取代
+    pub const STOP_START: WebApiVersion = WebApiVersion {
+        major: 2,
+        minor: 11,
+        patch: 0,
+    };
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        let mut parts = raw.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().unwrap_or("0").parse().ok()?;
+        let patch = parts.next().unwrap_or("0").parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+/// 不同 WebAPI 版本在端点与响应格式上的差异
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiDialect {
+    /// qBittorrent 4.x：`torrents/pause`、`torrents/resume`，添加返回纯文本
+    V4,
+    /// qBittorrent 5.x：`torrents/stop`、`torrents/start`，添加可能返回 JSON 摘要
+    V5,
+}
+
+impl ApiDialect {
+    /// 未知版本按 5.x 处理，与探测失败前的行为保持一致
+    pub fn from_version(version: Option<WebApiVersion>) -> Self {
+        match version {
+            Some(v) if v < WebApiVersion::STOP_START => ApiDialect::V4,
+            _ => ApiDialect::V5,
+        }
+    }
+
+    pub fn pause_path(self) -> &'static str {
+        match self {
+            ApiDialect::V4 => "/api/v2/torrents/pause",
+            ApiDialect::V5 => "/api/v2/torrents/stop",
+        }
+    }
+
+    pub fn resume_path(self) -> &'static str {
+        match self {
+            ApiDialect::V4 => "/api/v2/torrents/resume",
+            ApiDialect::V5 => "/api/v2/torrents/start",
+        }
+    }
+
+    /// 解析 `torrents/add` 的响应体。4.x 只会返回 `Ok.`/`Fails.`；
+    /// 5.x 新版本返回 `{"success_count", "failure_count", "pending_count"}`。
+    pub fn check_add_response(self, body: &str) -> Result<(), AppError> {
+        let body = body.trim();
+        if body == "Ok." {
+            return Ok(());
+        }
+        if self == ApiDialect::V5 {
+            if let Ok(summary) = serde_json::from_str::<AddSummary>(body) {
+                if summary.failure_count == 0 || summary.success_count + summary.pending_count > 0 {
+                    return Ok(());
+                }
+            }
+        }
+        Err(AppError::DownloaderRejected(body.to_string()))
+    }
+}
+
+#[derive(Deserialize)]
+struct AddSummary {
+    #[serde(default)]
+    success_count: u32,
+    #[serde(default)]
+    failure_count: u32,
+    #[serde(default)]
+    pending_count: u32,
 }
 
 #[derive(Deserialize, Debug, Clone, Serialize, TS)]
@@ -40,6 +136,17 @@ pub struct TorrentInfo {
     pub dlspeed: i64, // 字节/秒
     pub eta: i64,     // 秒
     pub save_path: String,
+    #[serde(default)]
+    pub size: i64, // 字节
+    #[serde(default)]
+    pub added_on: i64, // Unix 时间戳
+    #[serde(default)]
+    pub completion_on: i64, // 未完成时为 -1 或 0
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TorrentTracker {
+    pub url: String,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -86,16 +193,31 @@ impl QbitClient {
             base_url: config.api_url.clone().trim_end_matches('/').to_string(),
             cookie: None,
             config,
+            api_version: None,
         }
     }
 
-    pub async fn get_app_version(&self) -> Result<String, AppError> {
+    fn dialect(&self) -> ApiDialect {
+        ApiDialect::from_version(self.api_version)
+    }
+
+    async fn fetch_webapi_version(&self) -> Result<WebApiVersion, AppError> {
         let resp = self
-            .request(reqwest::Method::GET, "/api/v2/app/version")
+            .request(reqwest::Method::GET, "/api/v2/app/webapiVersion")
             .send()
             .await?;
         resp.error_for_status_ref()?;
         let txt = resp.text().await?;
+        WebApiVersion::parse(&txt)
+            .ok_or_else(|| AppError::Any(format!("无法解析 WebAPI 版本: {}", txt.trim())))
+    }
+
+    pub async fn get_app_version(&mut self) -> Result<String, AppError> {
+        let resp = self
+            .send(reqwest::Method::GET, "/api/v2/app/version", Ok)
+            .await?;
+        resp.error_for_status_ref()?;
+        let txt = resp.text().await?;
         Ok(txt)
     }
 
@@ -108,6 +230,7 @@ impl QbitClient {
             if let Some(s) = &*session {
                 if s.config_hash == current_hash {
                     self.cookie = Some(s.cookie.clone());
+                    self.api_version = s.api_version;
                     return Ok(());
                 }
             }
@@ -118,6 +241,7 @@ impl QbitClient {
         if let Some(s) = &*session {
             if s.config_hash == current_hash {
                 self.cookie = Some(s.cookie.clone());
+                self.api_version = s.api_version;
                 return Ok(());
             }
         }
@@ -137,6 +261,15 @@ impl QbitClient {
             .send()
             .await?;
 
+        if resp.status() == StatusCode::FORBIDDEN {
+            let body = resp.text().await.unwrap_or_default();
+            warn!(body = %body.trim(), "qBittorrent 拒绝登录");
+            // "Your IP address has been banned for too many failed login attempts"
+            if body.to_ascii_lowercase().contains("banned") {
+                return Err(AppError::DownloaderBanned);
+            }
+            return Err(AppError::DownloaderRejected(body));
+        }
         resp.error_for_status_ref()?;
 
         let mut cookie_val = String::new();
@@ -147,17 +280,38 @@ impl QbitClient {
             }
         }
 
-        // 4. Update global session
+        // 4. Detect WebAPI version; failure falls back to the 5.x dialect
+        self.api_version = match self.fetch_webapi_version().await {
+            Ok(v) => Some(v),
+            Err(e) => {
+                warn!(error = %e, "qBittorrent WebAPI 版本探测失败");
+                None
+            }
+        };
+
+        // 5. Update global session
         if !cookie_val.is_empty() {
             *session = Some(SessionState {
                 cookie: cookie_val,
                 config_hash: current_hash,
+                api_version: self.api_version,
             });
         }
 
         Ok(())
     }
 
+    /// 会话失效（qBittorrent 重启或超时）时清除全局缓存。
+    /// 只有缓存仍是本客户端持有的旧 cookie 时才清除，避免覆盖其他请求刚完成的登录。
+    async fn invalidate_session(&mut self) {
+        if let Some(stale) = self.cookie.take() {
+            let mut session = SESSION.write().await;
+            if session.as_ref().is_some_and(|s| s.cookie == stale) {
+                *session = None;
+            }
+        }
+    }
+
     fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
         let url = format!("{}{}", self.base_url, path);
         let mut builder = crate::infra::http::CLIENT.request(method, &url);
@@ -170,52 +324,67 @@ impl QbitClient {
         builder
     }
 
-    pub async fn add_torrent(&self, torrent_data: Vec<u8>) -> Result<(), AppError> {
-        let part = multipart::Part::bytes(torrent_data)
-            .file_name("torrent")
-            .mime_str("application/x-bittorrent")?;
+    /// 发送请求；遇到 401/403 时重新登录一次并重放请求。
+    /// `build` 会被调用两次，因此请求体需要能重复构造。
+    async fn send<F>(
+        &mut self,
+        method: reqwest::Method,
+        path: &str,
+        build: F,
+    ) -> Result<reqwest::Response, AppError>
+    where
+        F: Fn(reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder, AppError>,
+    {
+        let resp = build(self.request(method.clone(), path))?.send().await?;
+        if !matches!(
+            resp.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Ok(resp);
+        }
 
-        let form = multipart::Form::new().part("torrents", part);
+        debug!(path, status = %resp.status(), "qBittorrent 会话失效，重新登录");
+        self.invalidate_session().await;
+        self.login().await?;
+        let resp = build(self.request(method, path))?.send().await?;
+        Ok(resp)
+    }
 
+    pub async fn add_torrent(&mut self, torrent_data: Vec<u8>) -> Result<(), AppError> {
         let resp = self
-            .request(reqwest::Method::POST, "/api/v2/torrents/add")
-            .multipart(form)
-            .send()
+            .send(reqwest::Method::POST, "/api/v2/torrents/add", |b| {
+                let part = multipart::Part::bytes(torrent_data.clone())
+                    .file_name("torrent")
+                    .mime_str("application/x-bittorrent")?;
+                Ok(b.multipart(multipart::Form::new().part("torrents", part)))
+            })
             .await?;
 
         resp.error_for_status_ref()?;
         let txt = resp.text().await?;
-        if txt.trim() == "Ok." {
-            Ok(())
-        } else {
-            Err(AppError::DownloaderRejected(txt))
-        }
+        self.dialect().check_add_response(&txt)
     }
 
-    pub async fn add_url(&self, url: &str) -> Result<(), AppError> {
+    pub async fn add_url(&mut self, url: &str) -> Result<(), AppError> {
         let resp = self
-            .request(reqwest::Method::POST, "/api/v2/torrents/add")
-            .form(&[("urls", url.to_string())])
-            .send()
+            .send(reqwest::Method::POST, "/api/v2/torrents/add", |b| {
+                Ok(b.form(&[("urls", url)]))
+            })
             .await?;
         resp.error_for_status_ref()?;
         let txt = resp.text().await?;
-        if txt.trim() == "Ok." {
-            Ok(())
-        } else {
-            Err(AppError::DownloaderRejected(txt))
-        }
+        self.dialect().check_add_response(&txt)
     }
 
     pub async fn get_torrents_info(
-        &self,
+        &mut self,
         hashes: Vec<String>,
     ) -> Result<Vec<TorrentInfo>, AppError> {
         let hashes_str = hashes.join("|");
         let resp = self
-            .request(reqwest::Method::GET, "/api/v2/torrents/info")
-            .query(&[("hashes", hashes_str)])
-            .send()
+            .send(reqwest::Method::GET, "/api/v2/torrents/info", |b| {
+                Ok(b.query(&[("hashes", &hashes_str)]))
+            })
             .await?;
         resp.error_for_status_ref()?;
         let infos: Vec<TorrentInfo> = resp.json().await?;
@@ -223,50 +392,63 @@ impl QbitClient {
     }
 
     pub async fn pause(&mut self, hash: &str) -> Result<(), AppError> {
+        let path = self.dialect().pause_path();
+        self.post_hashes(path, hash).await
+    }
+
+    pub async fn resume(&mut self, hash: &str) -> Result<(), AppError> {
+        let path = self.dialect().resume_path();
+        self.post_hashes(path, hash).await
+    }
+
+    pub async fn recheck(&mut self, hash: &str) -> Result<(), AppError> {
+        self.post_hashes("/api/v2/torrents/recheck", hash).await
+    }
+
+    pub async fn reannounce(&mut self, hash: &str) -> Result<(), AppError> {
+        self.post_hashes("/api/v2/torrents/reannounce", hash).await
+    }
+
+    async fn post_hashes(&mut self, path: &str, hash: &str) -> Result<(), AppError> {
         let resp = self
-            .request(reqwest::Method::POST, "/api/v2/torrents/stop")
-            .form(&[("hashes", hash)])
-            .send()
+            .send(reqwest::Method::POST, path, |b| {
+                Ok(b.form(&[("hashes", hash)]))
+            })
             .await?;
         resp.error_for_status_ref()?;
         Ok(())
     }
 
-    pub async fn resume(&mut self, hash: &str) -> Result<(), AppError> {
+    pub async fn delete(&mut self, hash: &str, delete_files: bool) -> Result<(), AppError> {
+        let delete_files = if delete_files { "true" } else { "false" };
         let resp = self
-            .request(reqwest::Method::POST, "/api/v2/torrents/start")
-            .form(&[("hashes", hash)])
-            .send()
+            .send(reqwest::Method::POST, "/api/v2/torrents/delete", |b| {
+                Ok(b.form(&[("hashes", hash), ("deleteFiles", delete_files)]))
+            })
             .await?;
         resp.error_for_status_ref()?;
         Ok(())
     }
 
-    pub async fn delete(&self, hash: &str, delete_files: bool) -> Result<(), AppError> {
+    pub async fn get_torrent_trackers(
+        &mut self,
+        hash: &str,
+    ) -> Result<Vec<TorrentTracker>, AppError> {
         let resp = self
-            .request(reqwest::Method::POST, "/api/v2/torrents/delete")
-            .form(&[
-                ("hashes", hash.to_string()),
-                (
-                    "deleteFiles",
-                    if delete_files {
-                        String::from("true")
-                    } else {
-                        String::from("false")
-                    },
-                ),
-            ])
-            .send()
+            .send(reqwest::Method::GET, "/api/v2/torrents/trackers", |b| {
+                Ok(b.query(&[("hash", hash)]))
+            })
             .await?;
         resp.error_for_status_ref()?;
-        Ok(())
+        let trackers: Vec<TorrentTracker> = resp.json().await?;
+        Ok(trackers)
     }
 
-    pub async fn get_torrent_files(&self, hash: &str) -> Result<Vec<TorrentFile>, AppError> {
+    pub async fn get_torrent_files(&mut self, hash: &str) -> Result<Vec<TorrentFile>, AppError> {
         let resp = self
-            .request(reqwest::Method::GET, "/api/v2/torrents/files")
-            .query(&[("hash", hash)])
-            .send()
+            .send(reqwest::Method::GET, "/api/v2/torrents/files", |b| {
+                Ok(b.query(&[("hash", hash)]))
+            })
             .await?;
         resp.error_for_status_ref()?;
         let files: Vec<TorrentFile> = resp.json().await?;
@@ -321,49 +503,275 @@ fn base32_decode(s: &str) -> Option<Vec<u8>> {
 mod tests {
     use super::*;
     use std::io::{Read, Write};
-    use std::net::TcpListener;
-    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-    use std::sync::Arc;
+    use std::net::{SocketAddr, TcpListener};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
     use std::time::Duration;
 
-    #[tokio::test]
-    async fn concurrent_logins_share_one_request() {
-        *SESSION.write().await = None;
+    // Tests touching the global SESSION must not interleave
+    static SESSION_TEST_LOCK: Lazy<tokio::sync::Mutex<()>> =
+        Lazy::new(|| tokio::sync::Mutex::new(()));
 
-        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
-        listener.set_nonblocking(true).unwrap();
-        let address = listener.local_addr().unwrap();
-        let requests = Arc::new(AtomicUsize::new(0));
-        let stop = Arc::new(AtomicBool::new(false));
-        let server_requests = Arc::clone(&requests);
-        let server_stop = Arc::clone(&stop);
-        let server = std::thread::spawn(move || {
-            while !server_stop.load(Ordering::Relaxed) {
-                match listener.accept() {
-                    Ok((mut stream, _)) => {
-                        server_requests.fetch_add(1, Ordering::Relaxed);
-                        let mut buffer = [0; 1024];
-                        let _ = stream.read(&mut buffer);
-                        std::thread::sleep(Duration::from_millis(100));
-                        stream
-                            .write_all(
-                                b"HTTP/1.1 200 OK\r\nSet-Cookie: SID=test\r\nContent-Length: 3\r\nConnection: close\r\n\r\nOk.",
-                            )
-                            .unwrap();
-                    }
-                    Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
-                        std::thread::sleep(Duration::from_millis(5));
+    struct TestServer {
+        address: SocketAddr,
+        paths: Arc<Mutex<Vec<String>>>,
+        stop: Arc<AtomicBool>,
+        handle: Option<std::thread::JoinHandle<()>>,
+    }
+
+    struct TestResponse {
+        status: &'static str,
+        headers: String,
+        body: String,
+    }
+
+    impl TestResponse {
+        fn ok(body: &str) -> Self {
+            Self {
+                status: "200 OK",
+                headers: String::new(),
+                body: body.to_string(),
+            }
+        }
+
+        fn forbidden(body: &str) -> Self {
+            Self {
+                status: "403 Forbidden",
+                headers: String::new(),
+                body: body.to_string(),
+            }
+        }
+    }
+
+    fn ok_with_cookie(cookie: &str) -> TestResponse {
+        TestResponse {
+            headers: format!("Set-Cookie: {cookie}; HttpOnly\r\n"),
+            ..TestResponse::ok("Ok.")
+        }
+    }
+
+    impl TestServer {
+        fn spawn(webapi_version: &'static str, delay: Duration) -> Self {
+            Self::spawn_with(delay, move |path, _| match path {
+                "/api/v2/auth/login" => ok_with_cookie("SID=test"),
+                "/api/v2/app/webapiVersion" => TestResponse::ok(webapi_version),
+                _ => TestResponse::ok(""),
+            })
+        }
+
+        /// `handler` receives the request path and raw request head
+        fn spawn_with<F>(delay: Duration, handler: F) -> Self
+        where
+            F: Fn(&str, &str) -> TestResponse + Send + 'static,
+        {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.set_nonblocking(true).unwrap();
+            let address = listener.local_addr().unwrap();
+            let paths = Arc::new(Mutex::new(Vec::new()));
+            let stop = Arc::new(AtomicBool::new(false));
+            let server_paths = Arc::clone(&paths);
+            let server_stop = Arc::clone(&stop);
+            let handle = std::thread::spawn(move || {
+                while !server_stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((mut stream, _)) => {
+                            stream.set_nonblocking(false).unwrap();
+                            let mut buffer = [0; 4096];
+                            let n = stream.read(&mut buffer).unwrap_or(0);
+                            let head = String::from_utf8_lossy(&buffer[..n]);
+                            let path = head
+                                .split_whitespace()
+                                .nth(1)
+                                .unwrap_or("")
+                                .split('?')
+                                .next()
+                                .unwrap_or("")
+                                .to_string();
+                            server_paths.lock().unwrap().push(path.clone());
+                            std::thread::sleep(delay);
+                            let reply = handler(&path, &head);
+                            let response = format!(
+                                "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                                reply.status,
+                                reply.headers,
+                                reply.body.len(),
+                                reply.body
+                            );
+                            stream.write_all(response.as_bytes()).unwrap();
+                        }
+                        Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                            std::thread::sleep(Duration::from_millis(5));
+                        }
+                        Err(error) => panic!("test server failed: {error}"),
                     }
-                    Err(error) => panic!("test server failed: {error}"),
                 }
+            });
+            Self {
+                address,
+                paths,
+                stop,
+                handle: Some(handle),
             }
-        });
+        }
 
-        let config = DownloaderConfig {
-            api_url: format!("http://{address}"),
-            username: Some("user".to_string()),
-            password: Some("password".to_string()),
-        };
+        fn config(&self) -> DownloaderConfig {
+            DownloaderConfig {
+                api_url: format!("http://{}", self.address),
+                username: Some("user".to_string()),
+                password: Some("password".to_string()),
+            }
+        }
+
+        fn hits(&self, path: &str) -> usize {
+            self.paths
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|p| p.as_str() == path)
+                .count()
+        }
+
+        fn shutdown(&mut self) {
+            self.stop.store(true, Ordering::Relaxed);
+            if let Some(handle) = self.handle.take() {
+                handle.join().unwrap();
+            }
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            self.shutdown();
+        }
+    }
+
+    fn client_with_version(server: &TestServer, version: &str) -> QbitClient {
+        let mut qb = QbitClient::new(server.config());
+        qb.cookie = Some("SID=test".to_string());
+        qb.api_version = WebApiVersion::parse(version);
+        qb
+    }
+
+    #[test]
+    fn parses_webapi_versions() {
+        assert_eq!(
+            WebApiVersion::parse("2.11.2\n"),
+            Some(WebApiVersion {
+                major: 2,
+                minor: 11,
+                patch: 2
+            })
+        );
+        assert_eq!(
+            WebApiVersion::parse("2.8"),
+            Some(WebApiVersion {
+                major: 2,
+                minor: 8,
+                patch: 0
+            })
+        );
+        assert_eq!(WebApiVersion::parse("v2"), None);
+        assert_eq!(WebApiVersion::parse("2.8.1.1"), None);
+        assert!(WebApiVersion::parse("2.9.3") < WebApiVersion::parse("2.11.0"));
+    }
+
+    #[test]
+    fn selects_dialect_by_version() {
+        assert_eq!(
+            ApiDialect::from_version(WebApiVersion::parse("2.8.19")),
+            ApiDialect::V4
+        );
+        assert_eq!(
+            ApiDialect::from_version(WebApiVersion::parse("2.11.0")),
+            ApiDialect::V5
+        );
+        assert_eq!(ApiDialect::from_version(None), ApiDialect::V5);
+    }
+
+    #[test]
+    fn v4_add_response_accepts_only_ok_text() {
+        assert!(ApiDialect::V4.check_add_response("Ok.").is_ok());
+        assert!(matches!(
+            ApiDialect::V4.check_add_response("Fails."),
+            Err(AppError::DownloaderRejected(_))
+        ));
+        assert!(ApiDialect::V4
+            .check_add_response(r#"{"success_count":1,"failure_count":0}"#)
+            .is_err());
+    }
+
+    #[test]
+    fn v5_add_response_understands_json_summary() {
+        assert!(ApiDialect::V5.check_add_response("Ok.").is_ok());
+        assert!(ApiDialect::V5
+            .check_add_response(r#"{"success_count":1,"failure_count":0,"pending_count":0}"#)
+            .is_ok());
+        assert!(ApiDialect::V5
+            .check_add_response(r#"{"success_count":0,"failure_count":0,"pending_count":1}"#)
+            .is_ok());
+        assert!(matches!(
+            ApiDialect::V5
+                .check_add_response(r#"{"success_count":0,"failure_count":1,"pending_count":0}"#),
+            Err(AppError::DownloaderRejected(_))
+        ));
+        assert!(ApiDialect::V5.check_add_response("Fails.").is_err());
+    }
+
+    #[tokio::test]
+    async fn v4_client_uses_pause_and_resume() {
+        let server = TestServer::spawn("2.8.19", Duration::ZERO);
+        let mut qb = client_with_version(&server, "2.8.19");
+
+        qb.pause("abc").await.unwrap();
+        qb.resume("abc").await.unwrap();
+
+        assert_eq!(server.hits("/api/v2/torrents/pause"), 1);
+        assert_eq!(server.hits("/api/v2/torrents/resume"), 1);
+        assert_eq!(server.hits("/api/v2/torrents/stop"), 0);
+        assert_eq!(server.hits("/api/v2/torrents/start"), 0);
+    }
+
+    #[tokio::test]
+    async fn v5_client_uses_stop_and_start() {
+        let server = TestServer::spawn("2.11.2", Duration::ZERO);
+        let mut qb = client_with_version(&server, "2.11.2");
+
+        qb.pause("abc").await.unwrap();
+        qb.resume("abc").await.unwrap();
+
+        assert_eq!(server.hits("/api/v2/torrents/stop"), 1);
+        assert_eq!(server.hits("/api/v2/torrents/start"), 1);
+        assert_eq!(server.hits("/api/v2/torrents/pause"), 0);
+        assert_eq!(server.hits("/api/v2/torrents/resume"), 0);
+    }
+
+    #[tokio::test]
+    async fn login_detects_and_caches_webapi_version() {
+        let _guard = SESSION_TEST_LOCK.lock().await;
+        *SESSION.write().await = None;
+
+        let mut server = TestServer::spawn("2.8.19", Duration::ZERO);
+        let mut first = QbitClient::new(server.config());
+        first.login().await.unwrap();
+        let mut second = QbitClient::new(server.config());
+        second.login().await.unwrap();
+
+        server.shutdown();
+        *SESSION.write().await = None;
+
+        assert_eq!(first.dialect(), ApiDialect::V4);
+        assert_eq!(second.api_version, first.api_version);
+        assert_eq!(server.hits("/api/v2/auth/login"), 1);
+        assert_eq!(server.hits("/api/v2/app/webapiVersion"), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_logins_share_one_request() {
+        let _guard = SESSION_TEST_LOCK.lock().await;
+        *SESSION.write().await = None;
+
+        let mut server = TestServer::spawn("2.11.2", Duration::from_millis(100));
+        let config = server.config();
         let mut first = QbitClient::new(config.clone());
         let mut second = QbitClient::new(config);
         let barrier = Arc::new(tokio::sync::Barrier::new(3));
@@ -381,12 +789,86 @@ mod tests {
         };
         let (_, first_result, second_result) = tokio::join!(release, first_login, second_login);
 
-        stop.store(true, Ordering::Relaxed);
-        server.join().unwrap();
+        server.shutdown();
         *SESSION.write().await = None;
 
         first_result.unwrap();
         second_result.unwrap();
-        assert_eq!(requests.load(Ordering::Relaxed), 1);
+        assert_eq!(server.hits("/api/v2/auth/login"), 1);
+    }
+
+    fn seed_session(server: &TestServer, cookie: &str) {
+        *SESSION.try_write().unwrap() = Some(SessionState {
+            cookie: cookie.to_string(),
+            config_hash: calculate_config_hash(&server.config()),
+            api_version: WebApiVersion::parse("2.11.2"),
+        });
+    }
+
+    #[tokio::test]
+    async fn expired_session_relogs_in_and_replays_request() {
+        let _guard = SESSION_TEST_LOCK.lock().await;
+
+        let mut server = TestServer::spawn_with(Duration::ZERO, |path, head| match path {
+            "/api/v2/auth/login" => ok_with_cookie("SID=fresh"),
+            "/api/v2/app/webapiVersion" => TestResponse::ok("2.11.2"),
+            _ if head.contains("SID=fresh") => TestResponse::ok("[]"),
+            _ => TestResponse::forbidden("Forbidden"),
+        });
+        seed_session(&server, "SID=stale");
+        let mut qb = QbitClient::new(server.config());
+        qb.login().await.unwrap();
+
+        let infos = qb.get_torrents_info(vec!["abc".to_string()]).await;
+
+        server.shutdown();
+        let cached = SESSION.write().await.take().map(|s| s.cookie);
+
+        assert!(infos.unwrap().is_empty());
+        assert_eq!(server.hits("/api/v2/torrents/info"), 2);
+        assert_eq!(server.hits("/api/v2/auth/login"), 1);
+        assert_eq!(cached.as_deref(), Some("SID=fresh"));
+    }
+
+    #[tokio::test]
+    async fn relogin_is_attempted_only_once() {
+        let _guard = SESSION_TEST_LOCK.lock().await;
+
+        let mut server = TestServer::spawn_with(Duration::ZERO, |path, _| match path {
+            "/api/v2/auth/login" => ok_with_cookie("SID=fresh"),
+            "/api/v2/app/webapiVersion" => TestResponse::ok("2.11.2"),
+            _ => TestResponse::forbidden("Forbidden"),
+        });
+        seed_session(&server, "SID=stale");
+        let mut qb = QbitClient::new(server.config());
+        qb.login().await.unwrap();
+
+        let result = qb.recheck("abc").await;
+
+        server.shutdown();
+        *SESSION.write().await = None;
+
+        assert!(matches!(result, Err(AppError::Reqwest(_))));
+        assert_eq!(server.hits("/api/v2/torrents/recheck"), 2);
+        assert_eq!(server.hits("/api/v2/auth/login"), 1);
+    }
+
+    #[tokio::test]
+    async fn banned_login_is_reported() {
+        let _guard = SESSION_TEST_LOCK.lock().await;
+        *SESSION.write().await = None;
+
+        let mut server = TestServer::spawn_with(Duration::ZERO, |_, _| {
+            TestResponse::forbidden(
+                "Your IP address has been banned for too many failed login attempts.",
+            )
+        });
+        let mut qb = QbitClient::new(server.config());
+
+        let result = qb.login().await;
+
+        server.shutdown();
+        assert!(matches!(result, Err(AppError::DownloaderBanned)));
+        assert!(SESSION.read().await.is_none());
     }
 }
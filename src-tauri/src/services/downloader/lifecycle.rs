@@ -1,12 +1,19 @@
-use super::{build_metadata, client, config, projection, repo, DownloadItem};
+use super::{
+    build_metadata, client, config, magnet, parse_metadata, projection, repo, stats, DownloadItem,
+};
 use crate::error::AppError;
-use crate::infra::http::{wait_api_limit, CLIENT};
+use crate::infra::http::wait_api_limit;
 use futures::StreamExt;
+use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 const MAX_TORRENT_SIZE: usize = 20 * 1024 * 1024;
 
+/// 本次运行中已记录完成快照的下载，避免每次轮询重复写入
+static COMPLETION_RECORDED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
 pub struct DownloadStatusSnapshot {
     pub items: Vec<DownloadItem>,
     pub connected: bool,
@@ -47,7 +54,7 @@ async fn authenticated_client() -> Result<client::QbitClient, AppError> {
 }
 
 pub async fn test_connection() -> Result<String, AppError> {
-    let qb = authenticated_client().await?;
+    let mut qb = authenticated_client().await?;
     qb.get_app_version().await
 }
 
@@ -58,7 +65,8 @@ async fn torrent_payload(url: &str) -> Result<(String, TorrentPayload), AppError
     }
 
     wait_api_limit().await;
-    let resp = CLIENT.get(url).send().await?;
+    // Mikan 种子链接走镜像选择，失败时自动切换
+    let resp = crate::services::mikan::host::get_url(url).await?;
     resp.error_for_status_ref()?;
 
     if let Some(len) = resp.content_length() {
@@ -94,7 +102,14 @@ pub async fn add_torrent_and_track(
     meta_json: Option<String>,
 ) -> Result<(), AppError> {
     let (hash, payload) = torrent_payload(&url).await?;
-    let qb = authenticated_client().await?;
+    let source = match &payload {
+        TorrentPayload::File(data) => magnet::source_from_torrent(data).unwrap_or_else(|e| {
+            tracing::warn!("读取种子 tracker 失败 hash={}, error={}", hash, e);
+            magnet::TorrentSource::default()
+        }),
+        TorrentPayload::Magnet => magnet::source_from_magnet(&url),
+    };
+    let mut qb = authenticated_client().await?;
 
     let existing = qb.get_torrents_info(vec![hash.clone()]).await?;
     if !existing.is_empty() {
@@ -125,9 +140,119 @@ pub async fn add_torrent_and_track(
         return Err(e);
     }
 
+    if let Err(e) = repo::set_source(&hash, &source).await {
+        tracing::warn!("保存种子来源信息失败 hash={}, error={}", hash, e);
+    }
+
     Ok(())
 }
 
+/// 以资源标题和条目封面作为元数据添加下载；封面获取失败时留空
+pub async fn add_for_subject(
+    url: String,
+    subject_id: u32,
+    episode: Option<u32>,
+    episode_range: Option<String>,
+    title: String,
+) -> Result<(), AppError> {
+    let cover = crate::services::bangumi::fetch_subject(subject_id)
+        .await
+        .map(|s| s.images.large)
+        .unwrap_or_default();
+    add_torrent_and_track(
+        url,
+        subject_id,
+        episode,
+        episode_range,
+        Some(build_metadata(title, cover)),
+    )
+    .await
+}
+
+/// 为单个跟踪下载生成磁力链接
+pub async fn magnet_link(hash: &str) -> Result<String, AppError> {
+    let tracked = repo::get(hash).await?.ok_or(AppError::DownloadNotFound)?;
+    let mut magnets = build_magnets(&[tracked]).await?;
+    Ok(magnets.remove(0))
+}
+
+/// 导出某个条目下所有跟踪下载的磁力链接，按集数排序
+pub async fn subject_magnet_links(subject_id: u32) -> Result<Vec<String>, AppError> {
+    let mut tracked: Vec<repo::TrackedDownload> = repo::list()
+        .await?
+        .into_iter()
+        .filter(|t| t.subject_id == subject_id)
+        .collect();
+    tracked.sort_by_key(|t| (t.episode.unwrap_or(u32::MAX), t.created_at));
+    build_magnets(&tracked).await
+}
+
+/// 优先使用添加时保存的显示名与 tracker；缺失时向 qBittorrent 查询，
+/// 仍然没有显示名则回退到资源标题。下载器不可用时只生成基础磁力链接。
+async fn build_magnets(tracked: &[repo::TrackedDownload]) -> Result<Vec<String>, AppError> {
+    let mut sources = Vec::with_capacity(tracked.len());
+    for t in tracked {
+        sources.push(repo::get_source(&t.hash).await?.unwrap_or_default());
+    }
+
+    let incomplete: Vec<String> = tracked
+        .iter()
+        .zip(&sources)
+        .filter(|(_, s)| s.display_name.is_none() || s.trackers.is_empty())
+        .map(|(t, _)| t.hash.clone())
+        .collect();
+
+    let mut qb = None;
+    let mut live_names: HashMap<String, String> = HashMap::new();
+    if !incomplete.is_empty() {
+        match authenticated_client().await {
+            Ok(mut client) => {
+                match client.get_torrents_info(incomplete).await {
+                    Ok(infos) => {
+                        live_names = infos.into_iter().map(|i| (i.hash, i.name)).collect();
+                    }
+                    Err(e) => tracing::warn!("获取种子名称失败: {}", e),
+                }
+                qb = Some(client);
+            }
+            Err(e) => tracing::warn!("Qbit login failed: {}", e),
+        }
+    }
+
+    let mut magnets = Vec::with_capacity(tracked.len());
+    for (t, mut source) in tracked.iter().zip(sources) {
+        if source.trackers.is_empty() {
+            if let Some(client) = qb.as_mut() {
+                match client.get_torrent_trackers(&t.hash).await {
+                    Ok(list) => {
+                        source.trackers = list
+                            .into_iter()
+                            .map(|tr| tr.url)
+                            .filter(|url| magnet::is_real_tracker(url))
+                            .collect();
+                    }
+                    Err(e) => tracing::warn!("获取 tracker 失败 hash={}, error={}", t.hash, e),
+                }
+            }
+        }
+        let display_name = source
+            .display_name
+            .or_else(|| live_names.get(&t.hash).cloned())
+            .or_else(|| {
+                t.meta_json
+                    .as_deref()
+                    .and_then(parse_metadata)
+                    .map(|(title, _)| title)
+            });
+        magnets.push(magnet::build_magnet(
+            &t.hash,
+            display_name.as_deref(),
+            &source.trackers,
+        ));
+    }
+    Ok(magnets)
+}
+
 async fn batch_ensure_metadata(
     tracked: &[repo::TrackedDownload],
 ) -> Vec<projection::DownloadDisplayMetadata> {
@@ -223,7 +348,7 @@ pub async fn status_snapshot() -> Result<DownloadStatusSnapshot, AppError> {
 
     if tracked.is_empty() {
         let connected = match authenticated_client().await {
-            Ok(qb) => qb.get_app_version().await.is_ok(),
+            Ok(mut qb) => qb.get_app_version().await.is_ok(),
             Err(_) => false,
         };
         return Ok(DownloadStatusSnapshot {
@@ -234,7 +359,7 @@ pub async fn status_snapshot() -> Result<DownloadStatusSnapshot, AppError> {
 
     let metadata_list = batch_ensure_metadata(&tracked).await;
     let live_infos = match authenticated_client().await {
-        Ok(qb) => {
+        Ok(mut qb) => {
             let hashes: Vec<String> = tracked.iter().map(|t| t.hash.clone()).collect();
             match qb.get_torrents_info(hashes).await {
                 Ok(infos) => Some(infos),
@@ -249,6 +374,9 @@ pub async fn status_snapshot() -> Result<DownloadStatusSnapshot, AppError> {
             None
         }
     };
+    if let Some(infos) = &live_infos {
+        record_completions(&tracked, infos).await;
+    }
     let connected = live_infos.is_some();
     let items = projection::build_status_projection(tracked, metadata_list, live_infos);
 
@@ -259,8 +387,100 @@ pub async fn list_status_projection() -> Result<Vec<DownloadItem>, AppError> {
     Ok(status_snapshot().await?.items)
 }
 
+/// 刚完成的下载立即写入快照，统计与集数状态不依赖打开统计页
+async fn record_completions(tracked: &[repo::TrackedDownload], infos: &[client::TorrentInfo]) {
+    let snapshots = {
+        let recorded = COMPLETION_RECORDED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        stats::completed_snapshots(tracked, infos, &recorded)
+    };
+    if snapshots.is_empty() {
+        return;
+    }
+    let hashes: Vec<String> = snapshots.iter().map(|s| s.hash.clone()).collect();
+    match stats::save_snapshots(snapshots).await {
+        Ok(()) => COMPLETION_RECORDED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(hashes),
+        Err(e) => tracing::warn!("保存下载完成快照失败: {}", e),
+    }
+}
+
+/// 按下载器的实时进度补记条目下已完成的下载；下载器不可用时保持已记录的状态
+pub async fn refresh_completions(subject_id: u32) -> Result<(), AppError> {
+    let tracked: Vec<repo::TrackedDownload> = repo::list()
+        .await?
+        .into_iter()
+        .filter(|t| t.subject_id == subject_id)
+        .collect();
+    let live: Vec<client::TorrentInfo> = live_torrents(&tracked).await.into_values().collect();
+    record_completions(&tracked, &live).await;
+    Ok(())
+}
+
+/// 下载器中跟踪下载的实时信息；下载器不可用时为空
+async fn live_torrents(tracked: &[repo::TrackedDownload]) -> HashMap<String, client::TorrentInfo> {
+    if tracked.is_empty() {
+        return HashMap::new();
+    }
+    let hashes: Vec<String> = tracked.iter().map(|t| t.hash.clone()).collect();
+    match authenticated_client().await {
+        Ok(mut qb) => match qb.get_torrents_info(hashes).await {
+            Ok(infos) => infos.into_iter().map(|i| (i.hash.clone(), i)).collect(),
+            Err(e) => {
+                tracing::warn!("Failed to get torrent info: {}", e);
+                HashMap::new()
+            }
+        },
+        Err(e) => {
+            tracing::warn!("Qbit login failed: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// 用当前跟踪记录刷新快照后汇总统计；已删除的种子保留上次快照
+pub async fn usage_stats() -> Result<stats::DownloadStats, AppError> {
+    let tracked = repo::list().await?;
+    let live = live_torrents(&tracked).await;
+
+    let snapshots = tracked
+        .iter()
+        .map(|t| stats::snapshot_of(t, live.get(&t.hash)))
+        .collect();
+    stats::save_snapshots(snapshots).await?;
+
+    let snapshots = stats::list_snapshots().await?;
+    let mut subject_ids: Vec<u32> = snapshots.iter().map(|s| s.subject_id).collect();
+    subject_ids.sort_unstable();
+    subject_ids.dedup();
+    let titles = crate::services::subscriptions::batch_get_metadata(&subject_ids)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(id, m)| {
+            (
+                id,
+                if m.name_cn.is_empty() {
+                    m.name
+                } else {
+                    m.name_cn
+                },
+            )
+        })
+        .collect();
+
+    Ok(stats::aggregate(
+        &snapshots,
+        &titles,
+        stats::season_start(crate::infra::time::now_secs()),
+    ))
+}
+
 pub async fn live_download_info() -> Result<Vec<client::TorrentInfo>, AppError> {
-    let qb = authenticated_client().await?;
+    let mut qb = authenticated_client().await?;
     let tracked = repo::list().await?;
     if tracked.is_empty() {
         return Ok(vec![]);
@@ -279,14 +499,39 @@ pub async fn resume(hash: &str) -> Result<(), AppError> {
     qb.resume(hash).await
 }
 
+pub async fn recheck(hash: &str) -> Result<(), AppError> {
+    let mut qb = authenticated_client().await?;
+    qb.recheck(hash).await
+}
+
+pub async fn reannounce(hash: &str) -> Result<(), AppError> {
+    let mut qb = authenticated_client().await?;
+    qb.reannounce(hash).await
+}
+
+/// 删除前保存最后一次快照，统计中保留已删除的种子
 pub async fn delete(hash: String, delete_files: bool) -> Result<(), AppError> {
-    let qb = authenticated_client().await?;
+    let mut qb = authenticated_client().await?;
+    if let Some(tracked) = repo::get(&hash).await? {
+        let live = match qb.get_torrents_info(vec![hash.clone()]).await {
+            Ok(infos) => infos.into_iter().next(),
+            Err(e) => {
+                tracing::warn!("Failed to get torrent info: {}", e);
+                None
+            }
+        };
+        // 统计只是附带记录，写入失败不阻止删除
+        let snapshot = stats::snapshot_of(&tracked, live.as_ref());
+        if let Err(e) = stats::save_snapshots(vec![snapshot]).await {
+            tracing::warn!("保存删除前的下载快照失败 hash={}, error={}", hash, e);
+        }
+    }
     qb.delete(&hash, delete_files).await?;
     repo::delete(hash).await
 }
 
 pub async fn playable_file_path(hash: &str) -> Result<PathBuf, AppError> {
-    let qb = authenticated_client().await?;
+    let mut qb = authenticated_client().await?;
     let infos = qb.get_torrents_info(vec![hash.to_string()]).await?;
     let save_path = infos
         .first()
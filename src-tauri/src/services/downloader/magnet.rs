@@ -0,0 +1,167 @@
+use crate::error::AppError;
+use serde::Deserialize;
+
+/// 生成磁力链接所需的种子信息：显示名与 tracker 列表
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TorrentSource {
+    pub display_name: Option<String>,
+    pub trackers: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TorrentFileMeta {
+    #[serde(default)]
+    announce: Option<String>,
+    #[serde(default, rename = "announce-list")]
+    announce_list: Option<Vec<Vec<String>>>,
+    info: TorrentInfoName,
+}
+
+#[derive(Deserialize)]
+struct TorrentInfoName {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// 从 .torrent 文件中读取 `info.name` 与 `announce`/`announce-list`
+pub fn source_from_torrent(bytes: &[u8]) -> Result<TorrentSource, AppError> {
+    let meta: TorrentFileMeta = serde_bencode::from_bytes(bytes)?;
+    let mut trackers = Vec::new();
+    for tier in meta.announce_list.unwrap_or_default() {
+        trackers.extend(tier);
+    }
+    trackers.extend(meta.announce);
+    Ok(TorrentSource {
+        display_name: meta.info.name.filter(|n| !n.trim().is_empty()),
+        trackers: dedup_trackers(trackers),
+    })
+}
+
+/// 从磁力链接中读取 `dn` 与 `tr` 参数
+pub fn source_from_magnet(magnet: &str) -> TorrentSource {
+    let Ok(url) = reqwest::Url::parse(magnet) else {
+        return TorrentSource::default();
+    };
+    let mut source = TorrentSource::default();
+    let mut trackers = Vec::new();
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "dn" if source.display_name.is_none() && !value.trim().is_empty() => {
+                source.display_name = Some(value.into_owned());
+            }
+            "tr" => trackers.push(value.into_owned()),
+            _ => {}
+        }
+    }
+    source.trackers = dedup_trackers(trackers);
+    source
+}
+
+/// qBittorrent 的 tracker 列表包含 `** [DHT] **` 等伪条目，需要过滤
+pub fn is_real_tracker(url: &str) -> bool {
+    let url = url.trim();
+    !url.is_empty() && !url.starts_with("**")
+}
+
+fn dedup_trackers(trackers: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for t in trackers {
+        let t = t.trim().to_string();
+        if is_real_tracker(&t) && !out.contains(&t) {
+            out.push(t);
+        }
+    }
+    out
+}
+
+/// 构建 `magnet:?xt=urn:btih:<hash>&dn=<name>&tr=<tracker>...`
+pub fn build_magnet(hash: &str, display_name: Option<&str>, trackers: &[String]) -> String {
+    let mut uri = format!("magnet:?xt=urn:btih:{}", hash.to_lowercase());
+    if let Some(name) = display_name.filter(|n| !n.trim().is_empty()) {
+        uri.push_str("&dn=");
+        uri.push_str(&percent_encode(name));
+    }
+    for tracker in trackers {
+        uri.push_str("&tr=");
+        uri.push_str(&percent_encode(tracker));
+    }
+    uri
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+
+    #[test]
+    fn builds_magnet_with_encoded_name_and_trackers() {
+        let magnet = build_magnet(
+            &HASH.to_uppercase(),
+            Some("[Sub] 葬送的芙莉莲 - 01 [1080p].mkv"),
+            &["http://t.example.com:8080/announce".to_string()],
+        );
+        assert!(magnet.starts_with(&format!("magnet:?xt=urn:btih:{HASH}&dn=")));
+        assert!(magnet.ends_with("&tr=http%3A%2F%2Ft.example.com%3A8080%2Fannounce"));
+
+        let parsed = source_from_magnet(&magnet);
+        assert_eq!(
+            parsed.display_name.as_deref(),
+            Some("[Sub] 葬送的芙莉莲 - 01 [1080p].mkv")
+        );
+        assert_eq!(parsed.trackers, vec!["http://t.example.com:8080/announce"]);
+    }
+
+    #[test]
+    fn omits_empty_display_name() {
+        assert_eq!(
+            build_magnet(HASH, Some("  "), &[]),
+            format!("magnet:?xt=urn:btih:{HASH}")
+        );
+    }
+
+    #[test]
+    fn reads_name_and_trackers_from_torrent_file() {
+        let b = |s: &str| format!("{}:{}", s.len(), s);
+        let torrent = format!(
+            "d{}{}{}ll{}el{}{}ee{}d{}i1e{}{}{}i16384e{}0:ee",
+            b("announce"),
+            b("http://a/announce"),
+            b("announce-list"),
+            b("http://a/announce"),
+            b("udp://b:80/ann"),
+            b("http://a/announce"),
+            b("info"),
+            b("length"),
+            b("name"),
+            b("ep1.mkv"),
+            b("piece length"),
+            b("pieces"),
+        );
+        let source = source_from_torrent(torrent.as_bytes()).unwrap();
+        assert_eq!(source.display_name.as_deref(), Some("ep1.mkv"));
+        assert_eq!(source.trackers, vec!["http://a/announce", "udp://b:80/ann"]);
+    }
+
+    #[test]
+    fn filters_pseudo_trackers_and_duplicates() {
+        let source = source_from_magnet(&format!(
+            "magnet:?xt=urn:btih:{HASH}&tr=**%20%5BDHT%5D%20**&tr=udp%3A%2F%2Fx%3A1&tr=udp%3A%2F%2Fx%3A1"
+        ));
+        assert_eq!(source.trackers, vec!["udp://x:1"]);
+        assert_eq!(source.display_name, None);
+    }
+}
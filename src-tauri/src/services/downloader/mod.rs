@@ -1,9 +1,11 @@
 pub mod client;
 pub mod config;
 pub mod lifecycle;
+pub mod magnet;
 pub mod monitor;
 pub mod projection;
 pub mod repo;
+pub mod stats;
 
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
@@ -12,7 +14,7 @@ use ts_rs::TS;
 #[serde(tag = "kind", rename_all = "snake_case")]
 #[ts(export, export_to = "../../src/types/gen/downloader.ts")]
 pub enum DownloadExternalState {
-    Live { status: String },
+    Live { status: String, checking: bool },
     Stale,
     Missing,
 }
@@ -63,6 +65,12 @@ pub fn extract_resolution(live_name: Option<&str>, title: &str) -> Option<u32> {
         .or_else(|| parse_resolution(title))
 }
 
+/// qBittorrent reports hash verification as `checkingDL`, `checkingUP` or
+/// `checkingResumeData`; progress is rebuilt while the check runs.
+pub fn is_checking_state(state: &str) -> bool {
+    state.starts_with("checking")
+}
+
 pub fn build_metadata(title: String, cover: String) -> String {
     serde_json::json!({
         "resource_title": title,
@@ -1,5 +1,6 @@
 use super::{
-    client, extract_resolution, parse_metadata, repo, DownloadExternalState, DownloadItem,
+    client, extract_resolution, is_checking_state, parse_metadata, repo, DownloadExternalState,
+    DownloadItem,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -94,6 +95,7 @@ pub fn build_status_projection(
                     resolution,
                     external_state: DownloadExternalState::Live {
                         status: live.state.clone(),
+                        checking: is_checking_state(&live.state),
                     },
                     progress: live.progress * 100.0,
                     dlspeed: live.dlspeed,
@@ -156,6 +158,9 @@ mod tests {
             dlspeed: 1024,
             eta: 60,
             save_path: "/tmp/anime".to_string(),
+            size: 0,
+            added_on: 0,
+            completion_on: 0,
         }
     }
 
@@ -176,7 +181,10 @@ mod tests {
         );
 
         match &items[0].external_state {
-            DownloadExternalState::Live { status } => assert_eq!(status, "downloading"),
+            DownloadExternalState::Live { status, checking } => {
+                assert_eq!(status, "downloading");
+                assert!(!checking);
+            }
             _ => panic!("expected live state"),
         }
         assert_eq!(items[0].progress, 50.0);
@@ -184,6 +192,24 @@ mod tests {
         assert_eq!(items[0].resolution, Some(1080));
     }
 
+    #[test]
+    fn projects_checking_state_with_rebuilt_progress() {
+        let mut info = live("hash-a", "Anime - 01 1080p");
+        info.state = "checkingDL".to_string();
+        info.progress = 0.25;
+        let items = build_status_projection(
+            vec![tracked("hash-a", 1)],
+            vec![metadata("Anime")],
+            Some(vec![info]),
+        );
+
+        assert!(matches!(
+            items[0].external_state,
+            DownloadExternalState::Live { checking: true, .. }
+        ));
+        assert_eq!(items[0].progress, 25.0);
+    }
+
     #[test]
     fn projects_missing_state_when_external_downloader_has_no_hash() {
         let items = build_status_projection(
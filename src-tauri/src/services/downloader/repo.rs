@@ -1,6 +1,7 @@
+use super::magnet::TorrentSource;
 use crate::error::AppError;
 use crate::infra::time::now_secs;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +16,16 @@ pub struct TrackedDownload {
     pub updated_at: i64,
 }
 
+/// 条目的一条下载记录覆盖的集数，用于集数状态表
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpisodeCoverage {
+    pub episode: Option<u32>,
+    pub episode_range: Option<String>,
+    /// 仍在跟踪中
+    pub tracked: bool,
+    pub completed: bool,
+}
+
 pub async fn update_meta(hash: String, meta_json: String) -> Result<(), AppError> {
     let pool = crate::infra::db::data_pool()?;
     let conn = pool.get().await?;
@@ -61,35 +72,107 @@ pub async fn insert(
     Ok(())
 }
 
+const TRACKED_COLUMNS: &str =
+    "id, hash, subject_id, episode, episode_range, meta_json, created_at, updated_at";
+
+fn tracked_from_row(row: &rusqlite::Row) -> Result<TrackedDownload, rusqlite::Error> {
+    let ep_opt_i: Option<i64> = row.get(3)?;
+    let ep_opt_u: Option<u32> = ep_opt_i.map(|v| v as u32);
+    Ok(TrackedDownload {
+        id: row.get(0)?,
+        hash: row.get(1)?,
+        subject_id: row.get(2)?,
+        episode: ep_opt_u,
+        episode_range: row.get(4)?,
+        meta_json: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
 pub async fn list() -> Result<Vec<TrackedDownload>, AppError> {
     let pool = crate::infra::db::data_pool()?;
     let conn = pool.get().await?;
 
-    let items = conn.interact(move |conn| -> Result<Vec<TrackedDownload>, rusqlite::Error> {
-        let mut stmt = conn.prepare("SELECT id, hash, subject_id, episode, episode_range, meta_json, created_at, updated_at FROM tracked_downloads ORDER BY created_at DESC")?;
-        let rows = stmt.query_map([], |row| {
-            let ep_opt_i: Option<i64> = row.get(3)?;
-            let ep_opt_u: Option<u32> = ep_opt_i.map(|v| v as u32);
-            Ok(TrackedDownload {
-                id: row.get(0)?,
-                hash: row.get(1)?,
-                subject_id: row.get(2)?,
-                episode: ep_opt_u,
-                episode_range: row.get(4)?,
-                meta_json: row.get(5)?,
-                created_at: row.get(6)?,
-                updated_at: row.get(7)?,
-            })
-        })?;
-        let mut out = Vec::new();
-        for r in rows {
-            out.push(r?);
-        }
-        Ok(out)
-    }).await??;
+    let items = conn
+        .interact(
+            move |conn| -> Result<Vec<TrackedDownload>, rusqlite::Error> {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT {TRACKED_COLUMNS} FROM tracked_downloads ORDER BY created_at DESC"
+                ))?;
+                let rows = stmt.query_map([], tracked_from_row)?;
+                rows.collect()
+            },
+        )
+        .await??;
     Ok(items)
 }
 
+pub async fn get(hash: &str) -> Result<Option<TrackedDownload>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let hash = hash.to_string();
+    let item = conn.interact(move |conn| get_row(conn, &hash)).await??;
+    Ok(item)
+}
+
+fn get_row(
+    conn: &rusqlite::Connection,
+    hash: &str,
+) -> Result<Option<TrackedDownload>, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT {TRACKED_COLUMNS} FROM tracked_downloads WHERE hash = ?1"),
+        params![hash],
+        tracked_from_row,
+    )
+    .optional()
+}
+
+/// 保存添加时从种子或磁力链接中读取的显示名与 tracker，用于之后生成磁力链接
+pub async fn set_source(hash: &str, source: &TorrentSource) -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let hash = hash.to_string();
+    let display_name = source.display_name.clone();
+    let trackers_json = serde_json::to_string(&source.trackers)?;
+    conn.interact(move |conn| -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "UPDATE tracked_downloads SET display_name = ?1, trackers_json = ?2 WHERE hash = ?3",
+            params![display_name, trackers_json, hash],
+        )?;
+        Ok(())
+    })
+    .await??;
+    Ok(())
+}
+
+pub async fn get_source(hash: &str) -> Result<Option<TorrentSource>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let hash = hash.to_string();
+    let source = conn
+        .interact(
+            move |conn| -> Result<Option<TorrentSource>, rusqlite::Error> {
+                conn.query_row(
+                    "SELECT display_name, trackers_json FROM tracked_downloads WHERE hash = ?1",
+                    params![hash],
+                    |row| {
+                        let trackers_json: Option<String> = row.get(1)?;
+                        Ok(TorrentSource {
+                            display_name: row.get(0)?,
+                            trackers: trackers_json
+                                .and_then(|j| serde_json::from_str(&j).ok())
+                                .unwrap_or_default(),
+                        })
+                    },
+                )
+                .optional()
+            },
+        )
+        .await??;
+    Ok(source)
+}
+
 pub async fn delete(hash: String) -> Result<(), AppError> {
     let pool = crate::infra::db::data_pool()?;
     let conn = pool.get().await?;
@@ -103,3 +186,54 @@ pub async fn delete(hash: String) -> Result<(), AppError> {
     .await??;
     Ok(())
 }
+
+/// 跟踪中的下载，以及已移除跟踪但在统计中记录为完成的下载
+pub async fn episode_coverage(subject_id: u32) -> Result<Vec<EpisodeCoverage>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(
+            move |conn| -> Result<Vec<EpisodeCoverage>, rusqlite::Error> {
+                let mut stmt = conn.prepare(
+                    "SELECT t.episode, t.episode_range, 1, s.completed_on IS NOT NULL
+                 FROM tracked_downloads t
+                 LEFT JOIN download_stats s ON s.hash = t.hash
+                 WHERE t.subject_id = ?1
+                 UNION ALL
+                 SELECT episode, episode_range, 0, 1
+                 FROM download_stats
+                 WHERE subject_id = ?1 AND completed_on IS NOT NULL
+                   AND hash NOT IN (SELECT hash FROM tracked_downloads)",
+                )?;
+                let rows = stmt.query_map(params![subject_id as i64], |row| {
+                    Ok(EpisodeCoverage {
+                        episode: row.get::<_, Option<i64>>(0)?.map(|v| v as u32),
+                        episode_range: row.get(1)?,
+                        tracked: row.get(2)?,
+                        completed: row.get(3)?,
+                    })
+                })?;
+                rows.collect()
+            },
+        )
+        .await??;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_finds_a_single_tracked_download() {
+        let conn = crate::infra::db::test_data_connection();
+        conn.execute_batch(
+            "INSERT INTO tracked_downloads(hash, subject_id, episode, created_at, updated_at)
+             VALUES ('aaa', 1, 5, 10, 10), ('bbb', 2, NULL, 20, 20);",
+        )
+        .unwrap();
+        let found = get_row(&conn, "aaa").unwrap().unwrap();
+        assert_eq!((found.subject_id, found.episode), (1, Some(5)));
+        assert!(get_row(&conn, "ccc").unwrap().is_none());
+    }
+}
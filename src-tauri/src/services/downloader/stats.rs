@@ -0,0 +1,436 @@
+use super::{client, parse_metadata, repo};
+use crate::error::AppError;
+use crate::infra::time::now_secs;
+use crate::utils::parser::parse_resolution;
+use chrono::{Datelike, Local, TimeZone};
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use ts_rs::TS;
+
+/// 单个下载的持久化快照；种子被删除后仍保留，统计不会因此丢失
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadSnapshot {
+    pub hash: String,
+    pub subject_id: u32,
+    pub episode: Option<u32>,
+    pub episode_range: Option<String>,
+    pub group_name: Option<String>,
+    pub resolution: Option<u32>,
+    pub size_bytes: i64,
+    pub added_on: i64,
+    pub completed_on: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/downloader_stats.ts")]
+pub struct SubjectUsage {
+    pub subject_id: u32,
+    pub title: String,
+    pub bytes: i64,
+    pub episodes: u32,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/downloader_stats.ts")]
+pub struct GroupUsage {
+    pub group: String,
+    pub bytes: i64,
+    pub episodes: u32,
+    pub subjects: u32,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/types/gen/downloader_stats.ts")]
+pub struct DownloadStats {
+    pub total_bytes: i64,
+    pub season_bytes: i64,
+    pub episodes: u32,
+    #[ts(optional)]
+    pub avg_download_secs: Option<i64>,
+    pub subjects: Vec<SubjectUsage>,
+    pub groups: Vec<GroupUsage>,
+}
+
+/// 未识别字幕组的下载归入此分组
+pub const UNKNOWN_GROUP: &str = "未知";
+
+/// 由跟踪记录与下载器实时信息生成快照；下载器不可用时只保留本地可知字段
+pub fn snapshot_of(
+    tracked: &repo::TrackedDownload,
+    live: Option<&client::TorrentInfo>,
+) -> DownloadSnapshot {
+    let title = tracked
+        .meta_json
+        .as_deref()
+        .and_then(parse_metadata)
+        .map(|(title, _)| title);
+    let live_name = live.map(|l| l.name.as_str());
+    let group_name = title
+        .as_deref()
+        .and_then(crate::utils::parser::parse_group)
+        .or_else(|| live_name.and_then(crate::utils::parser::parse_group));
+    let resolution = live_name
+        .and_then(parse_resolution)
+        .or_else(|| title.as_deref().and_then(parse_resolution));
+
+    DownloadSnapshot {
+        hash: tracked.hash.clone(),
+        subject_id: tracked.subject_id,
+        episode: tracked.episode,
+        episode_range: tracked.episode_range.clone(),
+        group_name,
+        resolution,
+        size_bytes: live.map(|l| l.size.max(0)).unwrap_or(0),
+        added_on: live
+            .map(|l| l.added_on)
+            .filter(|t| *t > 0)
+            .unwrap_or(tracked.created_at),
+        completed_on: live.map(|l| l.completion_on).filter(|t| *t > 0),
+    }
+}
+
+/// 下载器报告已完成、且本次运行尚未记录过的下载的快照
+pub fn completed_snapshots(
+    tracked: &[repo::TrackedDownload],
+    live: &[client::TorrentInfo],
+    recorded: &HashSet<String>,
+) -> Vec<DownloadSnapshot> {
+    let live: HashMap<&str, &client::TorrentInfo> =
+        live.iter().map(|l| (l.hash.as_str(), l)).collect();
+    tracked
+        .iter()
+        .filter(|t| !recorded.contains(&t.hash))
+        .filter_map(|t| {
+            let info = live.get(t.hash.as_str()).filter(|l| l.completion_on > 0)?;
+            Some(snapshot_of(t, Some(info)))
+        })
+        .collect()
+}
+
+/// 一个下载覆盖的集数：合集按范围计算
+fn episode_count(snapshot: &DownloadSnapshot) -> u32 {
+    snapshot
+        .episode_range
+        .as_deref()
+        .and_then(|r| r.split_once('-'))
+        .and_then(|(s, e)| Some((s.trim().parse::<u32>().ok()?, e.trim().parse::<u32>().ok()?)))
+        .filter(|(s, e)| e >= s)
+        .map(|(s, e)| e - s + 1)
+        .unwrap_or(1)
+}
+
+/// 当前季度（1/4/7/10 月）起始时刻
+pub fn season_start(now: i64) -> i64 {
+    let Some(now) = Local.timestamp_opt(now, 0).single() else {
+        return 0;
+    };
+    let month = (now.month0() / 3) * 3 + 1;
+    Local
+        .with_ymd_and_hms(now.year(), month, 1, 0, 0, 0)
+        .earliest()
+        .map(|d| d.timestamp())
+        .unwrap_or(0)
+}
+
+pub fn aggregate(
+    snapshots: &[DownloadSnapshot],
+    titles: &HashMap<u32, String>,
+    season_start: i64,
+) -> DownloadStats {
+    let mut subjects: HashMap<u32, SubjectUsage> = HashMap::new();
+    let mut groups: HashMap<String, (GroupUsage, HashSet<u32>)> = HashMap::new();
+    let mut total_bytes = 0;
+    let mut season_bytes = 0;
+    let mut episodes = 0;
+    let mut durations = Vec::new();
+
+    for s in snapshots {
+        let count = episode_count(s);
+        total_bytes += s.size_bytes;
+        episodes += count;
+        if s.added_on >= season_start {
+            season_bytes += s.size_bytes;
+        }
+        if let Some(done) = s.completed_on {
+            if done >= s.added_on && s.added_on > 0 {
+                durations.push(done - s.added_on);
+            }
+        }
+
+        let subject = subjects
+            .entry(s.subject_id)
+            .or_insert_with(|| SubjectUsage {
+                subject_id: s.subject_id,
+                title: titles.get(&s.subject_id).cloned().unwrap_or_default(),
+                bytes: 0,
+                episodes: 0,
+            });
+        subject.bytes += s.size_bytes;
+        subject.episodes += count;
+
+        let name = s
+            .group_name
+            .clone()
+            .unwrap_or_else(|| UNKNOWN_GROUP.to_string());
+        let (group, subject_ids) = groups.entry(name.clone()).or_insert_with(|| {
+            (
+                GroupUsage {
+                    group: name,
+                    bytes: 0,
+                    episodes: 0,
+                    subjects: 0,
+                },
+                HashSet::new(),
+            )
+        });
+        group.bytes += s.size_bytes;
+        group.episodes += count;
+        subject_ids.insert(s.subject_id);
+    }
+
+    let mut subjects: Vec<SubjectUsage> = subjects.into_values().collect();
+    subjects.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.subject_id.cmp(&b.subject_id)));
+    let mut groups: Vec<GroupUsage> = groups
+        .into_values()
+        .map(|(mut g, ids)| {
+            g.subjects = ids.len() as u32;
+            g
+        })
+        .collect();
+    groups.sort_by(|a, b| b.episodes.cmp(&a.episodes).then(a.group.cmp(&b.group)));
+
+    let avg_download_secs = if durations.is_empty() {
+        None
+    } else {
+        Some(durations.iter().sum::<i64>() / durations.len() as i64)
+    };
+
+    DownloadStats {
+        total_bytes,
+        season_bytes,
+        episodes,
+        avg_download_secs,
+        subjects,
+        groups,
+    }
+}
+
+/// 写入快照。下载器离线时新快照大小为 0，不覆盖已记录的大小与完成时间
+pub async fn save_snapshots(snapshots: Vec<DownloadSnapshot>) -> Result<(), AppError> {
+    if snapshots.is_empty() {
+        return Ok(());
+    }
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let now = now_secs();
+    conn.interact(move |conn| -> Result<(), rusqlite::Error> {
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO download_stats
+                   (hash, subject_id, episode, episode_range, group_name, resolution,
+                    size_bytes, added_on, completed_on, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(hash) DO UPDATE SET
+                   subject_id=excluded.subject_id,
+                   episode=excluded.episode,
+                   episode_range=excluded.episode_range,
+                   group_name=COALESCE(excluded.group_name, download_stats.group_name),
+                   resolution=COALESCE(excluded.resolution, download_stats.resolution),
+                   size_bytes=CASE WHEN excluded.size_bytes > 0
+                                   THEN excluded.size_bytes ELSE download_stats.size_bytes END,
+                   added_on=CASE WHEN excluded.size_bytes > 0
+                                 THEN excluded.added_on ELSE download_stats.added_on END,
+                   completed_on=COALESCE(excluded.completed_on, download_stats.completed_on),
+                   updated_at=excluded.updated_at",
+            )?;
+            for s in &snapshots {
+                stmt.execute(params![
+                    s.hash,
+                    s.subject_id,
+                    s.episode,
+                    s.episode_range,
+                    s.group_name,
+                    s.resolution,
+                    s.size_bytes,
+                    s.added_on,
+                    s.completed_on,
+                    now
+                ])?;
+            }
+        }
+        tx.commit()
+    })
+    .await??;
+    Ok(())
+}
+
+pub async fn list_snapshots() -> Result<Vec<DownloadSnapshot>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let rows = conn
+        .interact(|conn| -> Result<Vec<DownloadSnapshot>, rusqlite::Error> {
+            let mut stmt = conn.prepare(
+                "SELECT hash, subject_id, episode, episode_range, group_name, resolution,
+                        size_bytes, added_on, completed_on
+                 FROM download_stats",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(DownloadSnapshot {
+                    hash: row.get(0)?,
+                    subject_id: row.get(1)?,
+                    episode: row.get(2)?,
+                    episode_range: row.get(3)?,
+                    group_name: row.get(4)?,
+                    resolution: row.get(5)?,
+                    size_bytes: row.get(6)?,
+                    added_on: row.get(7)?,
+                    completed_on: row.get(8)?,
+                })
+            })?;
+            rows.collect()
+        })
+        .await??;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(hash: &str, subject_id: u32, group: Option<&str>, size: i64) -> DownloadSnapshot {
+        DownloadSnapshot {
+            hash: hash.to_string(),
+            subject_id,
+            episode: Some(1),
+            episode_range: None,
+            group_name: group.map(str::to_string),
+            resolution: Some(1080),
+            size_bytes: size,
+            added_on: 1_000,
+            completed_on: None,
+        }
+    }
+
+    #[test]
+    fn snapshot_prefers_release_title_group_and_live_sizes() {
+        let tracked = repo::TrackedDownload {
+            id: 1,
+            hash: "h".to_string(),
+            subject_id: 7,
+            episode: Some(3),
+            episode_range: None,
+            meta_json: Some(super::super::build_metadata(
+                "[LoliHouse] Frieren - 03 [WebRip 1080p HEVC-10bit AAC]".to_string(),
+                "cover".to_string(),
+            )),
+            created_at: 50,
+            updated_at: 50,
+        };
+        let live = client::TorrentInfo {
+            hash: "h".to_string(),
+            name: "Frieren - 03.mkv".to_string(),
+            state: "uploading".to_string(),
+            progress: 1.0,
+            dlspeed: 0,
+            eta: 0,
+            save_path: "/tmp".to_string(),
+            size: 500,
+            added_on: 100,
+            completion_on: 400,
+        };
+
+        let s = snapshot_of(&tracked, Some(&live));
+        assert_eq!(s.group_name.as_deref(), Some("LoliHouse"));
+        assert_eq!(s.resolution, Some(1080));
+        assert_eq!(
+            (s.size_bytes, s.added_on, s.completed_on),
+            (500, 100, Some(400))
+        );
+
+        let offline = snapshot_of(&tracked, None);
+        assert_eq!(
+            (offline.size_bytes, offline.added_on, offline.completed_on),
+            (0, 50, None)
+        );
+    }
+
+    #[test]
+    fn completed_snapshots_skip_unfinished_and_recorded() {
+        let tracked: Vec<repo::TrackedDownload> = ["a", "b", "c"]
+            .iter()
+            .map(|h| repo::TrackedDownload {
+                id: 1,
+                hash: h.to_string(),
+                subject_id: 7,
+                episode: None,
+                episode_range: None,
+                meta_json: None,
+                created_at: 0,
+                updated_at: 0,
+            })
+            .collect();
+        let info = |hash: &str, completion_on: i64| client::TorrentInfo {
+            hash: hash.to_string(),
+            name: String::new(),
+            state: String::new(),
+            progress: 0.0,
+            dlspeed: 0,
+            eta: 0,
+            save_path: String::new(),
+            size: 500,
+            added_on: 100,
+            completion_on,
+        };
+        let live = vec![info("a", 400), info("b", -1), info("c", 500)];
+        let recorded: HashSet<String> = ["c".to_string()].into();
+        let out = completed_snapshots(&tracked, &live, &recorded);
+        assert_eq!(out.len(), 1);
+        assert_eq!(
+            (out[0].hash.as_str(), out[0].completed_on),
+            ("a", Some(400))
+        );
+    }
+
+    #[test]
+    fn aggregates_by_subject_group_and_season() {
+        let mut old = snapshot("a", 1, Some("ANi"), 100);
+        old.added_on = 10;
+        old.completed_on = Some(70);
+        let mut batch = snapshot("b", 1, Some("ANi"), 1_000);
+        batch.episode = None;
+        batch.episode_range = Some("01-12".to_string());
+        batch.completed_on = Some(1_200);
+        let other = snapshot("c", 2, None, 300);
+
+        let titles = HashMap::from([(1, "葬送的芙莉莲".to_string())]);
+        let stats = aggregate(&[old, batch, other], &titles, 500);
+
+        assert_eq!(stats.total_bytes, 1_400);
+        assert_eq!(stats.season_bytes, 1_300);
+        assert_eq!(stats.episodes, 14);
+        assert_eq!(stats.avg_download_secs, Some(130));
+
+        assert_eq!(stats.subjects[0].subject_id, 1);
+        assert_eq!(stats.subjects[0].title, "葬送的芙莉莲");
+        assert_eq!(stats.subjects[0].episodes, 13);
+        assert_eq!(stats.subjects[1].title, "");
+
+        assert_eq!(stats.groups[0].group, "ANi");
+        assert_eq!(
+            (stats.groups[0].episodes, stats.groups[0].subjects),
+            (13, 1)
+        );
+        assert_eq!(stats.groups[1].group, UNKNOWN_GROUP);
+    }
+
+    #[test]
+    fn season_starts_on_quarter_boundary() {
+        let now = Local.with_ymd_and_hms(2025, 8, 17, 12, 0, 0).unwrap();
+        let start = Local
+            .timestamp_opt(season_start(now.timestamp()), 0)
+            .unwrap();
+        assert_eq!((start.month(), start.day()), (7, 1));
+    }
+}
@@ -1,26 +1,105 @@
 use crate::error::AppError;
-use crate::infra::config::MIKAN_HOST;
-use crate::infra::http::CLIENT;
+use crate::models::mikan::MikanSubgroup;
+use crate::services::mikan::host;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use reqwest::StatusCode;
 use scraper::{Html, Selector};
 
-pub async fn resolve_subject(bangumi_id: u32) -> Result<Option<u32>, AppError> {
-    let url = format!("{}/Home/Bangumi/{}", MIKAN_HOST, bangumi_id);
-    let resp = CLIENT.get(&url).send().await?;
+/// Mikan 番组页中用于匹配 Bangumi 条目的信息
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BangumiPage {
+    /// 页面上的 bgm.tv 链接
+    pub subject_id: Option<u32>,
+    pub title: String,
+    /// 「放送开始」年份
+    pub year: Option<i32>,
+    /// 页面资源标题中出现的最大集数
+    pub max_episode: Option<u32>,
+    /// 发布过该番组资源的字幕组
+    pub subgroups: Vec<MikanSubgroup>,
+}
+
+static RE_AIR_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"放送开始[：:]\s*(?:\d{1,2}/\d{1,2}/(\d{4})|(\d{4})[-/.]\d{1,2})").unwrap()
+});
+
+pub async fn fetch_page(bangumi_id: u32) -> Result<BangumiPage, AppError> {
+    let resp = host::get(&format!("/Home/Bangumi/{}", bangumi_id)).await?;
     if resp.status() != StatusCode::OK {
         resp.error_for_status_ref()?;
     }
     let html = resp.text().await?;
-    let doc = Html::parse_document(&html);
-    let sel = Selector::parse("a").unwrap();
-    for a in doc.select(&sel) {
-        if let Some(href) = a.value().attr("href") {
-            if let Some(id) = parse_subject_id(href) {
-                return Ok(Some(id));
-            }
+    Ok(parse_page(&html))
+}
+
+pub async fn resolve_subject(bangumi_id: u32) -> Result<Option<u32>, AppError> {
+    Ok(fetch_page(bangumi_id).await?.subject_id)
+}
+
+pub fn parse_page(html: &str) -> BangumiPage {
+    let doc = Html::parse_document(html);
+    let sel_a = Selector::parse("a").unwrap();
+    let sel_title = Selector::parse("p.bangumi-title").unwrap();
+    let sel_info = Selector::parse("p.bangumi-info").unwrap();
+    let sel_episode = Selector::parse("a.magnet-link-wrap").unwrap();
+    let sel_subgroup = Selector::parse("div.subgroup-text[id]").unwrap();
+
+    let subject_id = doc
+        .select(&sel_a)
+        .filter_map(|a| a.value().attr("href"))
+        .find_map(parse_subject_id);
+
+    let title = doc
+        .select(&sel_title)
+        .next()
+        .map(|p| p.text().collect::<String>().trim().to_string())
+        .unwrap_or_default();
+
+    let year = doc.select(&sel_info).find_map(|p| {
+        let text = p.text().collect::<String>();
+        let c = RE_AIR_DATE.captures(&text)?;
+        c.get(1).or_else(|| c.get(2))?.as_str().parse().ok()
+    });
+
+    let max_episode = doc
+        .select(&sel_episode)
+        .filter_map(|a| {
+            let text = a.text().collect::<String>();
+            let (episode, range) = crate::utils::parser::parse_episode_info(&text);
+            episode.or(range.map(|r| r.end))
+        })
+        .max();
+
+    let mut subgroups: Vec<MikanSubgroup> = Vec::new();
+    for div in doc.select(&sel_subgroup) {
+        let Some(id) = div.value().id().and_then(|id| id.parse().ok()) else {
+            continue;
+        };
+        // 组名在第一个链接里，之后是 RSS 图标与订阅按钮
+        let name = div
+            .select(&sel_a)
+            .map(|a| a.text().collect::<String>().trim().to_string())
+            .find(|t| !t.is_empty())
+            .or_else(|| {
+                div.text()
+                    .map(str::trim)
+                    .find(|t| !t.is_empty())
+                    .map(String::from)
+            })
+            .unwrap_or_default();
+        if !subgroups.iter().any(|g| g.id == id) {
+            subgroups.push(MikanSubgroup { id, name });
         }
     }
-    Ok(None)
+
+    BangumiPage {
+        subject_id,
+        title,
+        year,
+        max_episode,
+        subgroups,
+    }
 }
 
 fn parse_subject_id(href: &str) -> Option<u32> {
@@ -45,3 +124,62 @@ fn parse_subject_id(href: &str) -> Option<u32> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"
+    <div class="pull-left leftbar-container">
+        <p class="bangumi-title">葬送的芙莉莲<a href="/RSS/Bangumi?bangumiId=3141" class="mikan-rss"></a></p>
+        <p class="bangumi-info">放送日期：星期五</p>
+        <p class="bangumi-info">放送开始：9/29/2023</p>
+        <p class="bangumi-info">Bangumi番组计划链接：<a class="w-other-c" href="https://bgm.tv/subject/400602">https://bgm.tv/subject/400602</a></p>
+    </div>
+    <div class="subgroup-text" id="583"><a href="/Home/PublishGroup/223" target="_blank">ANi</a><a href="/RSS/Bangumi?bangumiId=3141&amp;subgroupid=583" class="mikan-rss"><i class="fa fa-rss-square"></i></a></div>
+    <div class="subgroup-text" id="370"><a href="/Home/PublishGroup/370" target="_blank">LoliHouse</a></div>
+    <div class="subgroup-text" id="202">  生肉/不明字幕 <a class="mikan-rss" href="/RSS/Bangumi?bangumiId=3141&amp;subgroupid=202"></a></div>
+    <table>
+        <tr><td><a class="magnet-link-wrap" href="/Home/Episode/a">[ANi] 葬送的芙莉莲 - 27 [1080P]</a></td></tr>
+        <tr><td><a class="magnet-link-wrap" href="/Home/Episode/b">[LoliHouse] 葬送的芙莉莲 - 03 [WebRip 1080p]</a></td></tr>
+    </table>
+    "#;
+
+    #[test]
+    fn parses_bangumi_page_details() {
+        let page = parse_page(PAGE);
+        assert_eq!(page.subject_id, Some(400602));
+        assert_eq!(page.title, "葬送的芙莉莲");
+        assert_eq!(page.year, Some(2023));
+        assert_eq!(page.max_episode, Some(27));
+        let groups: Vec<(u32, &str)> = page
+            .subgroups
+            .iter()
+            .map(|g| (g.id, g.name.as_str()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![(583, "ANi"), (370, "LoliHouse"), (202, "生肉/不明字幕")]
+        );
+    }
+
+    #[test]
+    fn page_without_bgm_link_still_has_title() {
+        let page = parse_page(r#"<p class="bangumi-title"> 间谍过家家 </p>"#);
+        assert_eq!(page.subject_id, None);
+        assert_eq!(page.title, "间谍过家家");
+        assert_eq!(page.year, None);
+        assert_eq!(page.max_episode, None);
+        assert!(page.subgroups.is_empty());
+    }
+
+    #[test]
+    fn extracts_subject_id_from_known_domains() {
+        assert_eq!(
+            parse_subject_id("https://bangumi.tv/subject/12?x"),
+            Some(12)
+        );
+        assert_eq!(parse_subject_id("//chii.in/subject/34"), Some(34));
+        assert_eq!(parse_subject_id("https://example.com/subject/56"), None);
+    }
+}
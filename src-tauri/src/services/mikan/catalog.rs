@@ -0,0 +1,241 @@
+use crate::error::AppError;
+use crate::infra::cache;
+use crate::models::mikan::MikanCatalogItem;
+use crate::services::mikan::search::parse_bangumi_id;
+use crate::services::mikan::{bangumi_page, host};
+use crate::services::subscriptions;
+use scraper::{ElementRef, Html, Selector};
+use tracing::info;
+
+const SEARCH_TTL_SECS: i64 = 3600;
+const SEASON_TTL_SECS: i64 = 6 * 3600;
+/// Mikan 季度番组表使用的季节名
+pub const SEASONS: [&str; 4] = ["春", "夏", "秋", "冬"];
+
+/// 番组卡片：`li` 内含封面 `span[data-src]`、番组链接与 `.an-text` 标题
+fn parse_card(li: ElementRef) -> Option<MikanCatalogItem> {
+    let sel_a = Selector::parse("a[href]").unwrap();
+    let sel_cover = Selector::parse("[data-src]").unwrap();
+    let sel_text = Selector::parse(".an-text").unwrap();
+
+    let cover = li.select(&sel_cover).next();
+    let id = cover
+        .and_then(|c| c.value().attr("data-bangumiid"))
+        .and_then(|v| v.parse().ok())
+        .or_else(|| {
+            li.select(&sel_a)
+                .find_map(|a| parse_bangumi_id(a.value().attr("href")?))
+        })?;
+    let text = li.select(&sel_text).next()?;
+    let title = text
+        .value()
+        .attr("title")
+        .map(str::to_string)
+        .unwrap_or_else(|| text.text().collect::<String>())
+        .trim()
+        .to_string();
+    if title.is_empty() {
+        return None;
+    }
+    Some(MikanCatalogItem {
+        mikan_bangumi_id: id,
+        title,
+        cover_url: cover
+            .and_then(|c| c.value().attr("data-src"))
+            .map(String::from),
+        day_of_week: None,
+    })
+}
+
+fn push_unique(out: &mut Vec<MikanCatalogItem>, item: MikanCatalogItem) {
+    if !out
+        .iter()
+        .any(|i| i.mikan_bangumi_id == item.mikan_bangumi_id)
+    {
+        out.push(item);
+    }
+}
+
+/// 搜索页 `ul.an-ul` 中的番组列表（不含下方的资源列表）
+pub fn parse_search_results(html: &str) -> Vec<MikanCatalogItem> {
+    let doc = Html::parse_document(html);
+    let sel_li = Selector::parse("ul.an-ul > li").unwrap();
+    let mut out = Vec::new();
+    for item in doc.select(&sel_li).filter_map(parse_card) {
+        push_unique(&mut out, item);
+    }
+    out
+}
+
+/// 季度番组表按放送日分块，每块为 `div.sk-bangumi[data-dayofweek]`
+pub fn parse_season_index(html: &str) -> Vec<MikanCatalogItem> {
+    let doc = Html::parse_document(html);
+    let sel_day = Selector::parse("div.sk-bangumi").unwrap();
+    let sel_li = Selector::parse("ul.an-ul > li").unwrap();
+    let mut out = Vec::new();
+    for day in doc.select(&sel_day) {
+        let day_of_week = day
+            .value()
+            .attr("data-dayofweek")
+            .and_then(|v| v.parse().ok());
+        for mut item in day.select(&sel_li).filter_map(parse_card) {
+            item.day_of_week = day_of_week;
+            push_unique(&mut out, item);
+        }
+    }
+    out
+}
+
+async fn fetch_cached<F>(key: &str, path: &str, ttl: i64, build: F) -> Result<String, AppError>
+where
+    F: Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+{
+    if let Some((html, _, _)) = cache::get_entry(key).await? {
+        return Ok(html);
+    }
+    crate::infra::http::wait_api_limit().await;
+    let resp = host::send(path, build).await?;
+    resp.error_for_status_ref()?;
+    let html = resp.text().await?;
+    cache::set_entry(key, html.clone(), None, None, ttl).await?;
+    Ok(html)
+}
+
+/// 封面为站内相对路径，补全为当前镜像的链接
+async fn with_absolute_covers(mut items: Vec<MikanCatalogItem>) -> Vec<MikanCatalogItem> {
+    let rewriter = host::url_rewriter().await;
+    for item in &mut items {
+        item.cover_url = item.cover_url.as_deref().map(|c| rewriter.absolute(c));
+    }
+    items
+}
+
+pub async fn search(keyword: &str) -> Result<Vec<MikanCatalogItem>, AppError> {
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Ok(Vec::new());
+    }
+    let key = format!("mikan:search:{}", keyword);
+    let html = fetch_cached(&key, "/Home/Search", SEARCH_TTL_SECS, |b| {
+        b.query(&[("searchstr", keyword)])
+    })
+    .await?;
+    Ok(with_absolute_covers(parse_search_results(&html)).await)
+}
+
+/// 某年某季的番组表，`season` 为 春/夏/秋/冬
+pub async fn season_index(year: u32, season: &str) -> Result<Vec<MikanCatalogItem>, AppError> {
+    if !SEASONS.contains(&season) {
+        return Err(AppError::Any(format!("unknown mikan season: {season}")));
+    }
+    let key = format!("mikan:season:{}:{}", year, season);
+    let html = fetch_cached(
+        &key,
+        "/Home/BangumiCoverFlowByDayOfWeek",
+        SEASON_TTL_SECS,
+        |b| {
+            b.query(&[
+                ("year", year.to_string()),
+                ("seasonStr", season.to_string()),
+            ])
+        },
+    )
+    .await?;
+    Ok(with_absolute_covers(parse_season_index(&html)).await)
+}
+
+/// 从 Mikan 番组订阅：解析对应的 Bangumi 条目，写入锁定映射并订阅，返回条目 id
+pub async fn subscribe(mikan_id: u32) -> Result<u32, AppError> {
+    let sid = bangumi_page::resolve_subject(mikan_id)
+        .await?
+        .ok_or_else(|| AppError::Any(format!("mikan bangumi {mikan_id} has no bgm.tv link")))?;
+    super::bind_manual(sid, mikan_id).await?;
+    subscriptions::ensure_subscribed(sid).await?;
+    info!(mikan_id, subject_id = sid, "subscribed from mikan catalog");
+    Ok(sid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEARCH_HTML: &str = r#"
+    <div class="central-container">
+      <ul class="list-inline an-ul">
+        <li>
+          <a href="/Home/Bangumi/3141" target="_blank">
+            <span data-src="/images/Bangumi/202310/a.jpg?width=400" class="b-lazy"></span>
+            <div class="an-info"><div class="an-info-group">
+              <div class="an-text" title="葬送的芙莉莲">葬送的芙莉莲</div>
+            </div></div>
+          </a>
+        </li>
+        <li>
+          <a href="/Home/Bangumi/3822" target="_blank">
+            <span data-src="/images/Bangumi/202601/b.jpg" class="b-lazy"></span>
+            <div class="an-info"><div class="an-text">葬送的芙莉莲 第二季</div></div>
+          </a>
+        </li>
+      </ul>
+      <table><tr><td><a href="/Home/Episode/abc">[ANi] 葬送的芙莉莲 - 27</a></td></tr></table>
+    </div>"#;
+
+    const SEASON_HTML: &str = r#"
+    <div class="sk-bangumi" data-dayofweek="5">
+      <ul class="list-inline an-ul">
+        <li>
+          <span data-src="/images/Bangumi/202610/c.jpg" data-bangumiid="3901" class="js-expand_bangumi b-lazy"></span>
+          <div class="an-info"><div class="an-info-group">
+            <a href="/Home/Bangumi/3901" class="an-text" title="间谍过家家 第三季">间谍过家家 第三季</a>
+          </div></div>
+        </li>
+      </ul>
+    </div>
+    <div class="sk-bangumi" data-dayofweek="7">
+      <ul class="list-inline an-ul">
+        <li>
+          <span data-src="/images/Bangumi/202610/d.jpg" data-bangumiid="3950" class="b-lazy"></span>
+          <div class="an-info"><a href="/Home/Bangumi/3950" class="an-text" title="剧场版">剧场版</a></div>
+        </li>
+        <li><div class="an-info">no card</div></li>
+      </ul>
+    </div>"#;
+
+    #[test]
+    fn parses_search_cards() {
+        let items = parse_search_results(SEARCH_HTML);
+        assert_eq!(
+            items,
+            vec![
+                MikanCatalogItem {
+                    mikan_bangumi_id: 3141,
+                    title: "葬送的芙莉莲".into(),
+                    cover_url: Some("/images/Bangumi/202310/a.jpg?width=400".into()),
+                    day_of_week: None,
+                },
+                MikanCatalogItem {
+                    mikan_bangumi_id: 3822,
+                    title: "葬送的芙莉莲 第二季".into(),
+                    cover_url: Some("/images/Bangumi/202601/b.jpg".into()),
+                    day_of_week: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_season_index_with_weekday() {
+        let items = parse_season_index(SEASON_HTML);
+        let summary: Vec<(u32, Option<u8>)> = items
+            .iter()
+            .map(|i| (i.mikan_bangumi_id, i.day_of_week))
+            .collect();
+        assert_eq!(summary, vec![(3901, Some(5)), (3950, Some(7))]);
+        assert_eq!(items[0].title, "间谍过家家 第三季");
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_season() {
+        assert!(season_index(2026, "autumn").await.is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::error::AppError;
+use crate::infra::path::default_app_dir;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::fs;
+use ts_rs::TS;
+
+/// Mikan 相关设置，保存在 `mikan.json`；缺失字段取默认值，方便后续扩展
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export, export_to = "../../src/types/gen/mikan_config.ts")]
+pub struct MikanConfig {
+    /// 「我的番组」RSS 的 token
+    #[ts(optional)]
+    pub my_bangumi_token: Option<String>,
+    /// 是否定期按 token 重新同步订阅
+    pub my_bangumi_sync: bool,
+    /// 镜像列表，按顺序尝试；为空时使用内置列表
+    pub hosts: Vec<String>,
+    pub sources: SourceSettings,
+    pub preferences: ReleasePreferences,
+}
+
+/// 各资源站点的开关；默认只启用 Mikan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export, export_to = "../../src/types/gen/mikan_config.ts")]
+pub struct SourceSettings {
+    pub mikan: bool,
+    pub dmhy: bool,
+    pub nyaa: bool,
+}
+
+impl Default for SourceSettings {
+    fn default() -> Self {
+        Self {
+            mikan: true,
+            dmhy: false,
+            nyaa: false,
+        }
+    }
+}
+
+/// 资源排序偏好；列表按优先级从高到低
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export, export_to = "../../src/types/gen/mikan_config.ts")]
+pub struct ReleasePreferences {
+    pub preferred_groups: Vec<String>,
+    /// 屏蔽的字幕组不会出现在分组结果中
+    pub blocked_groups: Vec<String>,
+    #[ts(optional)]
+    pub resolution: Option<u32>,
+    /// 字幕语言，如 `简繁`、`简日`
+    pub languages: Vec<String>,
+    /// 字幕形式，如 `内封`、`外挂`
+    pub subtitle_types: Vec<String>,
+    /// 单集体积上限，超过时视为异常
+    pub max_episode_mb: u32,
+}
+
+impl Default for ReleasePreferences {
+    fn default() -> Self {
+        Self {
+            preferred_groups: Vec::new(),
+            blocked_groups: Vec::new(),
+            resolution: None,
+            languages: Vec::new(),
+            subtitle_types: Vec::new(),
+            max_episode_mb: 4096,
+        }
+    }
+}
+
+pub async fn get_config() -> Result<MikanConfig, AppError> {
+    load_config(&default_app_dir().join("mikan.json")).await
+}
+
+async fn load_config(path: &Path) -> Result<MikanConfig, AppError> {
+    match fs::read_to_string(path).await {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(MikanConfig::default()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+pub async fn save_config(config: MikanConfig) -> Result<(), AppError> {
+    save_config_to(&default_app_dir().join("mikan.json"), &config).await?;
+    super::host::reset_active();
+    Ok(())
+}
+
+async fn save_config_to(path: &Path, config: &MikanConfig) -> Result<(), AppError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    let temp_path = path.with_extension("tmp");
+    let content = serde_json::to_string_pretty(&config)?;
+    fs::write(&temp_path, content).await?;
+    if let Err(error) = fs::rename(&temp_path, path).await {
+        let _ = fs::remove_file(temp_path).await;
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_fields_fall_back_to_defaults() {
+        let dir = std::env::temp_dir().join(format!(
+            "animefun-mikan-config-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let path = dir.join("mikan.json");
+        assert_eq!(load_config(&path).await.unwrap(), MikanConfig::default());
+
+        fs::create_dir_all(&dir).await.unwrap();
+        fs::write(&path, r#"{"my_bangumi_token":"abc"}"#)
+            .await
+            .unwrap();
+        let config = load_config(&path).await.unwrap();
+        assert_eq!(config.my_bangumi_token.as_deref(), Some("abc"));
+        assert!(!config.my_bangumi_sync);
+        assert!(config.sources.mikan && !config.sources.nyaa);
+
+        save_config_to(&path, &config).await.unwrap();
+        assert_eq!(load_config(&path).await.unwrap(), config);
+
+        fs::remove_dir_all(dir).await.unwrap();
+    }
+}
@@ -0,0 +1,191 @@
+use crate::error::AppError;
+use crate::models::bangumi::Episode;
+use crate::models::mikan::MikanResourceItem;
+use crate::models::release::EpisodeRange;
+use crate::services::mikan::resolver::SubjectProfile;
+use crate::services::{bangumi, subscriptions};
+use std::collections::HashSet;
+use tracing::info;
+
+/// Bangumi 正片类型
+const EP_TYPE_MAIN: u8 = 0;
+const EPISODE_PAGE_LIMIT: u32 = 100;
+
+/// 推断 Mikan 集数到 Bangumi `sort` 的偏移。
+///
+/// 候选偏移为 0、条目中 `sort - ep` 的差值（字幕组按季内编号、Bangumi 连续编号），
+/// 以及把最小的 Mikan 集数对齐到第一集的偏移（字幕组连续编号、Bangumi 按季编号）。
+/// 取落入正片列表最多的候选；数据不足时返回 `None`。
+pub fn learn_offset(episodes: &[Episode], mikan_episodes: &[u32]) -> Option<i32> {
+    let main: Vec<&Episode> = episodes
+        .iter()
+        .filter(|e| e.item_type == EP_TYPE_MAIN && e.sort.fract() == 0.0)
+        .collect();
+    let sorts: HashSet<i32> = main.iter().map(|e| e.sort as i32).collect();
+    let first_sort = sorts.iter().min().copied()?;
+    let first_mikan = mikan_episodes.iter().min().copied()? as i32;
+
+    let mut candidates: Vec<i32> = vec![0];
+    for e in &main {
+        if let Some(ep) = e.ep.filter(|ep| ep.fract() == 0.0) {
+            candidates.push(e.sort as i32 - ep as i32);
+        }
+    }
+    candidates.push(first_sort - first_mikan);
+
+    let hits = |offset: i32| {
+        mikan_episodes
+            .iter()
+            .filter(|e| sorts.contains(&(**e as i32 + offset)))
+            .count()
+    };
+    let mut best: Option<(i32, usize)> = None;
+    for offset in candidates {
+        let n = hits(offset);
+        // 分数相同时保留先出现的候选，即优先不偏移
+        if n > 0 && best.is_none_or(|(_, b)| n > b) {
+            best = Some((offset, n));
+        }
+    }
+    best.map(|(offset, _)| offset)
+}
+
+/// 季数标记与条目不一致的资源不参与推断，如同一 feed 中混入的前作合集
+pub fn episodes_for_season(items: &[MikanResourceItem], season: Option<u32>) -> Vec<u32> {
+    let season = season.unwrap_or(1);
+    let mut out: Vec<u32> = items
+        .iter()
+        .filter(|it| it.release.season.unwrap_or(season) == season)
+        .filter_map(|it| it.episode)
+        .collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+pub(crate) fn shift(value: u32, offset: i32) -> Option<u32> {
+    u32::try_from(value as i64 + offset as i64).ok()
+}
+
+pub(crate) fn shift_range(range: EpisodeRange, offset: i32) -> Option<String> {
+    let (start, end) = (shift(range.start, offset)?, shift(range.end, offset)?);
+    Some(format!("{}-{}", start, end))
+}
+
+/// 把资源的集数换算为 Bangumi 编号；`release` 保留标题中的原始编号
+pub fn apply(items: &mut [MikanResourceItem], offset: i32) {
+    if offset == 0 {
+        return;
+    }
+    for item in items {
+        item.episode = item.episode.and_then(|e| shift(e, offset));
+        if let Some(range) = item.release.episode_range {
+            item.episode_range = shift_range(range, offset);
+        }
+    }
+}
+
+/// 订阅的偏移；尚未推断时用当前资源推断并保存。未订阅的条目不做换算。
+pub async fn resolve(subject_id: u32, items: &[MikanResourceItem]) -> Result<i32, AppError> {
+    if let Some(offset) = subscriptions::get_episode_offset(subject_id).await? {
+        return Ok(offset);
+    }
+    if items.is_empty() || !subscriptions::has(subject_id).await? {
+        return Ok(0);
+    }
+    let subject = bangumi::fetch_subject(subject_id).await?;
+    let season = SubjectProfile::from_subject(&subject).season;
+    let mikan_episodes = episodes_for_season(items, season);
+    if mikan_episodes.is_empty() {
+        return Ok(0);
+    }
+    let episodes = bangumi::fetch_episodes(
+        subject_id,
+        Some(EP_TYPE_MAIN),
+        Some(EPISODE_PAGE_LIMIT),
+        None,
+    )
+    .await?;
+    let Some(offset) = learn_offset(&episodes.data, &mikan_episodes) else {
+        return Ok(0);
+    };
+    info!(subject_id, offset, "episode offset learned");
+    subscriptions::set_episode_offset(subject_id, Some(offset)).await?;
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mikan::rss::test_item;
+
+    fn episode(sort: f32, ep: Option<f32>) -> Episode {
+        Episode {
+            ep,
+            ..bangumi::test_episode(sort)
+        }
+    }
+
+    #[test]
+    fn same_numbering_needs_no_offset() {
+        let eps: Vec<Episode> = (1..=12)
+            .map(|n| episode(n as f32, Some(n as f32)))
+            .collect();
+        assert_eq!(learn_offset(&eps, &[3, 4, 5]), Some(0));
+    }
+
+    #[test]
+    fn per_season_fansub_numbers_map_to_continuous_sort() {
+        // Bangumi 第二季 sort 13..24，ep 1..12；字幕组从 1 开始
+        let eps: Vec<Episode> = (1..=12)
+            .map(|n| episode((n + 12) as f32, Some(n as f32)))
+            .collect();
+        assert_eq!(learn_offset(&eps, &[1, 2, 3]), Some(12));
+        // 也有字幕组沿用连续编号
+        assert_eq!(learn_offset(&eps, &[13, 14]), Some(0));
+    }
+
+    #[test]
+    fn continuous_fansub_numbers_map_to_restarted_sort() {
+        let eps: Vec<Episode> = (1..=12)
+            .map(|n| episode(n as f32, Some(n as f32)))
+            .collect();
+        assert_eq!(learn_offset(&eps, &[13, 14, 15, 16]), Some(-12));
+    }
+
+    #[test]
+    fn missing_data_is_not_learned() {
+        let eps: Vec<Episode> = (1..=12)
+            .map(|n| episode(n as f32, Some(n as f32)))
+            .collect();
+        assert_eq!(learn_offset(&eps, &[]), None);
+        assert_eq!(learn_offset(&[], &[1, 2]), None);
+        let mut special = episode(1.5, None);
+        special.item_type = 1;
+        assert_eq!(learn_offset(&[special], &[1]), None);
+    }
+
+    #[test]
+    fn other_season_releases_are_ignored() {
+        let items = vec![
+            test_item("[LoliHouse] 药屋少女的呢喃 第二季 - 03 [1080p]"),
+            test_item("[LoliHouse] 药屋少女的呢喃 S2 - 02 [1080p]"),
+            test_item("[Group] 药屋少女的呢喃 第一季 - 24 [1080p]"),
+            test_item("[ANi] 药屋少女的呢喃 - 27 [1080P]"),
+        ];
+        assert_eq!(episodes_for_season(&items, Some(2)), vec![2, 3, 27]);
+        assert_eq!(episodes_for_season(&items, None), vec![24, 27]);
+    }
+
+    #[test]
+    fn apply_shifts_episode_and_range() {
+        let mut items = vec![
+            test_item("[ANi] 药屋少女的呢喃 - 27 [1080P]"),
+            test_item("【字幕组】药屋少女的呢喃 [25-36(全集)]"),
+        ];
+        apply(&mut items, -24);
+        assert_eq!(items[0].episode, Some(3));
+        assert_eq!(items[0].release.episode, Some(27));
+        assert_eq!(items[1].episode_range.as_deref(), Some("1-12"));
+    }
+}
@@ -0,0 +1,277 @@
+use crate::error::AppError;
+use crate::infra::config::MIKAN_HOSTS;
+use crate::infra::http::CLIENT;
+use once_cell::sync::Lazy;
+use reqwest::{RequestBuilder, Response};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+/// 最近一次请求成功的镜像，下次优先尝试
+static ACTIVE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// 规范为 `scheme://host[:port]`；缺少 scheme 时补 https
+pub fn normalize_host(raw: &str) -> Option<String> {
+    let raw = raw.trim().trim_end_matches('/');
+    if raw.is_empty() {
+        return None;
+    }
+    let with_scheme = if raw.contains("://") {
+        raw.to_string()
+    } else {
+        format!("https://{raw}")
+    };
+    let url = reqwest::Url::parse(&with_scheme).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+    Some(url.origin().ascii_serialization())
+}
+
+/// 配置的镜像（为空时用内置列表），健康的镜像排在最前
+fn ordered_hosts(configured: &[String], active: Option<&str>) -> Vec<String> {
+    let mut hosts: Vec<String> = Vec::new();
+    let base: Vec<String> = if configured.is_empty() {
+        MIKAN_HOSTS.iter().map(|h| h.to_string()).collect()
+    } else {
+        configured.to_vec()
+    };
+    for h in base.iter().filter_map(|h| normalize_host(h)) {
+        if !hosts.contains(&h) {
+            hosts.push(h);
+        }
+    }
+    if let Some(pos) = active.and_then(|a| hosts.iter().position(|h| h == a)) {
+        let h = hosts.remove(pos);
+        hosts.insert(0, h);
+    }
+    hosts
+}
+
+/// 内置与配置的全部镜像，用于识别需要改写的链接
+fn known_hosts(configured: &[String]) -> Vec<String> {
+    let mut hosts = ordered_hosts(configured, None);
+    for h in MIKAN_HOSTS.iter().filter_map(|h| normalize_host(h)) {
+        if !hosts.contains(&h) {
+            hosts.push(h);
+        }
+    }
+    hosts
+}
+
+/// RSS 中的链接可能是 http，按主机名判断是否属于已知镜像
+fn is_known(url: &reqwest::Url, known: &[String]) -> bool {
+    known.iter().any(|h| {
+        reqwest::Url::parse(h)
+            .ok()
+            .is_some_and(|u| u.host_str() == url.host_str() && u.port() == url.port())
+    })
+}
+
+fn path_and_query(url: &reqwest::Url) -> String {
+    match url.query() {
+        Some(q) => format!("{}?{}", url.path(), q),
+        None => url.path().to_string(),
+    }
+}
+
+/// 把指向任一已知镜像的链接改写到 `target`；其他链接原样返回
+fn rewrite_host(url: &str, known: &[String], target: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if is_known(&parsed, known) => format!("{}{}", target, path_and_query(&parsed)),
+        _ => url.to_string(),
+    }
+}
+
+fn active() -> Option<String> {
+    ACTIVE.lock().ok().and_then(|g| g.clone())
+}
+
+fn remember(host: &str) {
+    if let Ok(mut g) = ACTIVE.lock() {
+        if g.as_deref() != Some(host) {
+            info!(host, "mikan host selected");
+            *g = Some(host.to_string());
+        }
+    }
+}
+
+/// 配置变更后重新从第一个镜像开始
+pub fn reset_active() {
+    if let Ok(mut g) = ACTIVE.lock() {
+        *g = None;
+    }
+}
+
+async fn configured_hosts() -> Vec<String> {
+    match super::config::get_config().await {
+        Ok(c) => c.hosts,
+        Err(e) => {
+            warn!(error = %e, "mikan config load failed, using default hosts");
+            Vec::new()
+        }
+    }
+}
+
+/// 把 Mikan 链接（种子、剧集页等）改写到当前镜像
+pub struct UrlRewriter {
+    known: Vec<String>,
+    target: String,
+}
+
+impl UrlRewriter {
+    pub fn rewrite(&self, url: &str) -> String {
+        rewrite_host(url, &self.known, &self.target)
+    }
+
+    /// 页面中的站内相对路径（如封面图）补全为当前镜像的链接
+    pub fn absolute(&self, path: &str) -> String {
+        if path.starts_with('/') {
+            format!("{}{}", self.target, path)
+        } else {
+            self.rewrite(path)
+        }
+    }
+}
+
+pub async fn url_rewriter() -> UrlRewriter {
+    let configured = configured_hosts().await;
+    let target = ordered_hosts(&configured, active().as_deref())
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| MIKAN_HOSTS[0].to_string());
+    UrlRewriter {
+        known: known_hosts(&configured),
+        target,
+    }
+}
+
+/// 依次在各镜像上发送 `GET {host}{path}`；连接失败、超时或 5xx 时换下一个。
+/// 全部失败时返回最后一个错误（或最后一个 5xx 响应，交给调用方处理状态码）。
+pub async fn send<F>(path: &str, build: F) -> Result<Response, AppError>
+where
+    F: Fn(RequestBuilder) -> RequestBuilder,
+{
+    let configured = configured_hosts().await;
+    send_with_hosts(ordered_hosts(&configured, active().as_deref()), path, build).await
+}
+
+pub async fn get(path: &str) -> Result<Response, AppError> {
+    send(path, |b| b).await
+}
+
+/// 绝对链接：属于 Mikan 时走镜像选择，其他链接直接请求
+pub async fn get_url(url: &str) -> Result<Response, AppError> {
+    let configured = configured_hosts().await;
+    let known = known_hosts(&configured);
+    if let Ok(parsed) = reqwest::Url::parse(url) {
+        if is_known(&parsed, &known) {
+            return send(&path_and_query(&parsed), |b| b).await;
+        }
+    }
+    Ok(CLIENT.get(url).send().await?)
+}
+
+async fn send_with_hosts<F>(hosts: Vec<String>, path: &str, build: F) -> Result<Response, AppError>
+where
+    F: Fn(RequestBuilder) -> RequestBuilder,
+{
+    let mut last: Option<Result<Response, AppError>> = None;
+    for host in hosts {
+        let url = format!("{}{}", host, path);
+        match build(CLIENT.get(&url)).send().await {
+            Ok(resp) if resp.status().is_server_error() => {
+                warn!(host = %host, status = resp.status().as_u16(), "mikan host error, trying next");
+                last = Some(Ok(resp));
+            }
+            Ok(resp) => {
+                remember(&host);
+                return Ok(resp);
+            }
+            Err(e) if e.is_connect() || e.is_timeout() => {
+                warn!(host = %host, error = %e, "mikan host unreachable, trying next");
+                last = Some(Err(e.into()));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    last.unwrap_or_else(|| Err(AppError::Any("no mikan host configured".into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn normalizes_host_entries() {
+        assert_eq!(
+            normalize_host(" mikanime.tv/ ").as_deref(),
+            Some("https://mikanime.tv")
+        );
+        assert_eq!(
+            normalize_host("http://127.0.0.1:8080/path").as_deref(),
+            Some("http://127.0.0.1:8080")
+        );
+        assert_eq!(normalize_host("ftp://x"), None);
+        assert_eq!(normalize_host("  "), None);
+    }
+
+    #[test]
+    fn active_host_is_tried_first() {
+        let configured = vec!["a.example".to_string(), "https://b.example/".to_string()];
+        assert_eq!(
+            ordered_hosts(&configured, Some("https://b.example")),
+            vec!["https://b.example", "https://a.example"]
+        );
+        assert_eq!(ordered_hosts(&[], None)[0], MIKAN_HOSTS[0]);
+    }
+
+    #[test]
+    fn rewrites_only_known_hosts() {
+        let known = known_hosts(&["https://mirror.example".to_string()]);
+        assert_eq!(
+            rewrite_host(
+                "http://mikanani.me/Download/20240101/abc.torrent",
+                &known,
+                "https://mirror.example"
+            ),
+            "https://mirror.example/Download/20240101/abc.torrent"
+        );
+        assert_eq!(
+            rewrite_host(
+                "https://nyaa.si/download/1.torrent",
+                &known,
+                "https://mirror.example"
+            ),
+            "https://nyaa.si/download/1.torrent"
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_next_host_and_remembers_it() {
+        // 先绑定再释放，得到一个拒绝连接的端口
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let alive = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .await
+                .unwrap();
+        });
+
+        let resp = send_with_hosts(vec![dead, alive.clone()], "/RSS/Bangumi", |b| b)
+            .await
+            .unwrap();
+        assert_eq!(resp.text().await.unwrap(), "ok");
+        assert_eq!(active().as_deref(), Some(alive.as_str()));
+        server.await.unwrap();
+        reset_active();
+    }
+}
@@ -0,0 +1,219 @@
+use crate::error::AppError;
+use crate::models::mikan::{MikanLink, MikanLinkRole, MikanResourceItem, MikanSubgroup};
+use crate::services::mikan::{bangumi_page, map_store, rss};
+use crate::services::sources::archive;
+use std::collections::HashSet;
+use tracing::warn;
+
+/// 一个番组的 feed 及其集数范围
+type Feed = (Option<(u32, u32)>, Vec<MikanResourceItem>);
+
+fn range_of(link: &MikanLink) -> Option<(u32, u32)> {
+    Some((link.episode_start?, link.episode_end?))
+}
+
+/// 资源是否落在集数范围内；按原始编号判断，合集与范围有交集即保留，
+/// 没有编号或是特别篇的资源无法归属，一律保留
+pub fn in_range(item: &MikanResourceItem, range: Option<(u32, u32)>) -> bool {
+    let Some((start, end)) = range else {
+        return true;
+    };
+    let release = &item.release;
+    if release.special {
+        return true;
+    }
+    if let Some(r) = release.episode_range {
+        return r.start <= end && start <= r.end;
+    }
+    release.episode.is_none_or(|ep| start <= ep && ep <= end)
+}
+
+/// 按各自的集数范围过滤后合并多个番组的 feed，按页面链接去重
+pub fn merge(feeds: Vec<Feed>) -> Vec<MikanResourceItem> {
+    let mut seen = HashSet::new();
+    feeds
+        .into_iter()
+        .flat_map(|(range, items)| items.into_iter().filter(move |it| in_range(it, range)))
+        .filter(|it| seen.insert(it.page_url.clone()))
+        .collect()
+}
+
+/// 条目映射到的全部番组的资源。主映射失败时报错，附加番组失败只记录日志。
+pub async fn fetch_items(
+    subject_id: u32,
+    primary: u32,
+    subgroups: &[u32],
+) -> Result<Vec<MikanResourceItem>, AppError> {
+    let mut links = map_store::list_links(subject_id).await?;
+    if !links.iter().any(|l| l.mikan_bangumi_id == primary) {
+        links.insert(
+            0,
+            MikanLink {
+                subject_id,
+                mikan_bangumi_id: primary,
+                role: MikanLinkRole::Primary,
+                episode_start: None,
+                episode_end: None,
+            },
+        );
+    }
+    let mut feeds = Vec::with_capacity(links.len());
+    for link in &links {
+        match rss::fetch_rss_for(link.mikan_bangumi_id, subgroups).await {
+            Ok(mut items) => {
+                for item in &mut items {
+                    item.mikan_bangumi_id = Some(link.mikan_bangumi_id);
+                }
+                feeds.push((range_of(link), items));
+            }
+            Err(e) if link.mikan_bangumi_id != primary => warn!(
+                error = %e,
+                subject_id,
+                mikan_id = link.mikan_bangumi_id,
+                "linked mikan feed failed"
+            ),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(merge(feeds))
+}
+
+/// 全部番组页上的字幕组，按 ID 去重。与 [`fetch_items`] 一样，只有主映射失败时报错。
+pub async fn list_subgroups(subject_id: u32) -> Result<Vec<MikanSubgroup>, AppError> {
+    let mut out: Vec<MikanSubgroup> = Vec::new();
+    for link in map_store::list_links(subject_id).await? {
+        let page = match bangumi_page::fetch_page(link.mikan_bangumi_id).await {
+            Ok(page) => page,
+            Err(e) if link.role != MikanLinkRole::Primary => {
+                warn!(
+                    error = %e,
+                    subject_id,
+                    mikan_id = link.mikan_bangumi_id,
+                    "linked mikan page failed"
+                );
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        for group in page.subgroups {
+            if !out.iter().any(|g| g.id == group.id) {
+                out.push(group);
+            }
+        }
+    }
+    Ok(out)
+}
+
+pub async fn list(subject_id: u32) -> Result<Vec<MikanLink>, AppError> {
+    map_store::list_links(subject_id).await
+}
+
+pub(crate) fn validate_range(
+    start: Option<u32>,
+    end: Option<u32>,
+) -> Result<Option<(u32, u32)>, AppError> {
+    match (start, end) {
+        (None, None) => Ok(None),
+        (Some(s), Some(e)) if s <= e => Ok(Some((s, e))),
+        _ => Err(AppError::Any(format!(
+            "invalid episode range: {start:?}-{end:?}"
+        ))),
+    }
+}
+
+/// 添加或更新附加番组；主映射请使用绑定
+pub async fn add(link: MikanLink) -> Result<(), AppError> {
+    if link.role == MikanLinkRole::Primary {
+        return Err(AppError::Any(
+            "use manual binding to change the primary mapping".into(),
+        ));
+    }
+    validate_range(link.episode_start, link.episode_end)?;
+    if map_store::get(link.subject_id).await? == Some(link.mikan_bangumi_id) {
+        return Err(AppError::Any(format!(
+            "mikan bangumi {} is already the primary mapping",
+            link.mikan_bangumi_id
+        )));
+    }
+    map_store::upsert_link(link).await
+}
+
+pub async fn remove(subject_id: u32, mikan_id: u32) -> Result<(), AppError> {
+    map_store::delete_link(subject_id, mikan_id).await?;
+    archive::prune_unlinked().await
+}
+
+/// 设置某个番组的集数范围，主映射与附加番组均可；两端都为空时清除
+pub async fn set_range(
+    subject_id: u32,
+    mikan_id: u32,
+    episode_start: Option<u32>,
+    episode_end: Option<u32>,
+) -> Result<(), AppError> {
+    let range = validate_range(episode_start, episode_end)?;
+    if map_store::set_primary_range(subject_id, mikan_id, range).await? {
+        return Ok(());
+    }
+    let mut link = map_store::list_links(subject_id)
+        .await?
+        .into_iter()
+        .find(|l| l.mikan_bangumi_id == mikan_id)
+        .ok_or_else(|| {
+            AppError::Any(format!(
+                "subject {subject_id} is not linked to mikan bangumi {mikan_id}"
+            ))
+        })?;
+    link.episode_start = episode_start;
+    link.episode_end = episode_end;
+    map_store::upsert_link(link).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mikan::rss::test_item;
+
+    fn titles(items: &[MikanResourceItem]) -> Vec<&str> {
+        items.iter().map(|it| it.title.as_str()).collect()
+    }
+
+    #[test]
+    fn range_keeps_overlapping_batches_and_unnumbered_items() {
+        let range = Some((13, 24));
+        assert!(in_range(&test_item("[ANi] 咒术回战 - 13 [1080P]"), range));
+        assert!(!in_range(&test_item("[ANi] 咒术回战 - 12 [1080P]"), range));
+        assert!(in_range(&test_item("[Group] 咒术回战 [01-24 合集]"), range));
+        assert!(!in_range(
+            &test_item("[Group] 咒术回战 [01-12 合集]"),
+            range
+        ));
+        assert!(in_range(&test_item("[Group] 咒术回战 [OVA1]"), range));
+        assert!(in_range(&test_item("咒术回战 无集数"), range));
+        assert!(in_range(&test_item("[ANi] 咒术回战 - 01 [1080P]"), None));
+    }
+
+    #[test]
+    fn merge_filters_each_feed_and_dedupes() {
+        let merged_season = vec![
+            test_item("[ANi] 咒术回战 - 12 [1080P]"),
+            test_item("[ANi] 咒术回战 - 13 [1080P]"),
+        ];
+        let split_part = vec![
+            test_item("[ANi] 咒术回战 - 13 [1080P]"),
+            test_item("[ANi] 咒术回战 - 14 [1080P]"),
+        ];
+        let merged = merge(vec![(Some((13, 24)), merged_season), (None, split_part)]);
+        assert_eq!(
+            titles(&merged),
+            vec!["[ANi] 咒术回战 - 13 [1080P]", "[ANi] 咒术回战 - 14 [1080P]"]
+        );
+    }
+
+    #[test]
+    fn ranges_need_both_ends_in_order() {
+        assert_eq!(validate_range(None, None).unwrap(), None);
+        assert_eq!(validate_range(Some(1), Some(12)).unwrap(), Some((1, 12)));
+        assert!(validate_range(Some(12), Some(1)).is_err());
+        assert!(validate_range(Some(1), None).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::error::AppError;
+use crate::infra::time::now_secs;
+use crate::models::mikan::MikanMapAttempt;
+
+/// 所有搜索词都没有搜到番组
+pub const REASON_NO_RESULTS: &str = "no_results";
+/// 搜到了番组，但都对不上该条目
+pub const REASON_NO_MATCH: &str = "no_match";
+/// 只有置信度不足的候选，已保存为建议
+pub const REASON_LOW_CONFIDENCE: &str = "low_confidence";
+/// 请求 Bangumi 或 Mikan 失败
+pub const REASON_ERROR: &str = "error";
+
+const RETRY_BASE_SECS: i64 = 3600;
+const RETRY_MAX_SECS: i64 = 7 * 24 * 3600;
+
+/// 一次自动解析的过程记录
+#[derive(Debug, Clone, Default)]
+pub struct Attempt {
+    pub search_terms: Vec<String>,
+    pub candidates: Vec<u32>,
+    pub reason: &'static str,
+    pub error: Option<String>,
+}
+
+impl Attempt {
+    pub fn saw(&mut self, candidates: &[u32]) {
+        for id in candidates {
+            if !self.candidates.contains(id) {
+                self.candidates.push(*id);
+            }
+        }
+    }
+}
+
+/// 第 `attempts` 次失败后的等待时间：1 小时起逐次翻倍，最长 7 天
+pub fn retry_delay(attempts: u32) -> i64 {
+    let exp = attempts.saturating_sub(1).min(16);
+    (RETRY_BASE_SECS << exp).min(RETRY_MAX_SECS)
+}
+
+/// 是否到了重试时间；从未失败过的条目总是可以解析
+pub async fn is_due(subject_id: u32) -> Result<bool, AppError> {
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| is_due_row(conn, subject_id, now))
+        .await??;
+    Ok(out)
+}
+
+fn is_due_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+    now: i64,
+) -> Result<bool, rusqlite::Error> {
+    let next_retry: Option<i64> = conn
+        .query_row(
+            "SELECT next_retry FROM mikan_map_attempts WHERE bgm_subject_id = ?1",
+            params![subject_id as i64],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(next_retry.is_none_or(|t| t <= now))
+}
+
+pub async fn record_failure(subject_id: u32, attempt: Attempt) -> Result<(), AppError> {
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| record_failure_row(conn, subject_id, &attempt, now))
+        .await??;
+    Ok(())
+}
+
+fn record_failure_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+    attempt: &Attempt,
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    let previous: u32 = conn
+        .query_row(
+            "SELECT attempts FROM mikan_map_attempts WHERE bgm_subject_id = ?1",
+            params![subject_id as i64],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(0);
+    let attempts = previous + 1;
+    conn.execute(
+        "INSERT OR REPLACE INTO mikan_map_attempts(
+            bgm_subject_id, attempts, search_terms, candidates, reason, error, last_attempt, next_retry)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            subject_id as i64,
+            attempts,
+            serde_json::to_string(&attempt.search_terms).unwrap_or_default(),
+            serde_json::to_string(&attempt.candidates).unwrap_or_default(),
+            attempt.reason,
+            attempt.error,
+            now,
+            now + retry_delay(attempts)
+        ],
+    )?;
+    Ok(())
+}
+
+/// 映射成功或手动绑定/解绑后清除记录，下次访问立即解析
+pub async fn clear(subject_id: u32) -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| {
+        conn.execute(
+            "DELETE FROM mikan_map_attempts WHERE bgm_subject_id = ?1",
+            params![subject_id as i64],
+        )
+    })
+    .await??;
+    Ok(())
+}
+
+/// 待映射列表：没有映射的订阅，附带最近一次失败记录
+pub async fn list_unmapped() -> Result<Vec<MikanMapAttempt>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn.interact(|conn| list_unmapped_rows(conn)).await??;
+    Ok(out)
+}
+
+fn list_unmapped_rows(
+    conn: &rusqlite::Connection,
+) -> Result<Vec<MikanMapAttempt>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT s.subject_id, COALESCE(i.name, ''), COALESCE(i.name_cn, ''),
+                COALESCE(a.attempts, 0), a.search_terms, a.candidates, a.reason, a.error,
+                a.last_attempt, a.next_retry
+         FROM subscriptions s
+         LEFT JOIN mikan_map_attempts a ON a.bgm_subject_id = s.subject_id
+         LEFT JOIN subjects_index i ON i.subject_id = s.subject_id
+         WHERE NOT EXISTS (SELECT 1 FROM mikan_bangumi_map m WHERE m.bgm_subject_id = s.subject_id)
+         ORDER BY a.next_retry IS NULL, a.attempts DESC, s.added_at DESC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let terms: Option<String> = row.get(4)?;
+        let candidates: Option<String> = row.get(5)?;
+        Ok(MikanMapAttempt {
+            subject_id: row.get::<_, i64>(0)? as u32,
+            name: row.get(1)?,
+            name_cn: row.get(2)?,
+            attempts: row.get(3)?,
+            search_terms: terms
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
+            candidates: candidates
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
+            reason: row.get(6)?,
+            error: row.get(7)?,
+            last_attempt: row.get(8)?,
+            next_retry: row.get(9)?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_backs_off_exponentially_with_cap() {
+        assert_eq!(retry_delay(1), 3600);
+        assert_eq!(retry_delay(2), 7200);
+        assert_eq!(retry_delay(4), 8 * 3600);
+        assert_eq!(retry_delay(9), RETRY_MAX_SECS);
+        assert_eq!(retry_delay(100), RETRY_MAX_SECS);
+    }
+
+    #[test]
+    fn failures_are_recorded_and_listed_for_unmapped_subscriptions() {
+        let conn = crate::infra::db::test_data_connection();
+        conn.execute_batch(
+            "INSERT INTO subscriptions(subject_id, added_at) VALUES (1, 10), (2, 20), (3, 30);
+             INSERT INTO subjects_index(subject_id, updated_at, name, name_cn, status_code, status_ord)
+             VALUES (1, 0, 'Sousou no Frieren', '葬送的芙莉莲', 0, 0);
+             INSERT INTO mikan_bangumi_map(bgm_subject_id, mikan_bangumi_id, confidence, source, updated_at)
+             VALUES (3, 300, 1.0, 'explicit', 0);",
+        )
+        .unwrap();
+        assert!(is_due_row(&conn, 1, 0).unwrap());
+
+        let mut attempt = Attempt {
+            search_terms: vec!["葬送的芙莉莲".into()],
+            reason: REASON_LOW_CONFIDENCE,
+            ..Default::default()
+        };
+        attempt.saw(&[3141, 3142]);
+        attempt.saw(&[3142]);
+        record_failure_row(&conn, 1, &attempt, 1000).unwrap();
+        record_failure_row(&conn, 1, &attempt, 2000).unwrap();
+        assert!(!is_due_row(&conn, 1, 2000 + 7199).unwrap());
+        assert!(is_due_row(&conn, 1, 2000 + 7200).unwrap());
+
+        let rows = list_unmapped_rows(&conn).unwrap();
+        let ids: Vec<u32> = rows.iter().map(|r| r.subject_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(rows[0].attempts, 2);
+        assert_eq!(rows[0].name_cn, "葬送的芙莉莲");
+        assert_eq!(rows[0].candidates, vec![3141, 3142]);
+        assert_eq!(rows[0].reason.as_deref(), Some(REASON_LOW_CONFIDENCE));
+        assert_eq!(rows[0].next_retry, Some(2000 + 7200));
+        // 从未尝试过
+        assert_eq!(rows[1].attempts, 0);
+        assert_eq!(rows[1].reason, None);
+    }
+}
@@ -0,0 +1,508 @@
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::infra::time::now_secs;
+use crate::models::mikan::{MappingConflictPolicy, MappingImportResult, MikanLinkRole};
+use crate::services::mikan::links;
+use crate::services::subscriptions::repo::set_episode_offset_row;
+use tracing::warn;
+
+/// 映射包格式版本；只能导入不高于该版本的文件
+pub const PACK_VERSION: u32 = 1;
+
+/// 可分享的映射包文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MappingPack {
+    pub version: u32,
+    pub exported_at: i64,
+    pub mappings: Vec<PackEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackEntry {
+    pub subject_id: u32,
+    pub mikan_bangumi_id: u32,
+    pub confidence: f64,
+    pub source: String,
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub episode_start: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub episode_end: Option<u32>,
+    /// 订阅的集数偏移；只会写入本地已订阅的条目
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub episode_offset: Option<i32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<PackLink>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackLink {
+    pub mikan_bangumi_id: u32,
+    pub role: MikanLinkRole,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub episode_start: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub episode_end: Option<u32>,
+}
+
+pub async fn export() -> Result<MappingPack, AppError> {
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn.interact(move |conn| export_rows(conn, now)).await??;
+    Ok(out)
+}
+
+fn export_rows(conn: &rusqlite::Connection, now: i64) -> Result<MappingPack, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT m.bgm_subject_id, m.mikan_bangumi_id, m.confidence, m.source, m.locked,
+                m.episode_start, m.episode_end, s.episode_offset
+         FROM mikan_bangumi_map m
+         LEFT JOIN subscriptions s ON s.subject_id = m.bgm_subject_id
+         ORDER BY m.bgm_subject_id",
+    )?;
+    let mut mappings = stmt
+        .query_map([], |row| {
+            Ok(PackEntry {
+                subject_id: row.get::<_, i64>(0)? as u32,
+                mikan_bangumi_id: row.get::<_, i64>(1)? as u32,
+                confidence: row.get(2)?,
+                source: row.get(3)?,
+                locked: row.get::<_, i64>(4)? != 0,
+                episode_start: row.get(5)?,
+                episode_end: row.get(6)?,
+                episode_offset: row.get(7)?,
+                links: Vec::new(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT mikan_bangumi_id, role, episode_start, episode_end
+         FROM mikan_bangumi_links WHERE bgm_subject_id = ?1
+         ORDER BY created_at, mikan_bangumi_id",
+    )?;
+    for entry in &mut mappings {
+        entry.links = stmt
+            .query_map(params![entry.subject_id as i64], |row| {
+                let role: String = row.get(1)?;
+                Ok(PackLink {
+                    mikan_bangumi_id: row.get::<_, i64>(0)? as u32,
+                    role: MikanLinkRole::parse(&role).unwrap_or(MikanLinkRole::Extra),
+                    episode_start: row.get(2)?,
+                    episode_end: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+    }
+    Ok(MappingPack {
+        version: PACK_VERSION,
+        exported_at: now,
+        mappings,
+    })
+}
+
+/// 冲突时是否采用导入的一方
+fn take_imported(policy: MappingConflictPolicy, local: f64, imported: f64) -> bool {
+    match policy {
+        MappingConflictPolicy::KeepLocal => false,
+        MappingConflictPolicy::TakeImported => true,
+        MappingConflictPolicy::HigherConfidence => imported > local,
+    }
+}
+
+pub async fn import(
+    pack: MappingPack,
+    policy: MappingConflictPolicy,
+) -> Result<MappingImportResult, AppError> {
+    if pack.version == 0 || pack.version > PACK_VERSION {
+        return Err(AppError::Any(format!(
+            "unsupported mapping pack version: {}",
+            pack.version
+        )));
+    }
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| import_rows(conn, &pack, policy, now))
+        .await??;
+    // 被替换的映射留下的旧番组资源
+    crate::services::sources::archive::prune_unlinked().await?;
+    Ok(out)
+}
+
+/// 导入的附加番组，丢弃范围无效或与主映射重复的项
+fn valid_links(entry: &PackEntry) -> Vec<&PackLink> {
+    entry
+        .links
+        .iter()
+        .filter(|l| {
+            l.role != MikanLinkRole::Primary
+                && l.mikan_bangumi_id != 0
+                && l.mikan_bangumi_id != entry.mikan_bangumi_id
+                && links::validate_range(l.episode_start, l.episode_end).is_ok()
+        })
+        .collect()
+}
+
+type LinkKey = (u32, String, Option<u32>, Option<u32>);
+
+fn local_links(tx: &rusqlite::Transaction, sid: i64) -> Result<Vec<LinkKey>, rusqlite::Error> {
+    let mut stmt = tx.prepare(
+        "SELECT mikan_bangumi_id, role, episode_start, episode_end
+         FROM mikan_bangumi_links WHERE bgm_subject_id = ?1
+         ORDER BY mikan_bangumi_id",
+    )?;
+    let rows = stmt
+        .query_map(params![sid], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect();
+    rows
+}
+
+fn import_rows(
+    conn: &mut rusqlite::Connection,
+    pack: &MappingPack,
+    policy: MappingConflictPolicy,
+    now: i64,
+) -> Result<MappingImportResult, rusqlite::Error> {
+    let tx = conn.transaction()?;
+    let mut result = MappingImportResult::default();
+    for entry in &pack.mappings {
+        if entry.subject_id == 0 || entry.mikan_bangumi_id == 0 {
+            continue;
+        }
+        if let Err(e) = links::validate_range(entry.episode_start, entry.episode_end) {
+            warn!(subject_id = entry.subject_id, error = %e, "skipping imported mapping");
+            result.skipped += 1;
+            continue;
+        }
+        let sid = entry.subject_id as i64;
+        let confidence = entry.confidence.clamp(0.0, 1.0);
+        let local: Option<(u32, f64, Option<u32>, Option<u32>)> = tx
+            .query_row(
+                "SELECT mikan_bangumi_id, confidence, episode_start, episode_end
+                 FROM mikan_bangumi_map WHERE bgm_subject_id = ?1",
+                params![sid],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?;
+
+        let mut imported_links: Vec<LinkKey> = valid_links(entry)
+            .into_iter()
+            .map(|l| {
+                (
+                    l.mikan_bangumi_id,
+                    l.role.as_str().to_string(),
+                    l.episode_start,
+                    l.episode_end,
+                )
+            })
+            .collect();
+        imported_links.sort();
+        imported_links.dedup_by_key(|l| l.0);
+        let current_links = local_links(&tx, sid)?;
+        let links_differ = current_links != imported_links;
+
+        // 附加番组与主映射分开比较：主映射相同时也可能只更新附加番组
+        let (take, take_links) = match local {
+            None => {
+                result.added += 1;
+                (true, links_differ)
+            }
+            Some((mid, local_confidence, start, end))
+                if mid == entry.mikan_bangumi_id
+                    && start == entry.episode_start
+                    && end == entry.episode_end =>
+            {
+                let take_links = links_differ
+                    && (current_links.is_empty()
+                        || take_imported(policy, local_confidence, confidence));
+                if take_links {
+                    result.replaced += 1;
+                } else {
+                    result.unchanged += 1;
+                }
+                (false, take_links)
+            }
+            Some((_, local_confidence, _, _)) => {
+                let take = take_imported(policy, local_confidence, confidence);
+                if take {
+                    result.replaced += 1;
+                } else {
+                    result.skipped += 1;
+                }
+                (take, take && links_differ)
+            }
+        };
+
+        if take {
+            tx.execute(
+                "INSERT OR REPLACE INTO mikan_bangumi_map(
+                    bgm_subject_id, mikan_bangumi_id, confidence, source, locked, updated_at, episode_start, episode_end)
+                 VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    sid,
+                    entry.mikan_bangumi_id as i64,
+                    confidence,
+                    entry.source,
+                    entry.locked as i64,
+                    now,
+                    entry.episode_start,
+                    entry.episode_end
+                ],
+            )?;
+            tx.execute(
+                "DELETE FROM mikan_map_suggestions WHERE bgm_subject_id = ?1",
+                params![sid],
+            )?;
+            tx.execute(
+                "DELETE FROM mikan_map_attempts WHERE bgm_subject_id = ?1",
+                params![sid],
+            )?;
+        }
+        // 采用导入一方时以导入的附加番组为准，导入为空即清空本地
+        if take_links {
+            tx.execute(
+                "DELETE FROM mikan_bangumi_links WHERE bgm_subject_id = ?1",
+                params![sid],
+            )?;
+            for (mikan_id, role, start, end) in &imported_links {
+                tx.execute(
+                    "INSERT INTO mikan_bangumi_links(
+                        bgm_subject_id, mikan_bangumi_id, role, episode_start, episode_end, created_at)
+                     VALUES(?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![sid, *mikan_id as i64, role, start, end, now],
+                )?;
+            }
+        }
+
+        // 偏移只写入已订阅的条目：本地尚未推断，或本条映射采用了导入的一方
+        if let Some(offset) = entry.episode_offset {
+            let local_offset: Option<Option<i32>> = tx
+                .query_row(
+                    "SELECT episode_offset FROM subscriptions WHERE subject_id = ?1",
+                    params![sid],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(current) = local_offset {
+                let overwrite = take || policy == MappingConflictPolicy::TakeImported;
+                if current != Some(offset) && (current.is_none() || overwrite) {
+                    set_episode_offset_row(&tx, entry.subject_id, Some(offset))?;
+                    result.offsets += 1;
+                }
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(subject_id: u32, mikan_bangumi_id: u32, confidence: f64) -> PackEntry {
+        PackEntry {
+            subject_id,
+            mikan_bangumi_id,
+            confidence,
+            source: "explicit".into(),
+            locked: false,
+            episode_start: None,
+            episode_end: None,
+            episode_offset: None,
+            links: Vec::new(),
+        }
+    }
+
+    fn mapped(conn: &rusqlite::Connection, sid: u32) -> u32 {
+        conn.query_row(
+            "SELECT mikan_bangumi_id FROM mikan_bangumi_map WHERE bgm_subject_id = ?1",
+            params![sid],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn local_fixture() -> rusqlite::Connection {
+        let conn = crate::infra::db::test_data_connection();
+        conn.execute_batch(
+            "INSERT INTO subscriptions(subject_id, added_at, episode_offset) VALUES (1, 0, NULL), (2, 0, 0);
+             INSERT INTO mikan_bangumi_map(bgm_subject_id, mikan_bangumi_id, confidence, source, locked, updated_at)
+             VALUES (1, 100, 0.9, 'fuzzy', 0, 0), (2, 200, 1.0, 'manual', 1, 0);
+             INSERT INTO mikan_bangumi_links(bgm_subject_id, mikan_bangumi_id, role, created_at)
+             VALUES (2, 201, 'split', 0);
+             INSERT INTO mikan_map_attempts(bgm_subject_id, attempts, search_terms, candidates, reason, last_attempt, next_retry)
+             VALUES (3, 2, '[]', '[]', 'no_results', 0, 0);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn pack(mappings: Vec<PackEntry>) -> MappingPack {
+        MappingPack {
+            version: PACK_VERSION,
+            exported_at: 0,
+            mappings,
+        }
+    }
+
+    #[test]
+    fn export_round_trips_through_json() {
+        let conn = local_fixture();
+        let exported = export_rows(&conn, 50).unwrap();
+        assert_eq!(exported.mappings.len(), 2);
+        assert_eq!(exported.mappings[1].links[0].role, MikanLinkRole::Split);
+        assert_eq!(exported.mappings[1].episode_offset, Some(0));
+        assert!(exported.mappings[1].locked);
+
+        let json = serde_json::to_string(&exported).unwrap();
+        let parsed: MappingPack = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, exported);
+
+        let mut same = local_fixture();
+        let result =
+            import_rows(&mut same, &parsed, MappingConflictPolicy::TakeImported, 60).unwrap();
+        assert_eq!(
+            (result.unchanged, result.replaced, result.offsets),
+            (2, 0, 0)
+        );
+
+        let mut fresh = crate::infra::db::test_data_connection();
+        let result =
+            import_rows(&mut fresh, &parsed, MappingConflictPolicy::KeepLocal, 60).unwrap();
+        assert_eq!(result.added, 2);
+        assert_eq!(export_rows(&fresh, 50).unwrap().mappings[1].links.len(), 1);
+    }
+
+    #[test]
+    fn conflict_policies_decide_which_side_wins() {
+        let mut incoming = entry(1, 101, 0.95);
+        incoming.episode_offset = Some(12);
+        let mut new_subject = entry(3, 300, 0.7);
+        new_subject.episode_offset = Some(5);
+        let imported = pack(vec![incoming, entry(2, 202, 0.8), new_subject]);
+
+        let mut conn = local_fixture();
+        let result =
+            import_rows(&mut conn, &imported, MappingConflictPolicy::KeepLocal, 10).unwrap();
+        assert_eq!((result.added, result.replaced, result.skipped), (1, 0, 2));
+        // 本地未推断偏移的订阅仍会采用导入的偏移；未订阅的条目不写偏移
+        assert_eq!(result.offsets, 1);
+        assert_eq!(mapped(&conn, 1), 100);
+        assert_eq!(mapped(&conn, 3), 300);
+        let attempts: i64 = conn
+            .query_row("SELECT COUNT(*) FROM mikan_map_attempts", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(attempts, 0);
+
+        let mut conn = local_fixture();
+        let result = import_rows(
+            &mut conn,
+            &imported,
+            MappingConflictPolicy::HigherConfidence,
+            10,
+        )
+        .unwrap();
+        assert_eq!((result.replaced, result.skipped), (1, 1));
+        assert_eq!(mapped(&conn, 1), 101);
+        assert_eq!(mapped(&conn, 2), 200);
+
+        let mut conn = local_fixture();
+        let result = import_rows(
+            &mut conn,
+            &imported,
+            MappingConflictPolicy::TakeImported,
+            10,
+        )
+        .unwrap();
+        assert_eq!(result.replaced, 2);
+        assert_eq!(mapped(&conn, 2), 202);
+        // 采用导入的一方时附加番组也以导入为准，导入为空即清空
+        assert!(link_ids(&conn, 2).is_empty());
+    }
+
+    fn link_ids(conn: &rusqlite::Connection, sid: u32) -> Vec<u32> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT mikan_bangumi_id FROM mikan_bangumi_links
+                 WHERE bgm_subject_id = ?1 ORDER BY mikan_bangumi_id",
+            )
+            .unwrap();
+        stmt.query_map(params![sid], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn link(mikan_bangumi_id: u32, start: Option<u32>, end: Option<u32>) -> PackLink {
+        PackLink {
+            mikan_bangumi_id,
+            role: MikanLinkRole::Split,
+            episode_start: start,
+            episode_end: end,
+        }
+    }
+
+    #[test]
+    fn links_import_separately_from_an_unchanged_primary() {
+        let mut same_primary = entry(1, 100, 0.5);
+        same_primary.links = vec![link(102, Some(13), Some(24)), link(103, Some(5), None)];
+        let mut conn = local_fixture();
+        let result = import_rows(
+            &mut conn,
+            &pack(vec![same_primary]),
+            MappingConflictPolicy::KeepLocal,
+            10,
+        )
+        .unwrap();
+        // 本地没有附加番组时直接补上；只有一端的范围被丢弃
+        assert_eq!((result.replaced, result.unchanged), (1, 0));
+        assert_eq!(link_ids(&conn, 1), vec![102]);
+
+        let mut replacing = entry(2, 200, 1.0);
+        replacing.links = vec![link(203, None, None)];
+        let imported = pack(vec![replacing]);
+        let mut conn = local_fixture();
+        let result =
+            import_rows(&mut conn, &imported, MappingConflictPolicy::KeepLocal, 10).unwrap();
+        assert_eq!(result.unchanged, 1);
+        assert_eq!(link_ids(&conn, 2), vec![201]);
+        let result = import_rows(
+            &mut conn,
+            &imported,
+            MappingConflictPolicy::TakeImported,
+            10,
+        )
+        .unwrap();
+        assert_eq!(result.replaced, 1);
+        assert_eq!(link_ids(&conn, 2), vec![203]);
+    }
+
+    #[test]
+    fn invalid_primary_range_is_skipped() {
+        let mut reversed = entry(1, 101, 1.0);
+        reversed.episode_start = Some(24);
+        reversed.episode_end = Some(13);
+        let mut half_open = entry(3, 300, 1.0);
+        half_open.episode_start = Some(13);
+        let mut conn = local_fixture();
+        let result = import_rows(
+            &mut conn,
+            &pack(vec![reversed, half_open]),
+            MappingConflictPolicy::TakeImported,
+            10,
+        )
+        .unwrap();
+        assert_eq!((result.added, result.replaced, result.skipped), (0, 0, 2));
+        assert_eq!(mapped(&conn, 1), 100);
+    }
+}
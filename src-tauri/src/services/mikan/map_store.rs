@@ -1,7 +1,35 @@
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 
 use crate::error::AppError;
 use crate::infra::time::now_secs;
+use crate::models::mikan::{MikanLink, MikanLinkRole, MikanMapEntry, MikanMapSuggestion};
+use crate::services::mikan::resolver::ScoredCandidate;
+
+/// 手动绑定使用的 source 值
+pub const SOURCE_MANUAL: &str = "manual";
+/// 模糊匹配自动写入的 source 值
+pub const SOURCE_FUZZY: &str = "fuzzy";
+
+impl MikanLinkRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MikanLinkRole::Primary => "primary",
+            MikanLinkRole::Split => "split",
+            MikanLinkRole::Merged => "merged",
+            MikanLinkRole::Extra => "extra",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "primary" => Some(MikanLinkRole::Primary),
+            "split" => Some(MikanLinkRole::Split),
+            "merged" => Some(MikanLinkRole::Merged),
+            "extra" => Some(MikanLinkRole::Extra),
+            _ => None,
+        }
+    }
+}
 
 pub async fn get(subject_id: u32) -> Result<Option<u32>, AppError> {
     let pool = crate::infra::db::data_pool()?;
@@ -24,6 +52,26 @@ pub async fn get(subject_id: u32) -> Result<Option<u32>, AppError> {
     Ok(out)
 }
 
+/// 映射及其是否锁定
+pub async fn get_with_lock(subject_id: u32) -> Result<Option<(u32, bool)>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(
+            move |conn| -> Result<Option<(u32, bool)>, rusqlite::Error> {
+                conn.query_row(
+                "SELECT mikan_bangumi_id, locked FROM mikan_bangumi_map WHERE bgm_subject_id = ?1",
+                params![subject_id as i64],
+                |row| Ok((row.get::<_, i64>(0)? as u32, row.get::<_, i64>(1)? != 0)),
+            )
+            .optional()
+            },
+        )
+        .await??;
+    Ok(out)
+}
+
+/// 写入映射。已锁定的行只能被另一次锁定写入（手动绑定）覆盖，自动解析不会改动它。
 pub async fn upsert(
     subject_id: u32,
     mikan_id: u32,
@@ -32,22 +80,381 @@ pub async fn upsert(
     locked: bool,
 ) -> Result<(), AppError> {
     let pool = crate::infra::db::data_pool()?;
-    let sid = subject_id as i64;
-    let mid = mikan_id as i64;
-    let conf = confidence as f64;
     let src = source.to_string();
-    let lck = if locked { 1_i64 } else { 0_i64 };
-    let conn = pool.get().await?;
-    conn
-        .interact(move |conn| -> Result<(), rusqlite::Error> {
-            let now = now_secs();
-            conn.execute(
-                "INSERT INTO mikan_bangumi_map(bgm_subject_id, mikan_bangumi_id, confidence, source, locked, updated_at) VALUES(?1, ?2, ?3, ?4, ?5, ?6)
-                 ON CONFLICT(bgm_subject_id) DO UPDATE SET mikan_bangumi_id=excluded.mikan_bangumi_id, confidence=excluded.confidence, source=excluded.source, locked=excluded.locked, updated_at=excluded.updated_at",
-                params![sid, mid, conf, src, lck, now],
-            )?;
-            Ok(())
+    let conn = pool.get().await?;
+    conn.interact(move |conn| upsert_row(conn, subject_id, mikan_id, confidence, &src, locked))
+        .await??;
+    Ok(())
+}
+
+fn upsert_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+    mikan_id: u32,
+    confidence: f32,
+    source: &str,
+    locked: bool,
+) -> Result<(), rusqlite::Error> {
+    let now = now_secs();
+    conn.execute(
+        "INSERT INTO mikan_bangumi_map(bgm_subject_id, mikan_bangumi_id, confidence, source, locked, updated_at) VALUES(?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(bgm_subject_id) DO UPDATE SET
+             episode_start = CASE WHEN mikan_bangumi_id = excluded.mikan_bangumi_id THEN episode_start END,
+             episode_end = CASE WHEN mikan_bangumi_id = excluded.mikan_bangumi_id THEN episode_end END,
+             mikan_bangumi_id=excluded.mikan_bangumi_id, confidence=excluded.confidence, source=excluded.source, locked=excluded.locked, updated_at=excluded.updated_at
+         WHERE mikan_bangumi_map.locked = 0 OR excluded.locked = 1",
+        params![
+            subject_id as i64,
+            mikan_id as i64,
+            confidence as f64,
+            source,
+            locked as i64,
+            now
+        ],
+    )?;
+    Ok(())
+}
+
+pub async fn delete(subject_id: u32) -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "DELETE FROM mikan_bangumi_map WHERE bgm_subject_id = ?1",
+            params![subject_id as i64],
+        )?;
+        Ok(())
+    })
+    .await??;
+    Ok(())
+}
+
+/// 列出全部映射，并关联订阅索引中的条目名称（未订阅的条目名称为空）
+pub async fn list() -> Result<Vec<MikanMapEntry>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn.interact(|conn| list_rows(conn)).await??;
+    Ok(out)
+}
+
+fn list_rows(conn: &rusqlite::Connection) -> Result<Vec<MikanMapEntry>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT m.bgm_subject_id, m.mikan_bangumi_id, m.confidence, m.source, m.locked, m.updated_at,
+                COALESCE(s.name, ''), COALESCE(s.name_cn, '')
+         FROM mikan_bangumi_map m
+         LEFT JOIN subjects_index s ON s.subject_id = m.bgm_subject_id
+         ORDER BY m.updated_at DESC, m.bgm_subject_id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(MikanMapEntry {
+            subject_id: row.get::<_, i64>(0)? as u32,
+            mikan_bangumi_id: row.get::<_, i64>(1)? as u32,
+            confidence: row.get(2)?,
+            source: row.get(3)?,
+            locked: row.get::<_, i64>(4)? != 0,
+            updated_at: row.get(5)?,
+            name: row.get(6)?,
+            name_cn: row.get(7)?,
         })
+    })?;
+    rows.collect()
+}
+
+/// 条目映射到的全部 Mikan 番组：主映射在前，其余按添加顺序
+pub async fn list_links(subject_id: u32) -> Result<Vec<MikanLink>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| list_link_rows(conn, subject_id))
         .await??;
+    Ok(out)
+}
+
+fn list_link_rows(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+) -> Result<Vec<MikanLink>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT mikan_bangumi_id, 'primary', episode_start, episode_end
+         FROM mikan_bangumi_map WHERE bgm_subject_id = ?1
+         UNION ALL
+         SELECT * FROM (
+             SELECT l.mikan_bangumi_id, l.role, l.episode_start, l.episode_end
+             FROM mikan_bangumi_links l
+             WHERE l.bgm_subject_id = ?1
+               AND NOT EXISTS (SELECT 1 FROM mikan_bangumi_map m
+                               WHERE m.bgm_subject_id = l.bgm_subject_id
+                                 AND m.mikan_bangumi_id = l.mikan_bangumi_id)
+             ORDER BY l.created_at, l.mikan_bangumi_id
+         )",
+    )?;
+    let rows = stmt.query_map(params![subject_id as i64], |row| {
+        let role: String = row.get(1)?;
+        Ok(MikanLink {
+            subject_id,
+            mikan_bangumi_id: row.get::<_, i64>(0)? as u32,
+            role: MikanLinkRole::parse(&role).unwrap_or(MikanLinkRole::Extra),
+            episode_start: row.get(2)?,
+            episode_end: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// 添加或更新一个附加番组
+pub async fn upsert_link(link: MikanLink) -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| upsert_link_row(conn, &link, now_secs()))
+        .await??;
+    Ok(())
+}
+
+fn upsert_link_row(
+    conn: &rusqlite::Connection,
+    link: &MikanLink,
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO mikan_bangumi_links(bgm_subject_id, mikan_bangumi_id, role, episode_start, episode_end, created_at)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(bgm_subject_id, mikan_bangumi_id) DO UPDATE SET
+             role = excluded.role, episode_start = excluded.episode_start, episode_end = excluded.episode_end",
+        params![
+            link.subject_id as i64,
+            link.mikan_bangumi_id as i64,
+            link.role.as_str(),
+            link.episode_start,
+            link.episode_end,
+            now
+        ],
+    )?;
     Ok(())
 }
+
+pub async fn delete_link(subject_id: u32, mikan_id: u32) -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| {
+        conn.execute(
+            "DELETE FROM mikan_bangumi_links WHERE bgm_subject_id = ?1 AND mikan_bangumi_id = ?2",
+            params![subject_id as i64, mikan_id as i64],
+        )
+    })
+    .await??;
+    Ok(())
+}
+
+/// 设置主映射的集数范围；主映射不是该番组时返回 false
+pub async fn set_primary_range(
+    subject_id: u32,
+    mikan_id: u32,
+    range: Option<(u32, u32)>,
+) -> Result<bool, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| set_primary_range_row(conn, subject_id, mikan_id, range))
+        .await??;
+    Ok(out)
+}
+
+fn set_primary_range_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+    mikan_id: u32,
+    range: Option<(u32, u32)>,
+) -> Result<bool, rusqlite::Error> {
+    let changed = conn.execute(
+        "UPDATE mikan_bangumi_map SET episode_start = ?3, episode_end = ?4
+         WHERE bgm_subject_id = ?1 AND mikan_bangumi_id = ?2",
+        params![
+            subject_id as i64,
+            mikan_id as i64,
+            range.map(|r| r.0),
+            range.map(|r| r.1)
+        ],
+    )?;
+    Ok(changed > 0)
+}
+
+/// 用本次解析得到的边缘候选替换该条目之前的建议
+pub async fn replace_suggestions(
+    subject_id: u32,
+    candidates: Vec<ScoredCandidate>,
+) -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| replace_suggestion_rows(conn, subject_id, &candidates))
+        .await??;
+    Ok(())
+}
+
+fn replace_suggestion_rows(
+    conn: &mut rusqlite::Connection,
+    subject_id: u32,
+    candidates: &[ScoredCandidate],
+) -> Result<(), rusqlite::Error> {
+    let now = now_secs();
+    let tx = conn.transaction()?;
+    tx.execute(
+        "DELETE FROM mikan_map_suggestions WHERE bgm_subject_id = ?1",
+        params![subject_id as i64],
+    )?;
+    for c in candidates {
+        tx.execute(
+            "INSERT OR REPLACE INTO mikan_map_suggestions(bgm_subject_id, mikan_bangumi_id, score, title, created_at)
+             VALUES(?1, ?2, ?3, ?4, ?5)",
+            params![subject_id as i64, c.mikan_id as i64, c.score, c.title, now],
+        )?;
+    }
+    tx.commit()
+}
+
+pub async fn clear_suggestions(subject_id: u32) -> Result<(), AppError> {
+    replace_suggestions(subject_id, Vec::new()).await
+}
+
+pub async fn list_suggestions(
+    subject_id: Option<u32>,
+) -> Result<Vec<MikanMapSuggestion>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| list_suggestion_rows(conn, subject_id))
+        .await??;
+    Ok(out)
+}
+
+fn list_suggestion_rows(
+    conn: &rusqlite::Connection,
+    subject_id: Option<u32>,
+) -> Result<Vec<MikanMapSuggestion>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT g.bgm_subject_id, g.mikan_bangumi_id, g.score, g.title,
+                COALESCE(s.name, ''), COALESCE(s.name_cn, '')
+         FROM mikan_map_suggestions g
+         LEFT JOIN subjects_index s ON s.subject_id = g.bgm_subject_id
+         WHERE ?1 IS NULL OR g.bgm_subject_id = ?1
+         ORDER BY g.bgm_subject_id, g.score DESC",
+    )?;
+    let rows = stmt.query_map(params![subject_id.map(|s| s as i64)], |row| {
+        Ok(MikanMapSuggestion {
+            subject_id: row.get::<_, i64>(0)? as u32,
+            mikan_bangumi_id: row.get::<_, i64>(1)? as u32,
+            score: row.get(2)?,
+            title: row.get(3)?,
+            name: row.get(4)?,
+            name_cn: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mikan_id(conn: &rusqlite::Connection, sid: u32) -> (u32, String, bool) {
+        conn.query_row(
+            "SELECT mikan_bangumi_id, source, locked FROM mikan_bangumi_map WHERE bgm_subject_id = ?1",
+            params![sid],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get::<_, i64>(2)? != 0)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn automatic_upsert_never_overwrites_locked_row() {
+        let conn = crate::infra::db::test_data_connection();
+        upsert_row(&conn, 1, 100, 1.0, SOURCE_MANUAL, true).unwrap();
+        upsert_row(&conn, 1, 200, 0.9, "explicit", false).unwrap();
+        assert_eq!(mikan_id(&conn, 1), (100, SOURCE_MANUAL.to_string(), true));
+
+        upsert_row(&conn, 1, 300, 1.0, SOURCE_MANUAL, true).unwrap();
+        assert_eq!(mikan_id(&conn, 1).0, 300);
+    }
+
+    #[test]
+    fn automatic_upsert_replaces_unlocked_row() {
+        let conn = crate::infra::db::test_data_connection();
+        upsert_row(&conn, 1, 100, 0.5, "explicit", false).unwrap();
+        upsert_row(&conn, 1, 200, 0.9, "explicit", false).unwrap();
+        assert_eq!(mikan_id(&conn, 1), (200, "explicit".to_string(), false));
+    }
+
+    #[test]
+    fn lists_rows_with_subject_names() {
+        let conn = crate::infra::db::test_data_connection();
+        conn.execute(
+            "INSERT INTO subjects_index(subject_id, updated_at, name, name_cn, status_code, status_ord)
+             VALUES (1, 0, 'Sousou no Frieren', '葬送的芙莉莲', 0, 0)",
+            [],
+        )
+        .unwrap();
+        upsert_row(&conn, 1, 100, 1.0, SOURCE_MANUAL, true).unwrap();
+        upsert_row(&conn, 2, 200, 1.0, "explicit", false).unwrap();
+
+        let rows = list_rows(&conn).unwrap();
+        let frieren = rows.iter().find(|r| r.subject_id == 1).unwrap();
+        assert_eq!(frieren.name_cn, "葬送的芙莉莲");
+        assert!(frieren.locked);
+        let unknown = rows.iter().find(|r| r.subject_id == 2).unwrap();
+        assert_eq!(unknown.name, "");
+    }
+
+    #[test]
+    fn links_list_primary_first_and_reset_range_on_remap() {
+        let conn = crate::infra::db::test_data_connection();
+        let link = |mikan_bangumi_id, role, range: Option<(u32, u32)>| MikanLink {
+            subject_id: 1,
+            mikan_bangumi_id,
+            role,
+            episode_start: range.map(|r| r.0),
+            episode_end: range.map(|r| r.1),
+        };
+        upsert_row(&conn, 1, 100, 1.0, "explicit", false).unwrap();
+        assert!(set_primary_range_row(&conn, 1, 100, Some((13, 24))).unwrap());
+        assert!(!set_primary_range_row(&conn, 1, 999, None).unwrap());
+        upsert_link_row(&conn, &link(300, MikanLinkRole::Extra, None), 20).unwrap();
+        upsert_link_row(&conn, &link(200, MikanLinkRole::Split, None), 10).unwrap();
+        upsert_link_row(&conn, &link(300, MikanLinkRole::Merged, Some((1, 12))), 30).unwrap();
+
+        assert_eq!(
+            list_link_rows(&conn, 1).unwrap(),
+            vec![
+                link(100, MikanLinkRole::Primary, Some((13, 24))),
+                link(200, MikanLinkRole::Split, None),
+                link(300, MikanLinkRole::Merged, Some((1, 12))),
+            ]
+        );
+
+        // 同一番组重新写入保留范围，换成别的番组则清除
+        upsert_row(&conn, 1, 100, 1.0, "explicit", false).unwrap();
+        assert_eq!(list_link_rows(&conn, 1).unwrap()[0].episode_start, Some(13));
+        upsert_row(&conn, 1, 200, 1.0, SOURCE_MANUAL, true).unwrap();
+        let links = list_link_rows(&conn, 1).unwrap();
+        assert_eq!(links[0], link(200, MikanLinkRole::Primary, None));
+        // 与主映射重复的附加番组不再单独列出
+        assert_eq!(links.len(), 2);
+    }
+
+    #[test]
+    fn suggestions_are_replaced_per_subject() {
+        let mut conn = crate::infra::db::test_data_connection();
+        let candidate = |mikan_id, score| ScoredCandidate {
+            mikan_id,
+            title: format!("title-{mikan_id}"),
+            score,
+        };
+        replace_suggestion_rows(&mut conn, 1, &[candidate(10, 0.7), candidate(11, 0.8)]).unwrap();
+        replace_suggestion_rows(&mut conn, 2, &[candidate(20, 0.65)]).unwrap();
+        replace_suggestion_rows(&mut conn, 1, &[candidate(12, 0.75)]).unwrap();
+
+        let all = list_suggestion_rows(&conn, None).unwrap();
+        assert_eq!(all.len(), 2);
+        let first = list_suggestion_rows(&conn, Some(1)).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].mikan_bangumi_id, 12);
+    }
+}
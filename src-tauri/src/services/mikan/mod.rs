@@ -1,31 +1,12 @@
 use crate::error::AppError;
-use crate::infra::cache;
-use crate::models::mikan::{MikanResourceItem, MikanResourcesResponse};
-use crate::services::bangumi;
+use crate::models::mikan::{
+    MikanMapAttempt, MikanMapEntry, MikanMapSuggestion, MikanResourcesResponse, MikanSubgroup,
+    ReleaseCluster,
+};
+use crate::services::sources::{self, archive};
+use crate::services::{bangumi, subscriptions};
 
 const MAX_CONCURRENCY: usize = 5;
-const NO_MAP_TTL_SECS: i64 = 3600;
-
-use crate::utils::string::{generate_search_terms_by_stripping, normalize_name, replace_and_split};
-use std::collections::HashSet;
-
-/// 生成降级搜索词
-fn generate_search_terms(name: &str) -> Vec<String> {
-    let mut terms_set = HashSet::new();
-
-    // Level 0: 原始标题
-    terms_set.insert(name.to_string());
-
-    // Level 1+: 符号替换 + 逐步剔除
-    let words = replace_and_split(name);
-    let stripped_terms = generate_search_terms_by_stripping(&words);
-    terms_set.extend(stripped_terms);
-
-    // 转换为 Vec 并按长度降序排序（优先尝试更精确的搜索词）
-    let mut terms: Vec<_> = terms_set.into_iter().collect();
-    terms.sort_by_key(|s| std::cmp::Reverse(s.len()));
-    terms
-}
 
 pub async fn ensure_map(sid: u32) -> Result<Option<u32>, AppError> {
     // 检查持久化存储中的映射
@@ -33,89 +14,241 @@ pub async fn ensure_map(sid: u32) -> Result<Option<u32>, AppError> {
         return Ok(Some(mid));
     }
 
-    let no_map_key = format!("mikan:no-map:{}", sid);
-
-    // 检查缓存中是否存在"无映射"标记
-    // TODO:后续应该要实现用户可以手动绑定映射的逻辑，但是该功能需求优先级较低，暂时不做
-    if cache::get_entry(&no_map_key).await?.is_some() {
+    // 上次失败后还没到重试时间（手动绑定或解绑会清除失败记录）
+    if !map_attempts::is_due(sid).await? {
         return Ok(None);
     }
 
-    let subject = bangumi::api::fetch_subject(sid).await?;
-    let normalized_name = normalize_name(&subject.name, &subject.name_cn);
+    let mut attempt = map_attempts::Attempt::default();
+    match resolve_map(sid, &mut attempt).await {
+        Ok(Some(bid)) => {
+            map_attempts::clear(sid).await?;
+            Ok(Some(bid))
+        }
+        Ok(None) => {
+            tracing::info!(sid, reason = attempt.reason, "mikan mapping not found");
+            map_attempts::record_failure(sid, attempt).await?;
+            Ok(None)
+        }
+        Err(e) => {
+            attempt.reason = map_attempts::REASON_ERROR;
+            attempt.error = Some(e.to_string());
+            map_attempts::record_failure(sid, attempt).await?;
+            Err(e)
+        }
+    }
+}
 
-    tracing::debug!("Searching for Mikan ID: {}", normalized_name);
+/// 搜索并解析一次映射，过程记录到 `attempt`
+async fn resolve_map(
+    sid: u32,
+    attempt: &mut map_attempts::Attempt,
+) -> Result<Option<u32>, AppError> {
+    let subject = bangumi::api::fetch_subject(sid).await?;
+    let profile = resolver::SubjectProfile::from_subject(&subject);
 
-    // 生成降级搜索词（原始 → 符号替换 → 逐步剔除）
-    let search_terms = generate_search_terms(&normalized_name);
-    tracing::debug!(
-        "Generated {} search terms: {:?}",
-        search_terms.len(),
-        search_terms
+    // 中文名、原名、别名与罗马字，按估计精确度排列，每个名称再逐步剔除末尾的词
+    let names = search_terms::subject_names(
+        &subject.name,
+        &subject.name_cn,
+        subject.infobox.as_deref().unwrap_or_default(),
     );
-
-    // 依次尝试每一级，直到 resolver 成功才停止
-    let mut resolved_id = None;
-    for term in &search_terms {
+    let terms = search_terms::build(&names);
+    tracing::debug!("Generated {} search terms: {:?}", terms.len(), terms);
+
+    // 依次尝试每一级，直到得到精确或高置信度的结果；边缘候选累积为建议
+    let mut resolved = None;
+    let mut suggestions: Vec<resolver::ScoredCandidate> = Vec::new();
+    for term in &terms {
+        attempt.search_terms.push(term.clone());
         let candidates = search::search_candidates(term).await?;
         tracing::debug!("Search '{}' found {} candidates", term, candidates.len());
 
-        if !candidates.is_empty() {
-            match resolver::resolve_candidates(sid, candidates, MAX_CONCURRENCY).await? {
-                Some(id) => {
-                    tracing::info!("Successfully mapped to Mikan ID: {}", id);
-                    resolved_id = Some(id);
-                    break;
-                }
-                None => {
-                    tracing::debug!("Candidates found but none matched, trying next...");
-                    continue;
+        if candidates.is_empty() {
+            continue;
+        }
+        attempt.saw(&candidates);
+        match resolver::resolve_candidates(sid, &profile, candidates, MAX_CONCURRENCY).await? {
+            Some(resolver::Resolution::Exact(id)) => {
+                tracing::info!("Successfully mapped to Mikan ID: {}", id);
+                resolved = Some((id, 1.0, "explicit"));
+                break;
+            }
+            Some(resolver::Resolution::Confident(c)) => {
+                tracing::info!(
+                    "Fuzzy mapped to Mikan ID: {} (score {:.2})",
+                    c.mikan_id,
+                    c.score
+                );
+                resolved = Some((c.mikan_id, c.score, map_store::SOURCE_FUZZY));
+                break;
+            }
+            Some(resolver::Resolution::Suggestions(list)) => {
+                for c in list {
+                    if !suggestions.iter().any(|s| s.mikan_id == c.mikan_id) {
+                        suggestions.push(c);
+                    }
                 }
             }
+            None => {
+                tracing::debug!("Candidates found but none matched, trying next...");
+            }
         }
     }
 
-    match resolved_id {
-        Some(bid) => {
-            // 映射成功，保存到持久化存储
-            map_store::upsert(sid, bid, 1.0, "explicit", false).await?;
+    match resolved {
+        Some((bid, confidence, source)) => {
+            // 映射成功，保存到持久化存储（不会覆盖已锁定的手动绑定）
+            map_store::upsert(sid, bid, confidence as f32, source, false).await?;
+            map_store::clear_suggestions(sid).await?;
             Ok(Some(bid))
         }
         None => {
-            // 映射失败或没有找到候选，设置"无映射"标记
-            cache::set_entry(&no_map_key, "1".to_string(), None, None, NO_MAP_TTL_SECS).await?;
+            // 映射失败：保存边缘候选供用户确认
+            attempt.reason = if !suggestions.is_empty() {
+                map_attempts::REASON_LOW_CONFIDENCE
+            } else if attempt.candidates.is_empty() {
+                map_attempts::REASON_NO_RESULTS
+            } else {
+                map_attempts::REASON_NO_MATCH
+            };
+            if !suggestions.is_empty() {
+                suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+                map_store::replace_suggestions(sid, suggestions).await?;
+            }
             Ok(None)
         }
     }
 }
 
+pub async fn list_mappings() -> Result<Vec<MikanMapEntry>, AppError> {
+    map_store::list().await
+}
+
+/// 尚未映射的订阅及失败原因
+pub async fn list_unmapped() -> Result<Vec<MikanMapAttempt>, AppError> {
+    map_attempts::list_unmapped().await
+}
+
+/// 手动绑定：写入锁定的映射，自动解析不再覆盖
+pub async fn bind_manual(sid: u32, mikan_id: u32) -> Result<(), AppError> {
+    map_store::upsert(sid, mikan_id, 1.0, map_store::SOURCE_MANUAL, true).await?;
+    map_store::clear_suggestions(sid).await?;
+    map_attempts::clear(sid).await?;
+    archive::prune_unlinked().await?;
+    tracing::info!(sid, mikan_id, "manually bound mikan mapping");
+    Ok(())
+}
+
+/// 待确认的模糊匹配候选；`sid` 为空时列出全部
+pub async fn list_suggestions(sid: Option<u32>) -> Result<Vec<MikanMapSuggestion>, AppError> {
+    map_store::list_suggestions(sid).await
+}
+
+/// 解除绑定（包括锁定的行），下次访问时重新自动解析
+pub async fn unbind(sid: u32) -> Result<(), AppError> {
+    map_store::delete(sid).await?;
+    map_attempts::clear(sid).await?;
+    archive::prune_unlinked().await?;
+    Ok(())
+}
+
+/// 丢弃现有映射与失败记录后立即重新解析
+pub async fn re_resolve(sid: u32) -> Result<Option<u32>, AppError> {
+    unbind(sid).await?;
+    ensure_map(sid).await
+}
+
+/// 条目映射到的各 Mikan 番组页上的字幕组列表；未映射时为空
+pub async fn list_subgroups(subject_id: u32) -> Result<Vec<MikanSubgroup>, AppError> {
+    match ensure_map(subject_id).await? {
+        Some(_) => links::list_subgroups(subject_id).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 条目的资源列表：Mikan 映射到的各番组 feed，加上其他已启用站点的搜索结果
 pub async fn get_mikan_resources(subject_id: u32) -> Result<MikanResourcesResponse, AppError> {
+    let settings = config::get_config()
+        .await
+        .map(|c| c.sources)
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "mikan config load failed, using default sources");
+            Default::default()
+        });
     let mut mid = map_store::get(subject_id).await?;
-    if mid.is_none() {
+    if mid.is_none() && settings.mikan {
         mid = ensure_map(subject_id).await?;
     }
-    if let Some(mikan_id) = mid {
-        let items: Vec<MikanResourceItem> = rss::fetch_rss(mikan_id).await?;
-        Ok(MikanResourcesResponse {
-            mapped: true,
-            mikan_bangumi_id: Some(mikan_id),
-            items,
-        })
-    } else {
-        Ok(MikanResourcesResponse {
-            mapped: false,
-            mikan_bangumi_id: None,
-            items: Vec::new(),
-        })
+    let mikan = match mid {
+        // 订阅固定了字幕组时只看这些字幕组的 feed
+        Some(mikan_id) => Some((mikan_id, subscriptions::get_subgroups(subject_id).await?)),
+        None => None,
+    };
+    let pinned = mikan.as_ref().is_some_and(|(_, groups)| !groups.is_empty());
+    let live = sources::collect(subject_id, mikan, &settings).await?;
+    // 存档保存原始集数，需在换算偏移前写入
+    if let Err(e) = archive::record(subject_id, &live).await {
+        tracing::warn!(error = %e, subject_id, "resource archive write failed");
     }
+    let archived = archive::list(subject_id).await.unwrap_or_else(|e| {
+        tracing::warn!(error = %e, subject_id, "resource archive read failed");
+        Vec::new()
+    });
+    let mut items = archive::merge_archived(live, archived, &settings, pinned);
+    let offset = episode_offset::resolve(subject_id, &items)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, subject_id, "episode offset unavailable");
+            0
+        });
+    episode_offset::apply(&mut items, offset);
+    Ok(MikanResourcesResponse {
+        // Mikan 来源关闭时映射不参与结果
+        mapped: settings.mikan && mid.is_some(),
+        mikan_bangumi_id: mid,
+        items,
+    })
+}
+
+/// 按集分组并按用户偏好排序的资源
+pub async fn get_release_clusters(subject_id: u32) -> Result<Vec<ReleaseCluster>, AppError> {
+    let prefs = config::get_config().await?.preferences;
+    let resources = get_mikan_resources(subject_id).await?;
+    Ok(ranking::cluster_releases(resources.items, &prefs))
+}
+
+pub async fn get_release_preferences() -> Result<config::ReleasePreferences, AppError> {
+    Ok(config::get_config().await?.preferences)
+}
+
+pub async fn set_release_preferences(prefs: config::ReleasePreferences) -> Result<(), AppError> {
+    let mut current = config::get_config().await?;
+    current.preferences = prefs;
+    config::save_config(current).await
 }
 
+pub use my_bangumi::spawn_sync_worker;
 pub use preheat::spawn_preheat_worker;
 
+pub mod config;
+pub mod episode_offset;
+pub mod host;
+pub mod links;
+pub mod my_bangumi;
 pub mod preheat;
+pub mod ranking;
+pub mod release_notice;
 pub mod resolver;
+pub mod seen;
+pub mod watch;
 
 pub mod bangumi_page;
+pub mod catalog;
+pub mod map_attempts;
+pub mod map_pack;
 pub mod map_store;
 pub mod rss;
 pub mod search;
+pub mod search_terms;
+pub mod watch_store;
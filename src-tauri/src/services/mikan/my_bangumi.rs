@@ -0,0 +1,294 @@
+use crate::error::AppError;
+use crate::infra::cache;
+use crate::infra::time::now_secs;
+use crate::models::mikan::{MyBangumiImportResult, MyBangumiPreviewItem};
+use crate::services::mikan::{bangumi_page, config, host, map_store};
+use crate::services::subscriptions;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::RequestBuilder;
+use rusqlite::params;
+use std::collections::HashSet;
+use std::str::FromStr;
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+
+const SYNC_INTERVAL_SECS: u64 = 6 * 3600;
+/// 剧集页与番组的对应关系不会变化，缓存较久
+const EPISODE_TTL_SECS: i64 = 30 * 24 * 3600;
+
+const FEED_PATH: &str = "/RSS/MyBangumi";
+
+static RE_BANGUMI_LINK: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"href="[^"]*/Home/Bangumi/(\d+)"#).unwrap());
+
+fn with_token(builder: RequestBuilder, token: &str) -> RequestBuilder {
+    builder.query(&[("token", token.trim())])
+}
+
+/// 「我的番组」feed 中每个条目的剧集页链接（按出现顺序去重）
+fn parse_episode_links(xml: &str) -> Result<Vec<String>, AppError> {
+    let channel =
+        rss::Channel::from_str(xml).map_err(|e| AppError::Any(format!("mikan rss: {e}")))?;
+    let mut out: Vec<String> = Vec::new();
+    for link in channel.items().iter().filter_map(|it| it.link()) {
+        if !out.iter().any(|l| l == link) {
+            out.push(link.to_string());
+        }
+    }
+    Ok(out)
+}
+
+/// 剧集页上指向所属番组的链接
+fn parse_episode_bangumi_id(html: &str) -> Option<u32> {
+    RE_BANGUMI_LINK
+        .captures(html)
+        .and_then(|c| c.get(1)?.as_str().parse().ok())
+}
+
+async fn episode_bangumi_id(episode_url: &str) -> Result<Option<u32>, AppError> {
+    // 按剧集 hash 缓存，不同镜像的链接共用
+    let episode = episode_url.rsplit('/').next().unwrap_or(episode_url);
+    let key = format!("mikan:episode-bangumi:{}", episode);
+    if let Some((value, _, _)) = cache::get_entry(&key).await? {
+        return Ok(value.parse().ok());
+    }
+    crate::infra::http::wait_api_limit().await;
+    let resp = host::get_url(episode_url).await?;
+    resp.error_for_status_ref()?;
+    let html = resp.text().await?;
+    let id = parse_episode_bangumi_id(&html);
+    if let Some(id) = id {
+        cache::set_entry(&key, id.to_string(), None, None, EPISODE_TTL_SECS).await?;
+    }
+    Ok(id)
+}
+
+/// 拉取 feed 并把其中的番组解析为 Bangumi 条目，不写入任何数据
+pub async fn preview(token: &str) -> Result<Vec<MyBangumiPreviewItem>, AppError> {
+    let resp = host::send(FEED_PATH, |b| with_token(b, token)).await?;
+    resp.error_for_status_ref()?;
+    let xml = resp.text().await?;
+
+    let mut mikan_ids: Vec<u32> = Vec::new();
+    for link in parse_episode_links(&xml)? {
+        match episode_bangumi_id(&link).await {
+            Ok(Some(id)) if !mikan_ids.contains(&id) => mikan_ids.push(id),
+            Ok(_) => {}
+            Err(e) => warn!(error = %e, link = %link, "mikan episode page fetch failed"),
+        }
+    }
+
+    let subscribed = subscriptions::list_ids().await?;
+    let mut out = Vec::with_capacity(mikan_ids.len());
+    for mikan_id in mikan_ids {
+        let page = match bangumi_page::fetch_page(mikan_id).await {
+            Ok(page) => page,
+            Err(e) => {
+                warn!(error = %e, mikan_id, "mikan bangumi page fetch failed");
+                continue;
+            }
+        };
+        let mapped_mikan_id = match page.subject_id {
+            Some(sid) => map_store::get(sid).await?,
+            None => None,
+        };
+        out.push(MyBangumiPreviewItem {
+            mikan_bangumi_id: mikan_id,
+            title: page.title,
+            subject_id: page.subject_id,
+            subscribed: page.subject_id.is_some_and(|sid| subscribed.contains(&sid)),
+            mapped_mikan_id,
+        });
+    }
+    Ok(out)
+}
+
+/// 按预览结果创建订阅与锁定映射；`selected` 为空时导入全部。
+/// 已手动绑定到其他番组的条目不会被覆盖，记为冲突。
+pub async fn import(
+    token: &str,
+    selected: Option<Vec<u32>>,
+) -> Result<MyBangumiImportResult, AppError> {
+    let items = preview(token).await?;
+    let previewed: Vec<u32> = items.iter().map(|it| it.mikan_bangumi_id).collect();
+    let items = items
+        .into_iter()
+        .filter(|it| {
+            selected
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&it.mikan_bangumi_id))
+        })
+        .collect();
+    let result = import_items(items).await?;
+    // 预览过的番组都视为已处理，包括未勾选的，定时同步不再自动导入
+    mark_seen(previewed).await?;
+    Ok(result)
+}
+
+/// 定时同步：只导入 feed 中新出现的番组，用户取消的订阅不会被重新添加
+async fn sync(token: &str) -> Result<MyBangumiImportResult, AppError> {
+    let items = preview(token).await?;
+    let seen = seen_ids().await?;
+    let previewed: Vec<u32> = items.iter().map(|it| it.mikan_bangumi_id).collect();
+    let new = items
+        .into_iter()
+        .filter(|it| !seen.contains(&it.mikan_bangumi_id))
+        .collect();
+    let result = import_items(new).await?;
+    mark_seen(previewed).await?;
+    Ok(result)
+}
+
+async fn import_items(items: Vec<MyBangumiPreviewItem>) -> Result<MyBangumiImportResult, AppError> {
+    let mut result = MyBangumiImportResult::default();
+    for item in items {
+        let Some(sid) = item.subject_id else {
+            result.unresolved.push(item.mikan_bangumi_id);
+            continue;
+        };
+        match map_store::get_with_lock(sid).await? {
+            Some((mid, true)) if mid != item.mikan_bangumi_id => {
+                result.conflicts.push(sid);
+                continue;
+            }
+            Some((mid, _)) if mid == item.mikan_bangumi_id => {}
+            _ => {
+                super::bind_manual(sid, item.mikan_bangumi_id).await?;
+                result.mapped += 1;
+            }
+        }
+        if !item.subscribed && subscriptions::ensure_subscribed(sid).await? {
+            result.subscribed += 1;
+        }
+    }
+    info!(
+        subscribed = result.subscribed,
+        mapped = result.mapped,
+        unresolved = result.unresolved.len(),
+        conflicts = result.conflicts.len(),
+        "mikan my bangumi import complete"
+    );
+    Ok(result)
+}
+
+async fn seen_ids() -> Result<HashSet<u32>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn.interact(|conn| seen_ids_rows(conn)).await??;
+    Ok(out)
+}
+
+fn seen_ids_rows(conn: &rusqlite::Connection) -> Result<HashSet<u32>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT mikan_bangumi_id FROM mikan_my_bangumi_seen")?;
+    let rows = stmt.query_map([], |row| Ok(row.get::<_, i64>(0)? as u32))?;
+    rows.collect()
+}
+
+async fn mark_seen(ids: Vec<u32>) -> Result<(), AppError> {
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| mark_seen_rows(conn, &ids, now))
+        .await??;
+    Ok(())
+}
+
+fn mark_seen_rows(
+    conn: &rusqlite::Connection,
+    ids: &[u32],
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO mikan_my_bangumi_seen(mikan_bangumi_id, seen_at) VALUES (?1, ?2)",
+    )?;
+    for id in ids {
+        stmt.execute(params![*id as i64, now])?;
+    }
+    Ok(())
+}
+
+pub fn spawn_sync_worker() {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match config::get_config().await {
+                Ok(config::MikanConfig {
+                    my_bangumi_token: Some(token),
+                    my_bangumi_sync: true,
+                    ..
+                }) if !token.trim().is_empty() => {
+                    if let Err(error) = sync(&token).await {
+                        warn!(error = %error, "mikan my bangumi sync failed");
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => warn!(error = %error, "mikan config load failed"),
+            }
+            sleep(Duration::from_secs(SYNC_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0"><channel>
+<title>Mikan Project - 我的番组</title>
+<link>http://mikanani.me/RSS/MyBangumi?token=abc</link>
+<description>Mikan Project - 我的番组</description>
+<item>
+  <guid isPermaLink="false">[ANi] 葬送的芙莉莲 - 27 [1080P]</guid>
+  <link>https://mikanani.me/Home/Episode/aaa</link>
+  <title>[ANi] 葬送的芙莉莲 - 27 [1080P]</title>
+  <enclosure type="application/x-bittorrent" length="1" url="https://mikanani.me/Download/x/aaa.torrent" />
+</item>
+<item>
+  <link>https://mikanani.me/Home/Episode/bbb</link>
+  <title>[LoliHouse] 药屋少女的呢喃 - 03</title>
+</item>
+<item>
+  <link>https://mikanani.me/Home/Episode/aaa</link>
+  <title>duplicate</title>
+</item>
+</channel></rss>"#;
+
+    #[test]
+    fn parses_unique_episode_links() {
+        assert_eq!(
+            parse_episode_links(FEED).unwrap(),
+            vec![
+                "https://mikanani.me/Home/Episode/aaa",
+                "https://mikanani.me/Home/Episode/bbb"
+            ]
+        );
+        assert!(parse_episode_links("not xml").is_err());
+    }
+
+    #[test]
+    fn finds_bangumi_id_on_episode_page() {
+        let html = r#"<p class="bangumi-title"><a class="w-other-c" style="color:#555" href="/Home/Bangumi/3141">葬送的芙莉莲</a></p>"#;
+        assert_eq!(parse_episode_bangumi_id(html), Some(3141));
+        assert_eq!(parse_episode_bangumi_id("<p>nothing</p>"), None);
+    }
+
+    #[test]
+    fn token_is_query_encoded() {
+        let url = format!("{}{}", crate::infra::config::MIKAN_HOSTS[0], FEED_PATH);
+        let request = with_token(crate::infra::http::CLIENT.get(url), " a+b/c= ")
+            .build()
+            .unwrap();
+        assert_eq!(request.url().path(), "/RSS/MyBangumi");
+        assert_eq!(request.url().query(), Some("token=a%2Bb%2Fc%3D"));
+    }
+
+    #[test]
+    fn seen_ids_are_recorded_once() {
+        let conn = crate::infra::db::test_data_connection();
+        mark_seen_rows(&conn, &[3141, 3142], 10).unwrap();
+        mark_seen_rows(&conn, &[3142, 3143], 20).unwrap();
+        let seen = seen_ids_rows(&conn).unwrap();
+        assert_eq!(seen, HashSet::from([3141, 3142, 3143]));
+    }
+}
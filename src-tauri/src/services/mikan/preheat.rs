@@ -1,8 +1,12 @@
 use crate::error::AppError;
-use crate::services::mikan::map_store;
-use crate::services::mikan::rss;
-use crate::services::subscriptions::{self, update_last_seen_ep};
+use crate::infra::notification;
+use crate::models::mikan::EpisodeReleaseNotice;
+use crate::services::mikan::seen::{self, EpisodeKey};
+use crate::services::mikan::{config, episode_offset, links, release_notice, watch};
+use crate::services::sources::archive;
+use crate::services::subscriptions;
 use crate::utils::round_robin::{next_offset, round_robin_take};
+use std::future::Future;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::Semaphore;
@@ -13,6 +17,8 @@ use tracing::{debug, info, warn};
 const PREHEAT_CONCURRENCY: usize = 4;
 const PREHEAT_LIMIT: usize = 30;
 const PREHEAT_INTERVAL_SECS: u64 = 900;
+/// 通知正文最多列出的推荐资源数，其余只在事件中提供
+const NOTIFY_RELEASE_LINES: usize = 3;
 static PREHEAT_OFFSET: AtomicUsize = AtomicUsize::new(0);
 
 enum PreheatState {
@@ -42,6 +48,16 @@ impl PreheatStats {
     }
 }
 
+/// 提醒被通知适配器接受后才提交已见集数；提醒失败时这些集数保持未见，下轮重新提醒
+async fn notify_then_commit(
+    send: impl Future<Output = Result<bool, AppError>>,
+    commit: impl Future<Output = Result<(), AppError>>,
+) -> Result<bool, AppError> {
+    let notified = send.await?;
+    commit.await?;
+    Ok(notified)
+}
+
 pub fn spawn_preheat_worker() {
     tauri::async_runtime::spawn(async move {
         loop {
@@ -59,6 +75,10 @@ pub fn spawn_preheat_worker() {
                 ),
                 Err(error) => warn!(error = %error, "mikan preheat failed"),
             }
+            match watch::poll_once().await {
+                Ok(matched) => info!(matched, "resource watch poll complete"),
+                Err(error) => warn!(error = %error, "resource watch poll failed"),
+            }
             sleep(Duration::from_secs(PREHEAT_INTERVAL_SECS)).await;
         }
     });
@@ -94,37 +114,80 @@ pub(super) async fn preheat_once() -> Result<PreheatStats, AppError> {
                         .acquire_owned()
                         .await
                         .map_err(|error| AppError::Any(error.to_string()))?;
-                    let Some(mid) = map_store::get(sid).await? else {
+                    // 未映射的订阅到了退避时间会在这里重新解析
+                    let Some(mid) = super::ensure_map(sid).await? else {
                         debug!(subject_id = sid, "mikan preheat skip: no mapping");
                         return Ok(PreheatState::NoMap);
                     };
-                    let items = rss::fetch_rss(mid).await?;
-                    let new_max_ep = items
-                        .iter()
-                        .filter_map(|item| item.episode)
-                        .max()
-                        .unwrap_or(0);
-                    if new_max_ep <= last_seen_ep {
+                    let subgroups = subscriptions::get_subgroups(sid).await?;
+                    let items = links::fetch_items(sid, mid, &subgroups).await?;
+                    if let Err(error) = archive::record(sid, &items).await {
+                        warn!(subject_id = sid, error = %error, "resource archive write failed");
+                    }
+                    // 与资源列表一致，Bangumi 不可用时按不偏移处理，不影响提醒与已见记录
+                    let offset = match episode_offset::resolve(sid, &items).await {
+                        Ok(offset) => offset,
+                        Err(error) => {
+                            warn!(subject_id = sid, error = %error, "episode offset unavailable");
+                            0
+                        }
+                    };
+                    let keys = seen::episode_keys(&items);
+                    let (backfill, new_keys) =
+                        subscriptions::unseen_keys(sid, keys.iter().map(|k| k.encode()).collect())
+                            .await?;
+                    if new_keys.is_empty() {
+                        if backfill {
+                            subscriptions::mark_seen(sid, Vec::new()).await?;
+                        }
                         return Ok(PreheatState::Unchanged);
                     }
+                    let new: Vec<EpisodeKey> = keys
+                        .into_iter()
+                        .filter(|k| new_keys.contains(&k.encode()))
+                        .collect();
+                    let to_notify = seen::to_notify(&new, backfill, last_seen_ep, offset);
 
                     info!(
                         subject_id = sid,
-                        old_ep = last_seen_ep,
-                        new_ep = new_max_ep,
+                        new = new.len(),
+                        backfill,
                         notify,
-                        "new episode detected"
+                        "new episodes detected"
                     );
-                    if notify {
+                    let send = async {
+                        if !notify || to_notify.is_empty() {
+                            return Ok(false);
+                        }
                         let name = name_opt.ok_or_else(|| {
                             AppError::Any(format!(
                                 "anime name not found for notification: subject_id={sid}"
                             ))
                         })?;
-                        crate::infra::notification::notify_new_episode(&name, new_max_ep)?;
-                        notified = true;
-                    }
-                    update_last_seen_ep(sid, new_max_ep).await?;
+                        let prefs = config::get_config().await?.preferences;
+                        let labels: Vec<String> =
+                            to_notify.iter().map(|k| k.label(offset)).collect();
+                        let notices: Vec<EpisodeReleaseNotice> = to_notify
+                            .iter()
+                            .filter_map(|k| {
+                                release_notice::build(sid, &name, *k, offset, &items, &prefs)
+                            })
+                            .collect();
+                        let releases: Vec<String> = notices
+                            .iter()
+                            .take(NOTIFY_RELEASE_LINES)
+                            .map(|n| format!("{}：{}", n.label, release_notice::summary(n)))
+                            .collect();
+                        notification::notify_new_episodes(&name, &labels, &releases)?;
+                        for notice in &notices {
+                            if let Err(error) = notification::emit_episode_release(notice) {
+                                warn!(subject_id = sid, error = %error, "episode release event failed");
+                            }
+                        }
+                        Ok(true)
+                    };
+                    notified =
+                        notify_then_commit(send, subscriptions::mark_seen(sid, new_keys)).await?;
                     Ok(PreheatState::Updated)
                 }
                 .await;
@@ -168,4 +231,26 @@ mod tests {
             (1, 1, 1, 1)
         );
     }
+
+    #[tokio::test]
+    async fn failed_notification_leaves_keys_unseen() {
+        let committed = std::sync::atomic::AtomicBool::new(false);
+        let commit = || async {
+            committed.store(true, Ordering::Relaxed);
+            Ok(())
+        };
+
+        let failed = notify_then_commit(
+            async { Err(AppError::Any("notifier unavailable".into())) },
+            commit(),
+        )
+        .await;
+        assert!(failed.is_err());
+        assert!(!committed.load(Ordering::Relaxed));
+
+        assert!(notify_then_commit(async { Ok(true) }, commit())
+            .await
+            .unwrap());
+        assert!(committed.load(Ordering::Relaxed));
+    }
 }
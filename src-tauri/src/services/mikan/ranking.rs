@@ -0,0 +1,268 @@
+use crate::models::mikan::{MikanResourceItem, RankedRelease, ReleaseCluster};
+use crate::services::mikan::config::ReleasePreferences;
+
+const GROUP_WEIGHT: f64 = 0.35;
+const RESOLUTION_WEIGHT: f64 = 0.25;
+const LANGUAGE_WEIGHT: f64 = 0.2;
+const SUBTITLE_TYPE_WEIGHT: f64 = 0.1;
+const SIZE_WEIGHT: f64 = 0.1;
+/// 缺少信息时的中间分，不奖励也不惩罚
+const UNKNOWN_SCORE: f64 = 0.5;
+/// 单集小于该体积多半是失效或错误的种子
+const MIN_EPISODE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// 单集按集数分组，合集按范围分组
+type ClusterKey = (Option<u32>, Option<String>);
+
+/// 联合字幕组（`A&B`）拆开后逐个比较，忽略大小写
+fn group_names(item: &MikanResourceItem) -> Vec<String> {
+    item.group
+        .as_deref()
+        .map(|g| {
+            g.split(['&', '＆'])
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn contains_group(list: &[String], names: &[String]) -> Option<usize> {
+    list.iter()
+        .position(|g| names.contains(&g.trim().to_lowercase()))
+}
+
+/// 偏好列表中越靠前分数越高；列表为空时不区分
+fn list_score(position: Option<usize>, len: usize) -> f64 {
+    match position {
+        _ if len == 0 => UNKNOWN_SCORE,
+        Some(i) => 1.0 - i as f64 / len as f64,
+        None => 0.0,
+    }
+}
+
+fn resolution_score(resolution: Option<u32>, preferred: Option<u32>) -> f64 {
+    match (resolution, preferred) {
+        (Some(r), Some(p)) if r == p => 1.0,
+        (Some(r), Some(p)) if r > p => 0.6,
+        (Some(r), Some(p)) => 0.3 * r as f64 / p as f64,
+        (Some(r), None) => (r as f64 / 2160.0).min(1.0),
+        (None, _) => 0.2,
+    }
+}
+
+/// 合集按集数平摊后判断体积是否合理
+fn size_score(item: &MikanResourceItem, max_episode_mb: u32) -> f64 {
+    let Some(size) = item.size_bytes else {
+        return UNKNOWN_SCORE;
+    };
+    let episodes = item
+        .release
+        .episode_range
+        .map_or(1, |r| r.end.saturating_sub(r.start) + 1)
+        .max(1);
+    let per_episode = size / episodes as u64;
+    let max = u64::from(max_episode_mb) * 1024 * 1024;
+    if per_episode >= MIN_EPISODE_BYTES && per_episode <= max {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn is_blocked(item: &MikanResourceItem, prefs: &ReleasePreferences) -> bool {
+    contains_group(&prefs.blocked_groups, &group_names(item)).is_some()
+}
+
+/// 按偏好为资源打分，范围 `[0, 1]`
+pub fn score_release(item: &MikanResourceItem, prefs: &ReleasePreferences) -> f64 {
+    let group = list_score(
+        contains_group(&prefs.preferred_groups, &group_names(item)),
+        prefs.preferred_groups.len(),
+    );
+    let language = list_score(
+        item.subtitle_lang
+            .as_deref()
+            .and_then(|l| prefs.languages.iter().position(|p| p == l)),
+        prefs.languages.len(),
+    );
+    let subtitle_type = list_score(
+        item.subtitle_type
+            .as_deref()
+            .and_then(|t| prefs.subtitle_types.iter().position(|p| p == t)),
+        prefs.subtitle_types.len(),
+    );
+    GROUP_WEIGHT * group
+        + RESOLUTION_WEIGHT * resolution_score(item.resolution, prefs.resolution)
+        + LANGUAGE_WEIGHT * language
+        + SUBTITLE_TYPE_WEIGHT * subtitle_type
+        + SIZE_WEIGHT * size_score(item, prefs.max_episode_mb)
+}
+
+/// 候选中分数最高的资源，分数相同时取先出现的；屏蔽字幕组不参与
+pub fn best_release<'a>(
+    items: impl IntoIterator<Item = &'a MikanResourceItem>,
+    prefs: &ReleasePreferences,
+) -> Option<&'a MikanResourceItem> {
+    let mut best: Option<(&MikanResourceItem, f64)> = None;
+    for item in items.into_iter().filter(|it| !is_blocked(it, prefs)) {
+        let score = score_release(item, prefs);
+        if best.is_none_or(|(_, b)| score > b) {
+            best = Some((item, score));
+        }
+    }
+    best.map(|(item, _)| item)
+}
+
+/// 按集数（或合集范围）分组并排序。
+/// 单集按集数降序在前，合集其次，无法识别集数的资源最后；屏蔽字幕组的资源直接丢弃。
+pub fn cluster_releases(
+    items: Vec<MikanResourceItem>,
+    prefs: &ReleasePreferences,
+) -> Vec<ReleaseCluster> {
+    let mut groups: Vec<(ClusterKey, Vec<RankedRelease>)> = Vec::new();
+    for item in items.into_iter().filter(|it| !is_blocked(it, prefs)) {
+        let key = match item.episode {
+            Some(ep) => (Some(ep), None),
+            None => (None, item.episode_range.clone()),
+        };
+        let ranked = RankedRelease {
+            score: score_release(&item, prefs),
+            item,
+        };
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, list)) => list.push(ranked),
+            None => groups.push((key, vec![ranked])),
+        }
+    }
+
+    groups.sort_by_key(|((episode, range), _)| {
+        (
+            episode.is_none(),
+            range.is_none(),
+            std::cmp::Reverse(*episode),
+        )
+    });
+    groups
+        .into_iter()
+        .map(|((episode, episode_range), mut list)| {
+            // 同分时保留修正版本更高的，其余保持 feed 顺序（较新的在前）
+            list.sort_by(|a, b| {
+                b.score
+                    .total_cmp(&a.score)
+                    .then(b.item.release.version.cmp(&a.item.release.version))
+            });
+            let best = list.remove(0);
+            ReleaseCluster {
+                episode,
+                episode_range,
+                best,
+                alternatives: list,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mikan::rss::test_item;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn item(title: &str, size_mb: Option<u64>) -> MikanResourceItem {
+        let mut item = test_item(title);
+        item.size_bytes = size_mb.map(|s| s * MB);
+        item
+    }
+
+    fn prefs() -> ReleasePreferences {
+        ReleasePreferences {
+            preferred_groups: vec!["LoliHouse".into(), "ANi".into()],
+            blocked_groups: vec!["BadGroup".into()],
+            resolution: Some(1080),
+            languages: vec!["简繁".into(), "繁体".into()],
+            subtitle_types: vec!["内封".into()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn preferred_group_and_language_win() {
+        let lolihouse = item(
+            "[Nekomoe kissaten&LoliHouse] 葬送的芙莉莲 - 05 [WebRip 1080p HEVC-10bit AAC][简繁内封字幕]",
+            Some(600),
+        );
+        let other = item("[Other] 葬送的芙莉莲 - 05 [1080p][简体]", Some(600));
+        let p = prefs();
+        assert!(score_release(&lolihouse, &p) > score_release(&other, &p));
+    }
+
+    #[test]
+    fn resolution_prefers_exact_match_over_higher() {
+        assert!(
+            resolution_score(Some(1080), Some(1080)) > resolution_score(Some(2160), Some(1080))
+        );
+        assert!(resolution_score(Some(2160), Some(1080)) > resolution_score(Some(720), Some(1080)));
+        assert!(resolution_score(Some(2160), None) > resolution_score(Some(1080), None));
+    }
+
+    #[test]
+    fn implausible_sizes_are_penalized() {
+        let p = ReleasePreferences::default();
+        assert_eq!(
+            size_score(&item("[A] X - 01", Some(10)), p.max_episode_mb),
+            0.0
+        );
+        assert_eq!(
+            size_score(&item("[A] X - 01", Some(600)), p.max_episode_mb),
+            1.0
+        );
+        assert_eq!(
+            size_score(&item("[A] X - 01", Some(9000)), p.max_episode_mb),
+            0.0
+        );
+        // 合集按集数平摊
+        assert_eq!(
+            size_score(&item("[A] X [01-12][1080p]", Some(9000)), p.max_episode_mb),
+            1.0
+        );
+        assert_eq!(
+            size_score(&item("[A] X - 01", None), p.max_episode_mb),
+            UNKNOWN_SCORE
+        );
+    }
+
+    #[test]
+    fn clusters_by_episode_and_drops_blocked_groups() {
+        let items = vec![
+            item("[Other] 葬送的芙莉莲 - 04 [1080p]", Some(500)),
+            item("[BadGroup] 葬送的芙莉莲 - 05 [1080p][简繁内封]", Some(500)),
+            item("[ANi] 葬送的芙莉莲 - 05 [1080P][CHT]", Some(500)),
+            item("[Other] 葬送的芙莉莲 - 05 [720p]", Some(300)),
+            item("[LoliHouse] 葬送的芙莉莲 [01-12 合集][1080p]", Some(7000)),
+            item("[Other] 葬送的芙莉莲 - 05v2 [720p]", Some(300)),
+            item("葬送的芙莉莲 特别篇", None),
+        ];
+        let clusters = cluster_releases(items, &prefs());
+        let keys: Vec<(Option<u32>, Option<&str>)> = clusters
+            .iter()
+            .map(|c| (c.episode, c.episode_range.as_deref()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                (Some(5), None),
+                (Some(4), None),
+                (None, Some("1-12")),
+                (None, None)
+            ]
+        );
+
+        let five = &clusters[0];
+        assert_eq!(five.best.item.group.as_deref(), Some("ANi"));
+        assert_eq!(five.alternatives.len(), 2);
+        // 同分时修正版在前
+        assert_eq!(five.alternatives[0].item.release.version, Some(2));
+    }
+}
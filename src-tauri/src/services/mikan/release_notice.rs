@@ -0,0 +1,144 @@
+use crate::error::AppError;
+use crate::models::mikan::{EpisodeReleaseNotice, MikanResourceItem};
+use crate::services::downloader::lifecycle;
+use crate::services::mikan::config::ReleasePreferences;
+use crate::services::mikan::episode_offset::{shift, shift_range};
+use crate::services::mikan::ranking;
+use crate::services::mikan::seen::{self, EpisodeKey};
+
+const MB: u64 = 1024 * 1024;
+
+/// 为新的一集挑选推荐资源；优先有下载链接的资源，集数按偏移换算
+pub fn build(
+    subject_id: u32,
+    anime_name: &str,
+    key: EpisodeKey,
+    offset: i32,
+    items: &[MikanResourceItem],
+    prefs: &ReleasePreferences,
+) -> Option<EpisodeReleaseNotice> {
+    let candidates: Vec<&MikanResourceItem> =
+        items.iter().filter(|it| seen::has_key(it, key)).collect();
+    let downloadable = candidates
+        .iter()
+        .copied()
+        .filter(|it| it.torrent_url.is_some() || it.magnet.is_some());
+    let item = ranking::best_release(downloadable, prefs)
+        .or_else(|| ranking::best_release(candidates.iter().copied(), prefs))?;
+    Some(EpisodeReleaseNotice {
+        subject_id,
+        anime_name: anime_name.to_string(),
+        label: key.label(offset),
+        title: item.title.clone(),
+        group: item.group.clone(),
+        resolution: item.resolution,
+        subtitle_lang: item.subtitle_lang.clone(),
+        size_bytes: item.size_bytes,
+        url: item.torrent_url.clone().or_else(|| item.magnet.clone()),
+        episode: item.episode.and_then(|e| shift(e, offset)),
+        episode_range: item
+            .release
+            .episode_range
+            .and_then(|r| shift_range(r, offset)),
+    })
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * MB {
+        format!("{:.1} GB", bytes as f64 / (1024 * MB) as f64)
+    } else {
+        format!("{} MB", bytes.div_ceil(MB))
+    }
+}
+
+/// 通知正文中的资源概要，如 `ANi · 1080P · 繁体 · 350 MB`
+pub fn summary(notice: &EpisodeReleaseNotice) -> String {
+    let parts: Vec<String> = [
+        notice.group.clone(),
+        notice.resolution.map(|r| format!("{r}P")),
+        notice.subtitle_lang.clone(),
+        notice.size_bytes.map(format_size),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if parts.is_empty() {
+        notice.title.clone()
+    } else {
+        parts.join(" · ")
+    }
+}
+
+/// 下载通知中推荐的资源并归入条目
+pub async fn download(notice: EpisodeReleaseNotice) -> Result<(), AppError> {
+    let url = notice
+        .url
+        .ok_or_else(|| AppError::Any("resource has no torrent or magnet link".into()))?;
+    lifecycle::add_for_subject(
+        url,
+        notice.subject_id,
+        notice.episode,
+        notice.episode_range,
+        notice.title,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mikan::rss::test_item;
+
+    fn item(title: &str, torrent: bool, size_mb: u64) -> MikanResourceItem {
+        let mut item = test_item(title);
+        item.torrent_url = torrent.then(|| format!("https://mikan/{title}.torrent"));
+        item.size_bytes = Some(size_mb * MB);
+        item
+    }
+
+    fn prefs() -> ReleasePreferences {
+        ReleasePreferences {
+            preferred_groups: vec!["LoliHouse".into()],
+            blocked_groups: vec!["BadGroup".into()],
+            resolution: Some(1080),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn picks_preferred_downloadable_release_with_offset() {
+        let items = vec![
+            item("[BadGroup] 葬送的芙莉莲 - 05 [1080p]", true, 500),
+            item("[Other] 葬送的芙莉莲 - 05 [720p]", true, 300),
+            item("[LoliHouse] 葬送的芙莉莲 - 05 [1080p][简繁内封]", true, 600),
+            item("[LoliHouse] 葬送的芙莉莲 - 06 [1080p]", true, 600),
+        ];
+        let notice = build(
+            1,
+            "葬送的芙莉莲",
+            EpisodeKey::Episode(5),
+            12,
+            &items,
+            &prefs(),
+        )
+        .unwrap();
+        assert_eq!(notice.label, "第 17 话");
+        assert_eq!(notice.group.as_deref(), Some("LoliHouse"));
+        assert_eq!(notice.episode, Some(17));
+        assert_eq!(summary(&notice), "LoliHouse · 1080P · 简繁 · 600 MB");
+    }
+
+    #[test]
+    fn links_beat_score_and_batches_keep_their_range() {
+        let items = vec![
+            item("[LoliHouse] 葬送的芙莉莲 [01-12 合集][1080p]", false, 7000),
+            item("[Other] 葬送的芙莉莲 [01-12 合集][720p]", true, 3000),
+        ];
+        let notice = build(1, "X", EpisodeKey::Batch(1, 12), 0, &items, &prefs()).unwrap();
+        assert_eq!(notice.group.as_deref(), Some("Other"));
+        assert_eq!(notice.episode_range.as_deref(), Some("1-12"));
+        assert!(notice.url.is_some());
+        assert_eq!(format_size(3000 * MB), "2.9 GB");
+        assert!(build(1, "X", EpisodeKey::Episode(3), 0, &items, &prefs()).is_none());
+    }
+}
@@ -1,30 +1,261 @@
 use crate::error::AppError;
-use crate::services::mikan::bangumi_page;
+use crate::models::bangumi::SubjectResponse;
+use crate::services::mikan::bangumi_page::{self, BangumiPage};
+use crate::utils::parser::parse_season;
+use crate::utils::string::title_similarity;
 use tokio::task::JoinSet;
 
+/// 达到该分数直接写入映射
+pub const CONFIDENT_SCORE: f64 = 0.85;
+/// 介于两者之间的候选作为建议，等待用户确认
+pub const SUGGEST_SCORE: f64 = 0.6;
+
+const TITLE_WEIGHT: f64 = 0.6;
+const SEASON_WEIGHT: f64 = 0.15;
+const YEAR_WEIGHT: f64 = 0.15;
+const EPISODE_WEIGHT: f64 = 0.1;
+/// 季数明确不一致时的分数上限，保证不会被自动映射
+const SEASON_MISMATCH_CAP: f64 = 0.5;
+
+/// 参与匹配的 Bangumi 条目信息
+#[derive(Debug, Clone, Default)]
+pub struct SubjectProfile {
+    pub names: Vec<String>,
+    pub season: Option<u32>,
+    pub year: Option<i32>,
+    pub episodes: Option<u32>,
+}
+
+impl SubjectProfile {
+    pub fn from_subject(subject: &SubjectResponse) -> Self {
+        let mut names: Vec<String> = Vec::new();
+        let aliases = subject
+            .infobox
+            .iter()
+            .flatten()
+            .filter(|item| item.key == "别名" || item.key == "中文名")
+            .flat_map(|item| item.values.iter().cloned());
+        for name in [subject.name_cn.clone(), subject.name.clone()]
+            .into_iter()
+            .chain(aliases)
+        {
+            let name = name.trim().to_string();
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        let season = names.iter().find_map(|n| parse_season(n));
+        let year = subject
+            .date
+            .as_deref()
+            .and_then(|d| d.get(..4))
+            .and_then(|y| y.parse().ok());
+        let episodes = subject.total_episodes.or(subject.eps).filter(|n| *n > 0);
+        Self {
+            names,
+            season,
+            year,
+            episodes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredCandidate {
+    pub mikan_id: u32,
+    pub title: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// Mikan 页面直接链接到该条目
+    Exact(u32),
+    /// 模糊匹配分数足够高
+    Confident(ScoredCandidate),
+    /// 只有边缘候选，按分数降序
+    Suggestions(Vec<ScoredCandidate>),
+}
+
+/// 对没有 bgm.tv 链接的 Mikan 页面打分，范围 `[0, 1]`
+pub fn score_page(profile: &SubjectProfile, page: &BangumiPage) -> f64 {
+    let title = profile
+        .names
+        .iter()
+        .map(|n| title_similarity(n, &page.title))
+        .fold(0.0, f64::max);
+
+    // 未标注季数视为第一季
+    let season_match = profile.season.unwrap_or(1) == parse_season(&page.title).unwrap_or(1);
+    let season = if season_match { 1.0 } else { 0.0 };
+
+    let year = match (profile.year, page.year) {
+        (Some(a), Some(b)) if a == b => 1.0,
+        // 跨年开播的番剧两边日期可能差一年
+        (Some(a), Some(b)) if (a - b).abs() == 1 => 0.5,
+        (Some(_), Some(_)) => 0.0,
+        _ => 0.5,
+    };
+
+    let episodes = match (profile.episodes, page.max_episode) {
+        // 部分字幕组按总集数编号，放宽一倍
+        (Some(total), Some(max)) if max <= total.saturating_mul(2) => 1.0,
+        (Some(_), Some(_)) => 0.0,
+        _ => 0.5,
+    };
+
+    let score = TITLE_WEIGHT * title
+        + SEASON_WEIGHT * season
+        + YEAR_WEIGHT * year
+        + EPISODE_WEIGHT * episodes;
+    if season_match {
+        score
+    } else {
+        score.min(SEASON_MISMATCH_CAP)
+    }
+}
+
+/// 汇总一批页面：链接到其他条目的页面直接排除
+pub fn rank_pages(
+    sid: u32,
+    profile: &SubjectProfile,
+    pages: Vec<(u32, BangumiPage)>,
+) -> Option<Resolution> {
+    let mut scored: Vec<ScoredCandidate> = Vec::new();
+    for (mikan_id, page) in pages {
+        match page.subject_id {
+            Some(s) if s == sid => return Some(Resolution::Exact(mikan_id)),
+            Some(_) => continue,
+            None => {
+                let score = score_page(profile, &page);
+                if score >= SUGGEST_SCORE {
+                    scored.push(ScoredCandidate {
+                        mikan_id,
+                        title: page.title,
+                        score,
+                    });
+                }
+            }
+        }
+    }
+    scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+    match scored.first() {
+        Some(best) if best.score >= CONFIDENT_SCORE => Some(Resolution::Confident(best.clone())),
+        Some(_) => Some(Resolution::Suggestions(scored)),
+        None => None,
+    }
+}
+
 pub async fn resolve_candidates(
     sid: u32,
+    profile: &SubjectProfile,
     candidates: Vec<u32>,
     max: usize,
-) -> Result<Option<u32>, AppError> {
-    let mut js: JoinSet<(u32, Result<Option<u32>, AppError>)> = JoinSet::new();
+) -> Result<Option<Resolution>, AppError> {
+    let mut js: JoinSet<(u32, Result<BangumiPage, AppError>)> = JoinSet::new();
     let mut idx = 0usize;
     while idx < candidates.len() && js.len() < max {
         let bid = candidates[idx];
         idx += 1;
-        js.spawn(async move { (bid, bangumi_page::resolve_subject(bid).await) });
+        js.spawn(async move { (bid, bangumi_page::fetch_page(bid).await) });
     }
+    let mut pages = Vec::new();
     while let Some(res) = js.join_next().await {
-        if let Ok((bid, Ok(Some(s)))) = res {
-            if s == sid {
-                return Ok(Some(bid));
+        if let Ok((bid, Ok(page))) = res {
+            if page.subject_id == Some(sid) {
+                return Ok(Some(Resolution::Exact(bid)));
             }
+            pages.push((bid, page));
         }
         if idx < candidates.len() {
             let bid2 = candidates[idx];
             idx += 1;
-            js.spawn(async move { (bid2, bangumi_page::resolve_subject(bid2).await) });
+            js.spawn(async move { (bid2, bangumi_page::fetch_page(bid2).await) });
         }
     }
-    Ok(None)
+    Ok(rank_pages(sid, profile, pages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> SubjectProfile {
+        SubjectProfile {
+            names: vec![
+                "辉夜大小姐想让我告白？～天才们的恋爱头脑战～".to_string(),
+                "かぐや様は告らせたい～天才たちの恋愛頭脳戦～".to_string(),
+                "辉夜姬想让人告白".to_string(),
+            ],
+            season: None,
+            year: Some(2019),
+            episodes: Some(12),
+        }
+    }
+
+    fn page(title: &str, year: Option<i32>, max_episode: Option<u32>) -> BangumiPage {
+        BangumiPage {
+            subject_id: None,
+            title: title.to_string(),
+            year,
+            max_episode,
+            subgroups: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn alias_title_with_matching_year_is_confident() {
+        let score = score_page(&profile(), &page("辉夜姬想让人告白", Some(2019), Some(12)));
+        assert!(score >= CONFIDENT_SCORE, "score = {score}");
+    }
+
+    #[test]
+    fn season_mismatch_is_never_confident() {
+        let score = score_page(
+            &profile(),
+            &page("辉夜姬想让人告白 第二季", Some(2020), Some(12)),
+        );
+        assert!(score <= SEASON_MISMATCH_CAP, "score = {score}");
+    }
+
+    #[test]
+    fn year_and_episode_count_separate_remakes() {
+        let original = score_page(&profile(), &page("辉夜姬想让人告白", Some(2019), Some(12)));
+        let other = score_page(&profile(), &page("辉夜姬想让人告白", Some(2012), Some(48)));
+        assert!(original > other);
+        assert!(other < CONFIDENT_SCORE);
+    }
+
+    #[test]
+    fn ranks_exact_link_over_fuzzy_score() {
+        let mut linked = page("完全不同的标题", None, None);
+        linked.subject_id = Some(7);
+        let pages = vec![
+            (1, page("辉夜姬想让人告白", Some(2019), Some(12))),
+            (2, linked),
+        ];
+        assert_eq!(rank_pages(7, &profile(), pages), Some(Resolution::Exact(2)));
+    }
+
+    #[test]
+    fn borderline_candidates_become_suggestions() {
+        let pages = vec![
+            (1, page("辉夜姬想让人告白 剧场版", None, None)),
+            (2, page("间谍过家家", Some(2022), Some(12))),
+        ];
+        match rank_pages(7, &profile(), pages) {
+            Some(Resolution::Suggestions(list)) => {
+                assert_eq!(list.len(), 1);
+                assert_eq!(list[0].mikan_id, 1);
+            }
+            other => panic!("unexpected resolution: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn pages_linked_to_other_subjects_are_skipped() {
+        let mut other = page("辉夜姬想让人告白", Some(2019), Some(12));
+        other.subject_id = Some(99);
+        assert_eq!(rank_pages(7, &profile(), vec![(1, other)]), None);
+    }
 }
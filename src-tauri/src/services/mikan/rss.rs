@@ -1,11 +1,9 @@
 use crate::error::AppError;
 use crate::infra::cache;
-use crate::infra::config::MIKAN_HOST;
-use crate::infra::http::CLIENT;
-use crate::models::mikan::MikanResourceItem;
-use crate::utils::parser::parse_resolution;
-use once_cell::sync::Lazy;
-use regex::Regex;
+use crate::infra::http::with_validators;
+use crate::models::mikan::{MikanResourceItem, ResourceSource};
+use crate::services::mikan::host;
+use crate::utils::parser::{parse_release, parse_resolution, parse_subtitle_text};
 use reqwest::header::{ETAG, LAST_MODIFIED};
 use reqwest::StatusCode;
 use std::str::FromStr;
@@ -13,6 +11,8 @@ use std::time::Instant;
 use tracing::{info, warn};
 
 const RSS_TTL_SECS: i64 = 6 * 3600;
+/// 关键词订阅按预热周期轮询，搜索 feed 缓存不能长于一个周期
+const SEARCH_RSS_TTL_SECS: i64 = 600;
 
 fn extract_meta(headers: &reqwest::header::HeaderMap) -> (Option<String>, Option<String>) {
     let new_etag = headers
@@ -26,87 +26,178 @@ fn extract_meta(headers: &reqwest::header::HeaderMap) -> (Option<String>, Option
     (new_etag, new_lm)
 }
 
-async fn cache_body_and_meta(key: &str, body: String, headers: &reqwest::header::HeaderMap) {
+async fn cache_body_and_meta(
+    key: &str,
+    body: String,
+    headers: &reqwest::header::HeaderMap,
+    ttl: i64,
+) {
     let (new_etag, new_lm) = extract_meta(headers);
-    let _ = cache::set_entry(key, body, new_etag, new_lm, RSS_TTL_SECS).await;
+    let _ = cache::set_entry(key, body, new_etag, new_lm, ttl).await;
 }
 
 pub async fn fetch_rss(mid: u32) -> Result<Vec<MikanResourceItem>, AppError> {
-    let key = format!("mikan:rss:{}", mid);
-    let url = format!("{}/RSS/Bangumi?bangumiId={}", MIKAN_HOST, mid);
+    fetch_feed(mid, None).await
+}
 
-    let xml = if let Ok(Some((body, _etag, _last_modified))) = cache::get_entry(&key).await {
-        info!(
-            bangumi_id = mid,
-            xml_len = body.len(),
-            source = "cache",
-            "mikan rss xml ready"
-        );
-        body
-    } else {
-        info!(
-            bangumi_id = mid,
-            "mikan rss cache miss, fetching from network"
-        );
+/// 只拉取指定字幕组的 feed 并合并；未指定字幕组时退化为整个番组的 feed。
+/// 单个字幕组失败时跳过，全部失败才返回错误
+pub async fn fetch_rss_for(
+    mid: u32,
+    subgroups: &[u32],
+) -> Result<Vec<MikanResourceItem>, AppError> {
+    if subgroups.is_empty() {
+        return fetch_feed(mid, None).await;
+    }
+    let mut feeds = Vec::with_capacity(subgroups.len());
+    let mut last_error = None;
+    for gid in subgroups {
+        match fetch_feed(mid, Some(*gid)).await {
+            Ok(items) => feeds.push(items),
+            Err(e) => {
+                warn!(mikan_id = mid, subgroup = gid, error = %e, "mikan subgroup feed failed");
+                last_error = Some(e);
+            }
+        }
+    }
+    match last_error {
+        Some(e) if feeds.is_empty() => Err(e),
+        _ => Ok(merge_feeds(feeds)),
+    }
+}
 
-        crate::infra::http::wait_api_limit().await;
-        let net_start = Instant::now();
+/// 合并多个 feed，按页面链接去重，保持各 feed 内的顺序
+fn merge_feeds(feeds: Vec<Vec<MikanResourceItem>>) -> Vec<MikanResourceItem> {
+    let mut seen = std::collections::HashSet::new();
+    feeds
+        .into_iter()
+        .flatten()
+        .filter(|item| seen.insert(item.page_url.clone()))
+        .collect()
+}
 
-        let resp = CLIENT.get(&url).send().await?;
-        resp.error_for_status_ref()?;
-        let headers = resp.headers().clone();
-        let status = resp.status();
+fn feed_key_and_path(mid: u32, subgroup: Option<u32>) -> (String, String) {
+    match subgroup {
+        Some(gid) => (
+            format!("mikan:rss:{}:{}", mid, gid),
+            format!("/RSS/Bangumi?bangumiId={}&subgroupid={}", mid, gid),
+        ),
+        None => (
+            format!("mikan:rss:{}", mid),
+            format!("/RSS/Bangumi?bangumiId={}", mid),
+        ),
+    }
+}
 
-        let body = if status == StatusCode::NOT_MODIFIED {
-            info!(bangumi_id = mid, net_ms = %net_start.elapsed().as_millis(), status = 304, "mikan rss not modified");
+async fn fetch_feed(mid: u32, subgroup: Option<u32>) -> Result<Vec<MikanResourceItem>, AppError> {
+    let (key, path) = feed_key_and_path(mid, subgroup);
+    fetch_cached_feed(&key, &path, &[], RSS_TTL_SECS).await
+}
 
-            match cache::get_entry(&key).await {
-                Ok(Some((cached_body, _, _))) => cached_body,
-                Ok(None) => {
-                    warn!(
-                        bangumi_id = mid,
-                        "mikan rss 304 but no cached body, returning empty"
-                    );
-                    String::new()
-                }
-                Err(e) => {
-                    warn!(error = %e, bangumi_id = mid, "mikan rss 304 but cache read error");
-                    String::new()
-                }
-            }
-        } else {
-            let body = resp.text().await?;
+/// Mikan 搜索结果的 RSS，用于关键词订阅
+pub async fn fetch_search_rss(keyword: &str) -> Result<Vec<MikanResourceItem>, AppError> {
+    let key = format!("mikan:rss:search:{}", keyword);
+    fetch_cached_feed(
+        &key,
+        "/RSS/Search",
+        &[("searchstr", keyword)],
+        SEARCH_RSS_TTL_SECS,
+    )
+    .await
+}
+
+async fn fetch_cached_feed(
+    key: &str,
+    path: &str,
+    query: &[(&str, &str)],
+    ttl: i64,
+) -> Result<Vec<MikanResourceItem>, AppError> {
+    let cached = cache::get_with_stale(key).await.unwrap_or_else(|e| {
+        warn!(error = %e, feed = key, "mikan rss cache read error");
+        None
+    });
+
+    let xml = match cached {
+        Some(entry) if entry.fresh => {
             info!(
-                bangumi_id = mid,
-                net_ms = %net_start.elapsed().as_millis(),
-                xml_len = body.len(),
-                status = %status.as_u16(),
-                "mikan rss fetched"
+                feed = key,
+                xml_len = entry.value.len(),
+                source = "cache",
+                "mikan rss xml ready"
             );
-            cache_body_and_meta(&key, body.clone(), &headers).await;
-            body
-        };
+            entry.value
+        }
+        cached => {
+            // 过期条目带有验证器时发起条件请求，304 直接复用旧内容
+            let stale = cached.filter(|e| e.has_validator());
+            info!(
+                feed = key,
+                revalidate = stale.is_some(),
+                "mikan rss cache miss, fetching from network"
+            );
+
+            crate::infra::http::wait_api_limit().await;
+            let net_start = Instant::now();
 
-        body
+            let resp = host::send(path, |b| {
+                let b = if query.is_empty() { b } else { b.query(query) };
+                match &stale {
+                    Some(e) => with_validators(b, e.etag.as_deref(), e.last_modified.as_deref()),
+                    None => b,
+                }
+            })
+            .await?;
+            resp.error_for_status_ref()?;
+            let headers = resp.headers().clone();
+            let status = resp.status();
+
+            match stale {
+                Some(entry) if status == StatusCode::NOT_MODIFIED => {
+                    info!(feed = key, net_ms = %net_start.elapsed().as_millis(), status = 304, "mikan rss not modified");
+                    if let Err(e) = cache::touch_entry(key, ttl).await {
+                        warn!(error = %e, feed = key, "mikan rss cache ttl extend failed");
+                    }
+                    entry.value
+                }
+                _ => {
+                    let body = resp.text().await?;
+                    info!(
+                        feed = key,
+                        net_ms = %net_start.elapsed().as_millis(),
+                        xml_len = body.len(),
+                        status = %status.as_u16(),
+                        "mikan rss fetched"
+                    );
+                    cache_body_and_meta(key, body.clone(), &headers, ttl).await;
+                    body
+                }
+            }
+        }
     };
 
     let parse_start = Instant::now();
-    let ch = match parse_rss_channel(mid, &xml) {
+    let ch = match parse_rss_channel(key, &xml) {
         Some(c) => c,
         None => return Ok(Vec::new()),
     };
-    info!(bangumi_id = mid, parse_ms = %parse_start.elapsed().as_millis(), "mikan rss xml parsed");
+    info!(feed = key, parse_ms = %parse_start.elapsed().as_millis(), "mikan rss xml parsed");
     let build_start = Instant::now();
-    let out = parse_rss_items(&ch);
-    info!(bangumi_id = mid, items = out.len(), build_ms = %build_start.elapsed().as_millis(), "mikan rss items built");
+    let mut out = parse_rss_items(&ch);
+    // 缓存的 feed 可能来自其他镜像，统一指向当前可用的镜像
+    let rewriter = host::url_rewriter().await;
+    for item in out.iter_mut() {
+        item.page_url = rewriter.rewrite(&item.page_url);
+        item.torrent_url = item.torrent_url.as_deref().map(|u| rewriter.rewrite(u));
+    }
+    info!(feed = key, items = out.len(), build_ms = %build_start.elapsed().as_millis(), "mikan rss items built");
     Ok(out)
 }
 
-fn parse_rss_channel(mid: u32, xml: &str) -> Option<rss::Channel> {
+fn parse_rss_channel(key: &str, xml: &str) -> Option<rss::Channel> {
     match rss::Channel::from_str(xml) {
         Ok(c) => Some(c),
         Err(e) => {
-            warn!(error = %e, bangumi_id = mid, "mikan rss parse error");
+            warn!(error = %e, feed = key, "mikan rss parse error");
             None
         }
     }
@@ -115,8 +206,6 @@ fn parse_rss_channel(mid: u32, xml: &str) -> Option<rss::Channel> {
 fn parse_rss_items(ch: &rss::Channel) -> Vec<MikanResourceItem> {
     let mut out: Vec<MikanResourceItem> = Vec::new();
     for it in ch.items() {
-        let title = it.title().unwrap_or("").to_string();
-        let page_url = it.link().unwrap_or("").to_string();
         let mut torrent_url: Option<String> = None;
         let mut size_bytes: Option<u64> = None;
         if let Some(enc) = it.enclosure() {
@@ -126,194 +215,61 @@ fn parse_rss_items(ch: &rss::Channel) -> Vec<MikanResourceItem> {
                 size_bytes = len_str.parse::<u64>().ok();
             }
         }
-        let pub_date = it.pub_date().map(|s| s.to_string());
-        let desc = it.description().map(|s| s.to_string());
-        let group = parse_group(&title);
-        let (episode, episode_range) = parse_episode_info(&title);
-        let resolution =
-            parse_resolution(&title).or_else(|| desc.as_deref().and_then(parse_resolution));
-        let (subtitle_lang, subtitle_type) = parse_subtitle(&title, desc.as_deref());
-        out.push(MikanResourceItem {
-            title,
-            page_url,
-            torrent_url,
-            magnet: None,
-            pub_date,
-            size_bytes,
-            group,
-            episode,
-            episode_range,
-            resolution,
-            subtitle_lang,
-            subtitle_type,
-        });
+        out.push(build_item(
+            RawItem {
+                title: it.title().unwrap_or("").to_string(),
+                page_url: it.link().unwrap_or("").to_string(),
+                torrent_url,
+                magnet: None,
+                pub_date: it.pub_date().map(|s| s.to_string()),
+                size_bytes,
+                description: it.description().map(|s| s.to_string()),
+            },
+            ResourceSource::Mikan,
+        ));
     }
     out
 }
 
-fn leading_group(text: &str) -> Option<String> {
-    let t = text.trim();
-    let pair = match t.chars().next() {
-        Some('[') => Some(('[', ']')),
-        Some('(') => Some(('(', ')')),
-        Some('{') => Some(('{', '}')),
-        Some('【') => Some(('【', '】')),
-        _ => None,
-    }?;
-    let start = t.chars().next()?.len_utf8();
-    let right = pair.1;
-    if let Some(rel_end) = t[start..].find(right) {
-        let end = start + rel_end;
-        if end > start {
-            let g = t[start..end].trim();
-            if !g.is_empty() && g.len() <= 40 {
-                return Some(g.to_string());
-            }
-        }
-    }
-    None
-}
-
-fn any_group(text: &str) -> Option<String> {
-    let t = text;
-    let _pairs = [('[', ']'), ('(', ')'), ('{', '}'), ('【', '】')];
-    for (i, ch) in t.char_indices() {
-        let right = match ch {
-            '[' => Some(']'),
-            '(' => Some(')'),
-            '{' => Some('}'),
-            '【' => Some('】'),
-            _ => None,
-        };
-        if let Some(r) = right {
-            let start = i + ch.len_utf8();
-            if let Some(rel_end) = t[start..].find(r) {
-                let end = start + rel_end;
-                if end > start {
-                    let g = t[start..end].trim();
-                    if !g.is_empty() && g.len() <= 40 {
-                        return Some(g.to_string());
-                    }
-                }
-            }
-        }
-    }
-    None
-}
-
-fn parse_group(title: &str) -> Option<String> {
-    leading_group(title).or_else(|| any_group(title))
+/// 各站点 RSS 条目中与解析无关的原始字段
+pub(crate) struct RawItem {
+    pub title: String,
+    pub page_url: String,
+    pub torrent_url: Option<String>,
+    pub magnet: Option<String>,
+    pub pub_date: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub description: Option<String>,
 }
 
-// Episode parsing patterns - ordered by priority
-static RE_EPISODE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?i)(?:EP|E|第)\s*(\d{1,3})(?:\s*(?:话|話|集))?\b").unwrap());
-static RE_RANGE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)\b(\d{1,3})\s*-\s*(\d{1,3})(?:\s*[\[(（]?(?:全集|END|完)[\])）]?)?").unwrap()
-});
-static RE_BRACKET_NUM: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)[\[(【]\s*(?:OAD|OVA|SP|Special|特典)?\s*(\d{1,3})\s*[\])）】]").unwrap()
-});
-static RE_DASH_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)[\s\-]\s*(\d{1,3})\b").unwrap());
-
-fn parse_episode_info(title: &str) -> (Option<u32>, Option<String>) {
-    // Try explicit EP/E/第 pattern first
-    if let Some(c) = RE_EPISODE.captures(title) {
-        if let Ok(n) = c.get(1).unwrap().as_str().parse::<u32>() {
-            return (Some(n), None);
-        }
+/// 从标题解析发布信息；分辨率与字幕信息缺失时再从描述中补充
+pub(crate) fn build_item(raw: RawItem, source: ResourceSource) -> MikanResourceItem {
+    let title = raw.title;
+    let desc = raw.description.as_deref();
+    let mut release = parse_release(&title);
+    if release.resolution.is_none() {
+        release.resolution = desc.and_then(parse_resolution);
     }
-
-    // Try range pattern (e.g., "01-12")
-    if let Some(c) = RE_RANGE.captures(title) {
-        if let (Ok(sn), Ok(en)) = (
-            c.get(1).unwrap().as_str().parse::<u32>(),
-            c.get(2).unwrap().as_str().parse::<u32>(),
-        ) {
-            // Check if this is a season indicator like "S2 - 23" (not a range)
-            if is_season_indicator(title, &c, sn) {
-                return (Some(en), None);
-            }
-            return (None, Some(format!("{}-{}", sn, en)));
-        }
-    }
-
-    // Try bracketed number (e.g., "[01]")
-    if let Some(c) = RE_BRACKET_NUM.captures(title) {
-        if let Ok(n) = c.get(1).unwrap().as_str().parse::<u32>() {
-            return (Some(n), None);
-        }
+    (release.language, release.subtitle_type) = parse_subtitle(&title, desc);
+    MikanResourceItem {
+        page_url: raw.page_url,
+        torrent_url: raw.torrent_url,
+        magnet: raw.magnet,
+        pub_date: raw.pub_date,
+        size_bytes: raw.size_bytes,
+        group: release.group.clone(),
+        episode: release.episode,
+        episode_range: release
+            .episode_range
+            .map(|r| format!("{}-{}", r.start, r.end)),
+        resolution: release.resolution,
+        subtitle_lang: release.language.clone(),
+        subtitle_type: release.subtitle_type.clone(),
+        mikan_bangumi_id: None,
+        source,
+        release,
+        title,
     }
-
-    // Try standalone dash-separated number
-    if let Some(n) = parse_dash_number(title) {
-        return (Some(n), None);
-    }
-
-    (None, None)
-}
-
-// Check if a range match is actually a season indicator (e.g., "S2 - 23" means episode 23 of season 2)
-fn is_season_indicator(title: &str, c: &regex::Captures, season_num: u32) -> bool {
-    let m = c.get(0).unwrap();
-    let start = m.start();
-    let end = m.end();
-
-    // Check for brackets around the match
-    let prev = title[..start].chars().rev().find(|ch| !ch.is_whitespace());
-    let next = title[end..].chars().find(|ch| !ch.is_whitespace());
-    let bracketed = matches!(prev, Some('[') | Some('(') | Some('（') | Some('【'))
-        || matches!(next, Some(']') | Some(')') | Some('）') | Some('】'));
-
-    // Check for END/全集/完 keywords
-    let has_end_marker = m.as_str().to_lowercase().contains("end")
-        || m.as_str().contains("全集")
-        || m.as_str().contains("完");
-
-    if bracketed || has_end_marker {
-        return false;
-    }
-
-    // Check for "S{N}" or "{N} /" or "{N}-\" patterns that indicate season
-    let season_pattern = format!(r"(?i)\bS{}\b", season_num);
-    let has_season_marker = Regex::new(&season_pattern)
-        .ok()
-        .map(|r| r.is_match(title))
-        .unwrap_or(false)
-        || title.contains(&format!(" {} /", season_num))
-        || title.contains(&format!(" {}-", season_num))
-        || title.contains(&format!(" {} -", season_num));
-
-    has_season_marker
-}
-
-// Parse dash-separated numbers, excluding "1080p"-like patterns
-fn parse_dash_number(title: &str) -> Option<u32> {
-    // Remove parenthesized parts to avoid false matches
-    let main = title
-        .split_once('(')
-        .map(|(a, _)| a)
-        .unwrap_or(title)
-        .split_once('（')
-        .map(|(a, _)| a)
-        .unwrap_or(title);
-
-    RE_DASH_NUM
-        .captures_iter(main)
-        .filter_map(|c| {
-            let m = c.get(1)?;
-            let s = m.as_str();
-            let end = m.end();
-
-            // Skip if followed by 'p' or 'P' (resolution like 1080p)
-            let next = main[end..].chars().next();
-            if matches!(next, Some('p') | Some('P')) {
-                return None;
-            }
-
-            s.parse::<u32>().ok()
-        })
-        .last()
 }
 
 fn parse_subtitle(title: &str, desc: Option<&str>) -> (Option<String>, Option<String>) {
@@ -325,84 +281,61 @@ fn parse_subtitle(title: &str, desc: Option<&str>) -> (Option<String>, Option<St
     desc.map_or((None, None), |d| parse_subtitle_text(d))
 }
 
-fn parse_subtitle_text(text: &str) -> (Option<String>, Option<String>) {
-    let s = text.to_lowercase();
-
-    // Language detection (priority order matters)
-    let lang = if s.contains("简繁日") {
-        Some("简繁日")
-    } else if s.contains("简日") {
-        Some("简日")
-    } else if s.contains("简繁") || s.contains("chs&cht") || s.contains("chs+cht") {
-        Some("简繁")
-    } else if s.contains("简体") || s.contains("chs") || s.contains("gb") {
-        Some("简体")
-    } else if s.contains("繁体") || s.contains("cht") || s.contains("big5") {
-        Some("繁体")
-    } else if s.contains("繁日") {
-        Some("繁日")
-    } else {
-        None
-    };
-
-    // Type detection
-    let typ = if s.contains("外挂") || s.contains("external") {
-        Some("外挂")
-    } else if s.contains("内封")
-        || s.contains("内嵌")
-        || s.contains("内置")
-        || s.contains("softsub")
-    {
-        Some("内封")
-    } else if s.contains("硬字幕") || s.contains("hardsub") {
-        Some("硬字幕")
-    } else {
-        None
-    };
-
-    (lang.map(str::to_string), typ.map(str::to_string))
+/// 测试用资源：只有标题，其余信息由标题解析
+#[cfg(test)]
+pub(crate) fn test_item(title: &str) -> MikanResourceItem {
+    build_item(
+        RawItem {
+            title: title.to_string(),
+            page_url: title.to_string(),
+            torrent_url: None,
+            magnet: None,
+            pub_date: None,
+            size_bytes: None,
+            description: None,
+        },
+        ResourceSource::Mikan,
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::release::ReleaseInfo;
 
-    #[test]
-    fn test_parse_group_brackets() {
-        let t = "【动漫国字幕组】★01月新番[恋上换装娃娃 / 更衣人偶坠入爱河][01-12(全集)][1080P][简繁外挂][MKV]";
-        assert_eq!(parse_group(t).as_deref(), Some("动漫国字幕组"));
-    }
-
-    #[test]
-    fn test_parse_group_square() {
-        let t =
-            "[H-Enc] 更衣人偶坠入爱河 / Sono Bisque Doll wa Koi wo Suru (BDRip 1080p HEVC FLAC)";
-        assert_eq!(parse_group(t).as_deref(), Some("H-Enc"));
-    }
-
-    #[test]
-    fn test_parse_episode_range() {
-        let t = "【动漫国字幕组】恋上换装娃娃 [01-12(全集)] 1080P 简繁外挂";
-        let (ep, range) = parse_episode_info(t);
-        assert!(ep.is_none());
-        // format! 会将数字转换为无前导零的形式
-        assert_eq!(range.as_deref(), Some("1-12"));
+    fn item(page_url: &str) -> MikanResourceItem {
+        MikanResourceItem {
+            title: page_url.to_string(),
+            page_url: page_url.to_string(),
+            torrent_url: None,
+            magnet: None,
+            pub_date: None,
+            size_bytes: None,
+            group: None,
+            episode: None,
+            episode_range: None,
+            resolution: None,
+            subtitle_lang: None,
+            subtitle_type: None,
+            mikan_bangumi_id: None,
+            source: ResourceSource::Mikan,
+            release: ReleaseInfo::default(),
+        }
     }
 
     #[test]
-    fn test_parse_episode_single() {
-        let t = "[Lilith-Raws] 更衣人偶坠入爱河 - 第07话 1080p";
-        let (ep, range) = parse_episode_info(t);
-        assert_eq!(ep, Some(7));
-        assert!(range.is_none());
+    fn subgroup_feeds_use_their_own_cache_key() {
+        let (key, path) = feed_key_and_path(3141, Some(583));
+        assert_eq!(key, "mikan:rss:3141:583");
+        assert_eq!(path, "/RSS/Bangumi?bangumiId=3141&subgroupid=583");
+        assert_eq!(feed_key_and_path(3141, None).0, "mikan:rss:3141");
     }
 
     #[test]
-    fn test_parse_episode_season_dash_episode() {
-        let t = "[黒ネズミたち] 拥有超常技能的异世界流浪美食家 S2 / Tondemo Skill de Isekai Hourou Meshi 2 - 23 (ABEMA 1920x1080 AVC AAC MP4)";
-        let (ep, range) = parse_episode_info(t);
-        assert_eq!(ep, Some(23));
-        assert!(range.is_none());
+    fn merged_feeds_drop_duplicate_pages() {
+        let merged = merge_feeds(vec![vec![item("a"), item("b")], vec![item("b"), item("c")]]);
+        let urls: Vec<&str> = merged.iter().map(|i| i.page_url.as_str()).collect();
+        assert_eq!(urls, vec!["a", "b", "c"]);
     }
 
     #[test]
@@ -416,45 +349,4 @@ mod tests {
         assert_eq!(lang2.as_deref(), Some("繁体"));
         assert_eq!(typ2.as_deref(), Some("内封"));
     }
-
-    #[test]
-    fn test_parse_episode_special_oad() {
-        let t = "【DHR动研字幕组】[魔物娘的同居日常_Everyday Life with Monster Girls][OAD2][繁体][720P][MP4]";
-        let (ep, range) = parse_episode_info(t);
-        assert_eq!(ep, Some(2));
-        assert!(range.is_none());
-    }
-
-    #[test]
-    fn test_parse_episode_special_ova() {
-        let t = "[Group] Anime Title [OVA1][1080P]";
-        let (ep, range) = parse_episode_info(t);
-        assert_eq!(ep, Some(1));
-        assert!(range.is_none());
-    }
-
-    #[test]
-    fn test_parse_episode_special_sp() {
-        let t = "【字幕组】作品名 [SP3] 繁体";
-        let (ep, range) = parse_episode_info(t);
-        assert_eq!(ep, Some(3));
-        assert!(range.is_none());
-    }
-
-    #[test]
-    fn test_parse_episode_special_tokuten() {
-        let t = "【字幕组】作品名 【特典5】 1080P";
-        let (ep, range) = parse_episode_info(t);
-        assert_eq!(ep, Some(5));
-        assert!(range.is_none());
-    }
-
-    #[test]
-    fn test_parse_episode_bracket_backward_compatibility() {
-        // 确保原有的纯数字括号还能工作
-        let t = "[Group] Anime Title [12][1080P]";
-        let (ep, range) = parse_episode_info(t);
-        assert_eq!(ep, Some(12));
-        assert!(range.is_none());
-    }
 }
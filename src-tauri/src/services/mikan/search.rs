@@ -1,13 +1,11 @@
 use crate::error::AppError;
-use crate::infra::config::MIKAN_HOST;
-use crate::infra::http::CLIENT;
+use crate::services::mikan::host;
 use reqwest::StatusCode;
 use scraper::{Html, Selector};
 use std::collections::HashSet;
 
 pub async fn search_candidates(name: &str) -> Result<Vec<u32>, AppError> {
-    let url = format!("{}/Home/Search", MIKAN_HOST);
-    let resp = CLIENT.get(url).query(&[("searchstr", name)]).send().await?;
+    let resp = host::send("/Home/Search", |b| b.query(&[("searchstr", name)])).await?;
     if resp.status() != StatusCode::OK {
         resp.error_for_status_ref()?;
     }
@@ -15,7 +13,7 @@ pub async fn search_candidates(name: &str) -> Result<Vec<u32>, AppError> {
     Ok(parse_bangumi_ids(&html))
 }
 
-fn parse_bangumi_id(href: &str) -> Option<u32> {
+pub(crate) fn parse_bangumi_id(href: &str) -> Option<u32> {
     let p = "/Home/Bangumi/";
     if let Some(pos) = href.find(p) {
         let s = &href[pos + p.len()..];
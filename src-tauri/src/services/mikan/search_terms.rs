@@ -0,0 +1,144 @@
+use crate::models::bangumi::InfoItem;
+use crate::utils::romaji::romanize;
+use crate::utils::string::{generate_search_terms_by_stripping, normalize_name, replace_and_split};
+
+/// 单次解析最多尝试的搜索词数，每个词都是一次 Mikan 搜索请求
+const MAX_SEARCH_TERMS: usize = 12;
+/// 剔除词后过短的搜索词命中面太广，不再尝试
+const MIN_TERM_CHARS: usize = 2;
+
+fn infobox_values<'a>(infobox: &'a [InfoItem], key: &'a str) -> impl Iterator<Item = &'a str> {
+    infobox
+        .iter()
+        .filter(move |item| item.key == key)
+        .flat_map(|item| item.values.iter().map(String::as_str))
+}
+
+/// 条目的候选名称，按估计的精确度排列：
+/// 中文名 → 信息框中文名 → 原名 → 别名 → 纯假名标题的罗马字
+pub fn subject_names(name: &str, name_cn: &str, infobox: &[InfoItem]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut push = |n: String| {
+        let n = n.trim().to_string();
+        if !n.is_empty() && !names.iter().any(|x| x.eq_ignore_ascii_case(&n)) {
+            names.push(n);
+        }
+    };
+    push(normalize_name(name, name_cn));
+    for alias in infobox_values(infobox, "中文名") {
+        push(normalize_name("", alias));
+    }
+    push(name.to_string());
+    for alias in infobox_values(infobox, "别名") {
+        push(alias.to_string());
+    }
+    let romaji: Vec<String> = std::iter::once(name)
+        .chain(infobox_values(infobox, "别名"))
+        .filter_map(romanize)
+        .collect();
+    for r in romaji {
+        push(r);
+    }
+    names
+}
+
+/// 由候选名称生成搜索词并去重。
+///
+/// 先尝试全部完整名称，再尝试符号替换后的形式，最后按名称顺序逐级剔除末尾的词。
+pub fn build(names: &[String]) -> Vec<String> {
+    let split: Vec<Vec<String>> = names.iter().map(|n| replace_and_split(n)).collect();
+    let stripped: Vec<Vec<String>> = split
+        .iter()
+        .map(|words| generate_search_terms_by_stripping(words))
+        .collect();
+    let levels = stripped.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut terms: Vec<String> = Vec::new();
+    let mut push = |t: &str| {
+        let t = t.trim();
+        if t.chars().filter(|c| c.is_alphanumeric()).count() >= MIN_TERM_CHARS
+            && !terms.iter().any(|x| x.eq_ignore_ascii_case(t))
+        {
+            terms.push(t.to_string());
+        }
+    };
+    for name in names {
+        push(name);
+    }
+    for level in 0..levels {
+        for list in &stripped {
+            if let Some(term) = list.get(level) {
+                push(term);
+            }
+        }
+    }
+    terms.truncate(MAX_SEARCH_TERMS);
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(key: &str, values: &[&str]) -> InfoItem {
+        InfoItem {
+            key: key.to_string(),
+            values: values.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn names_are_ordered_and_deduped() {
+        let infobox = vec![
+            info("中文名", &["孤独摇滚！"]),
+            info(
+                "别名",
+                &["孤独摇滚", "Bocchi the Rock!", "ぼっち・ざ・ろっく"],
+            ),
+        ];
+        assert_eq!(
+            subject_names("ぼっち・ざ・ろっく！", "孤独摇滚！", &infobox),
+            vec![
+                "孤独摇滚",
+                "ぼっち・ざ・ろっく！",
+                "Bocchi the Rock!",
+                "ぼっち・ざ・ろっく",
+                "botchi za rokku",
+            ]
+        );
+        // 没有中文名时以原名开头
+        assert_eq!(subject_names("SPY×FAMILY", "", &[]), vec!["SPY×FAMILY"]);
+    }
+
+    #[test]
+    fn full_names_come_before_stripped_terms() {
+        let names = vec![
+            "辉夜大小姐想让我告白-超级浪漫".to_string(),
+            "かぐや様は告らせたい-ウルトラロマンティック-".to_string(),
+            "Kaguya-sama wa Kokurasetai".to_string(),
+        ];
+        assert_eq!(
+            build(&names),
+            vec![
+                "辉夜大小姐想让我告白-超级浪漫",
+                "かぐや様は告らせたい-ウルトラロマンティック-",
+                "Kaguya-sama wa Kokurasetai",
+                "辉夜大小姐想让我告白 超级浪漫",
+                "かぐや様は告らせたい ウルトラロマンティック",
+                "Kaguya sama wa Kokurasetai",
+                "辉夜大小姐想让我告白",
+                "かぐや様は告らせたい",
+                "Kaguya sama wa",
+                "Kaguya sama",
+                "Kaguya",
+            ]
+        );
+    }
+
+    #[test]
+    fn short_terms_are_dropped_and_list_is_capped() {
+        assert_eq!(build(&["A B".to_string()]), vec!["A B"]);
+        let names: Vec<String> = (0..20).map(|i| format!("标题{i}")).collect();
+        assert_eq!(build(&names).len(), MAX_SEARCH_TERMS);
+    }
+}
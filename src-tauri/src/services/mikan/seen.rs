@@ -0,0 +1,161 @@
+use crate::models::mikan::MikanResourceItem;
+use crate::services::mikan::episode_offset::shift;
+use std::collections::BTreeSet;
+
+/// 订阅见过的一集资源。编号取标题中的原始编号，调整集数偏移后不会重复提醒。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EpisodeKey {
+    Episode(u32),
+    /// 修正版重发，如 `05v2`
+    Revision(u32, u32),
+    /// OVA / SP 等；标题中没有编号时为 0
+    Special(u32),
+    Batch(u32, u32),
+}
+
+impl EpisodeKey {
+    /// `seen_episodes.episode_key` 中的存储形式
+    pub fn encode(self) -> String {
+        match self {
+            Self::Episode(n) => format!("ep:{n}"),
+            Self::Revision(n, v) => format!("ep:{n}v{v}"),
+            Self::Special(n) => format!("sp:{n}"),
+            Self::Batch(start, end) => format!("batch:{start}-{end}"),
+        }
+    }
+
+    /// 通知中的名称；正片与合集按偏移换算为 Bangumi 编号
+    pub fn label(self, offset: i32) -> String {
+        let ep = |n: u32| shift(n, offset).unwrap_or(n);
+        match self {
+            Self::Episode(n) => format!("第 {} 话", ep(n)),
+            Self::Revision(n, v) => format!("第 {} 话 v{}", ep(n), v),
+            Self::Special(0) => "SP".to_string(),
+            Self::Special(n) => format!("SP{n}"),
+            Self::Batch(start, end) => format!("第 {}-{} 话合集", ep(start), ep(end)),
+        }
+    }
+}
+
+fn item_keys(item: &MikanResourceItem) -> Vec<EpisodeKey> {
+    let release = &item.release;
+    if release.special {
+        return vec![EpisodeKey::Special(release.episode.unwrap_or(0))];
+    }
+    if let Some(range) = release.episode_range {
+        return vec![EpisodeKey::Batch(range.start, range.end)];
+    }
+    let Some(n) = release.episode else {
+        return Vec::new();
+    };
+    let mut keys = vec![EpisodeKey::Episode(n)];
+    if let Some(v) = release.version {
+        keys.push(EpisodeKey::Revision(n, v));
+    }
+    keys
+}
+
+/// 资源是否属于某一集
+pub fn has_key(item: &MikanResourceItem, key: EpisodeKey) -> bool {
+    item_keys(item).contains(&key)
+}
+
+/// 资源列表中出现的全部集，去重并排序
+pub fn episode_keys(items: &[MikanResourceItem]) -> Vec<EpisodeKey> {
+    let keys: BTreeSet<EpisodeKey> = items.iter().flat_map(item_keys).collect();
+    keys.into_iter().collect()
+}
+
+/// 从新出现的集中挑出需要提醒的。
+///
+/// 首次运行（`backfill`）只记录不提醒，但仍提醒超过旧版 `last_seen_ep` 水位的正片，
+/// 以免升级时漏掉刚发布的一集。同一集的首版与修正版同时出现时只提醒首版。
+pub fn to_notify(
+    new: &[EpisodeKey],
+    backfill: bool,
+    last_seen_ep: u32,
+    offset: i32,
+) -> Vec<EpisodeKey> {
+    if backfill {
+        if last_seen_ep == 0 {
+            return Vec::new();
+        }
+        return new
+            .iter()
+            .copied()
+            .filter(|k| match k {
+                EpisodeKey::Episode(n) => shift(*n, offset).is_some_and(|e| e > last_seen_ep),
+                _ => false,
+            })
+            .collect();
+    }
+    new.iter()
+        .copied()
+        .filter(|k| match k {
+            EpisodeKey::Revision(n, _) => !new.contains(&EpisodeKey::Episode(*n)),
+            _ => true,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mikan::rss::test_item;
+
+    #[test]
+    fn keys_cover_specials_batches_and_revisions() {
+        let items = vec![
+            test_item("[ANi] 葬送的芙莉莲 - 05 [1080P]"),
+            test_item("[LoliHouse] 葬送的芙莉莲 - 05v2 [1080p]"),
+            test_item("[Other] 葬送的芙莉莲 - 03 [720p]"),
+            test_item("[Group] 葬送的芙莉莲 [OVA1][1080P]"),
+            test_item("[Group] 葬送的芙莉莲 [01-12 合集][1080p]"),
+            test_item("葬送的芙莉莲 无集数"),
+        ];
+        let keys: Vec<String> = episode_keys(&items)
+            .into_iter()
+            .map(EpisodeKey::encode)
+            .collect();
+        assert_eq!(keys, vec!["ep:3", "ep:5", "ep:5v2", "sp:1", "batch:1-12"]);
+    }
+
+    #[test]
+    fn labels_use_bangumi_numbering() {
+        assert_eq!(EpisodeKey::Episode(3).label(12), "第 15 话");
+        assert_eq!(EpisodeKey::Revision(5, 2).label(0), "第 5 话 v2");
+        assert_eq!(EpisodeKey::Special(1).label(12), "SP1");
+        assert_eq!(EpisodeKey::Batch(13, 24).label(-12), "第 1-12 话合集");
+    }
+
+    #[test]
+    fn backfill_is_silent_except_past_legacy_high_water_mark() {
+        let new = vec![
+            EpisodeKey::Episode(4),
+            EpisodeKey::Episode(5),
+            EpisodeKey::Special(1),
+        ];
+        assert!(to_notify(&new, true, 0, 0).is_empty());
+        assert_eq!(to_notify(&new, true, 4, 0), vec![EpisodeKey::Episode(5)]);
+        // 水位是换算后的编号
+        assert!(to_notify(&new, true, 17, 12).is_empty());
+    }
+
+    #[test]
+    fn late_releases_and_revisions_are_notified() {
+        let new = vec![
+            EpisodeKey::Episode(2),
+            EpisodeKey::Revision(2, 2),
+            EpisodeKey::Revision(7, 2),
+            EpisodeKey::Batch(1, 12),
+        ];
+        assert_eq!(
+            to_notify(&new, false, 8, 0),
+            vec![
+                EpisodeKey::Episode(2),
+                EpisodeKey::Revision(7, 2),
+                EpisodeKey::Batch(1, 12)
+            ]
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::error::AppError;
+use crate::models::mikan::{MikanResourceItem, ResourceWatch, ResourceWatchInput};
+use crate::services::downloader::lifecycle;
+use crate::services::mikan::{episode_offset, rss, watch_store};
+use crate::services::sources::archive::resource_key;
+use crate::services::subscriptions;
+use regex::Regex;
+use tracing::{info, warn};
+
+/// 校验并规整输入：关键词必填，正则必须能编译
+pub fn normalize(mut input: ResourceWatchInput) -> Result<ResourceWatchInput, AppError> {
+    input.keyword = input.keyword.trim().to_string();
+    if input.keyword.is_empty() {
+        return Err(AppError::Any("watch keyword is empty".into()));
+    }
+    input.name = match input.name.trim() {
+        "" => input.keyword.clone(),
+        name => name.to_string(),
+    };
+    input.pattern = input
+        .pattern
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    if let Some(pattern) = &input.pattern {
+        Regex::new(pattern).map_err(|e| AppError::Any(format!("invalid watch pattern: {e}")))?;
+    }
+    Ok(input)
+}
+
+pub async fn list() -> Result<Vec<ResourceWatch>, AppError> {
+    watch_store::list().await
+}
+
+pub async fn add(input: ResourceWatchInput) -> Result<ResourceWatch, AppError> {
+    watch_store::insert(normalize(input)?).await
+}
+
+pub async fn update(id: u32, input: ResourceWatchInput) -> Result<ResourceWatch, AppError> {
+    watch_store::update(id, normalize(input)?)
+        .await?
+        .ok_or_else(|| AppError::Any(format!("resource watch {id} not found")))
+}
+
+pub async fn delete(id: u32) -> Result<(), AppError> {
+    watch_store::delete(id).await
+}
+
+/// 搜索结果中标题匹配正则的资源；没有正则时全部保留
+pub fn matching(items: Vec<MikanResourceItem>, pattern: Option<&Regex>) -> Vec<MikanResourceItem> {
+    items
+        .into_iter()
+        .filter(|it| pattern.is_none_or(|re| re.is_match(&it.title)))
+        .collect()
+}
+
+/// 下载到指定条目，集数按该条目订阅的偏移换算
+async fn download(subject_id: u32, item: MikanResourceItem) -> Result<(), AppError> {
+    let url = item
+        .torrent_url
+        .clone()
+        .or_else(|| item.magnet.clone())
+        .ok_or_else(|| AppError::Any("resource has no torrent or magnet link".into()))?;
+    let offset = subscriptions::get_episode_offset(subject_id)
+        .await?
+        .unwrap_or(0);
+    let mut items = [item];
+    episode_offset::apply(&mut items, offset);
+    let [item] = items;
+    lifecycle::add_for_subject(
+        url,
+        subject_id,
+        item.episode,
+        item.episode_range,
+        item.title,
+    )
+    .await
+}
+
+/// 轮询一个关键词订阅，返回新资源数。首次轮询只记录不提醒。
+async fn poll_watch(watch: &ResourceWatch) -> Result<usize, AppError> {
+    let pattern = watch
+        .pattern
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| AppError::Any(format!("invalid watch pattern: {e}")))?;
+    let items = matching(
+        rss::fetch_search_rss(&watch.keyword).await?,
+        pattern.as_ref(),
+    );
+    let keys: Vec<String> = items.iter().map(resource_key).collect();
+    let (backfill, new_keys) = watch_store::record_hits(watch.id, keys).await?;
+    if backfill || new_keys.is_empty() {
+        return Ok(0);
+    }
+    let new: Vec<MikanResourceItem> = items
+        .into_iter()
+        .filter(|it| new_keys.contains(&resource_key(it)))
+        .collect();
+    info!(
+        watch_id = watch.id,
+        new = new.len(),
+        "resource watch matched"
+    );
+
+    let titles: Vec<String> = new.iter().map(|it| it.title.clone()).collect();
+    if let Err(e) = crate::infra::notification::notify_watch_match(&watch.name, &titles) {
+        warn!(error = %e, watch_id = watch.id, "resource watch notification failed");
+    }
+    let count = new.len();
+    if let Some(subject_id) = watch.download_subject_id {
+        for item in new {
+            let title = item.title.clone();
+            if let Err(e) = download(subject_id, item).await {
+                warn!(error = %e, watch_id = watch.id, title, "resource watch download failed");
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// 轮询所有启用的关键词订阅，返回新资源总数
+pub(super) async fn poll_once() -> Result<usize, AppError> {
+    let mut total = 0;
+    for watch in watch_store::list().await?.iter().filter(|w| w.enabled) {
+        match poll_watch(watch).await {
+            Ok(n) => total += n,
+            Err(e) => warn!(error = %e, watch_id = watch.id, "resource watch poll failed"),
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mikan::rss::test_item;
+
+    fn input(keyword: &str, pattern: Option<&str>) -> ResourceWatchInput {
+        ResourceWatchInput {
+            name: " ".into(),
+            keyword: keyword.into(),
+            pattern: pattern.map(str::to_string),
+            download_subject_id: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn normalize_validates_keyword_and_pattern() {
+        let ok = normalize(input(" 芙莉莲 ", Some(" "))).unwrap();
+        assert_eq!(ok.keyword, "芙莉莲");
+        assert_eq!(ok.name, "芙莉莲");
+        assert_eq!(ok.pattern, None);
+        assert!(normalize(input("  ", None)).is_err());
+        assert!(normalize(input("芙莉莲", Some("(unclosed"))).is_err());
+    }
+
+    #[test]
+    fn pattern_filters_search_results() {
+        let items = vec![
+            test_item("[VCB-Studio] Sousou no Frieren [Ma10p_1080p][BDRip]"),
+            test_item("[ANi] 葬送的芙莉莲 - 05 [1080P][Baha]"),
+        ];
+        let re = Regex::new(r"(?i)vcb-studio.*bdrip").unwrap();
+        let matched = matching(items, Some(&re));
+        assert_eq!(matched.len(), 1);
+        assert!(matched[0].title.starts_with("[VCB-Studio]"));
+    }
+}
@@ -0,0 +1,247 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::error::AppError;
+use crate::infra::time::now_secs;
+use crate::models::mikan::{ResourceWatch, ResourceWatchInput};
+
+const WATCH_COLUMNS: &str = "id, name, keyword, pattern, download_subject_id, enabled, created_at";
+
+fn watch_from_row(row: &rusqlite::Row) -> Result<ResourceWatch, rusqlite::Error> {
+    Ok(ResourceWatch {
+        id: row.get::<_, i64>(0)? as u32,
+        name: row.get(1)?,
+        keyword: row.get(2)?,
+        pattern: row.get(3)?,
+        download_subject_id: row.get::<_, Option<i64>>(4)?.map(|v| v as u32),
+        enabled: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+pub async fn list() -> Result<Vec<ResourceWatch>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn.interact(|conn| list_rows(conn)).await??;
+    Ok(out)
+}
+
+fn list_rows(conn: &rusqlite::Connection) -> Result<Vec<ResourceWatch>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {WATCH_COLUMNS} FROM resource_watches ORDER BY created_at DESC, id DESC"
+    ))?;
+    let rows = stmt.query_map([], watch_from_row)?;
+    rows.collect()
+}
+
+pub async fn insert(input: ResourceWatchInput) -> Result<ResourceWatch, AppError> {
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| insert_row(conn, &input, now))
+        .await??;
+    Ok(out)
+}
+
+fn insert_row(
+    conn: &rusqlite::Connection,
+    input: &ResourceWatchInput,
+    now: i64,
+) -> Result<ResourceWatch, rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO resource_watches(name, keyword, pattern, download_subject_id, enabled, created_at)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            input.name,
+            input.keyword,
+            input.pattern,
+            input.download_subject_id.map(|v| v as i64),
+            input.enabled,
+            now
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    conn.query_row(
+        &format!("SELECT {WATCH_COLUMNS} FROM resource_watches WHERE id = ?1"),
+        params![id],
+        watch_from_row,
+    )
+}
+
+/// 修改关键词或正则后匹配范围变了，重新回填一次，避免把旧资源当成新资源提醒
+pub async fn update(id: u32, input: ResourceWatchInput) -> Result<Option<ResourceWatch>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| update_row(conn, id, &input))
+        .await??;
+    Ok(out)
+}
+
+fn update_row(
+    conn: &mut rusqlite::Connection,
+    id: u32,
+    input: &ResourceWatchInput,
+) -> Result<Option<ResourceWatch>, rusqlite::Error> {
+    let transaction = conn.transaction()?;
+    let changed = transaction.execute(
+        "UPDATE resource_watches
+         SET initialized = CASE WHEN keyword = ?2 AND pattern IS ?3 THEN initialized ELSE 0 END,
+             name = ?1, keyword = ?2, pattern = ?3, download_subject_id = ?4, enabled = ?5
+         WHERE id = ?6",
+        params![
+            input.name,
+            input.keyword,
+            input.pattern,
+            input.download_subject_id.map(|v| v as i64),
+            input.enabled,
+            id as i64
+        ],
+    )?;
+    if changed == 0 {
+        return Ok(None);
+    }
+    transaction.execute(
+        "DELETE FROM resource_watch_hits
+         WHERE watch_id = ?1
+           AND (SELECT initialized FROM resource_watches WHERE id = ?1) = 0",
+        params![id as i64],
+    )?;
+    let watch = transaction
+        .query_row(
+            &format!("SELECT {WATCH_COLUMNS} FROM resource_watches WHERE id = ?1"),
+            params![id as i64],
+            watch_from_row,
+        )
+        .optional()?;
+    transaction.commit()?;
+    Ok(watch)
+}
+
+pub async fn delete(id: u32) -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| delete_row(conn, id)).await??;
+    Ok(())
+}
+
+fn delete_row(conn: &mut rusqlite::Connection, id: u32) -> Result<(), rusqlite::Error> {
+    let transaction = conn.transaction()?;
+    transaction.execute(
+        "DELETE FROM resource_watch_hits WHERE watch_id = ?1",
+        params![id as i64],
+    )?;
+    transaction.execute(
+        "DELETE FROM resource_watches WHERE id = ?1",
+        params![id as i64],
+    )?;
+    transaction.commit()
+}
+
+/// 写入本次匹配到的资源，返回此前未见过的键；`bool` 为首次记录（回填）
+pub async fn record_hits(id: u32, keys: Vec<String>) -> Result<(bool, Vec<String>), AppError> {
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| record_hits_row(conn, id, &keys, now))
+        .await??;
+    Ok(out)
+}
+
+fn record_hits_row(
+    conn: &mut rusqlite::Connection,
+    id: u32,
+    keys: &[String],
+    now: i64,
+) -> Result<(bool, Vec<String>), rusqlite::Error> {
+    let transaction = conn.transaction()?;
+    let initialized: bool = transaction
+        .query_row(
+            "SELECT initialized FROM resource_watches WHERE id = ?1",
+            params![id as i64],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(false);
+    let mut new = Vec::new();
+    {
+        let mut stmt = transaction.prepare(
+            "INSERT OR IGNORE INTO resource_watch_hits(watch_id, resource_key, first_seen)
+             VALUES(?1, ?2, ?3)",
+        )?;
+        for key in keys {
+            if stmt.execute(params![id as i64, key, now])? > 0 {
+                new.push(key.clone());
+            }
+        }
+    }
+    transaction.execute(
+        "UPDATE resource_watches SET initialized = 1 WHERE id = ?1",
+        params![id as i64],
+    )?;
+    transaction.commit()?;
+    Ok((!initialized, new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(keyword: &str, pattern: Option<&str>) -> ResourceWatchInput {
+        ResourceWatchInput {
+            name: "BD".into(),
+            keyword: keyword.into(),
+            pattern: pattern.map(str::to_string),
+            download_subject_id: Some(400602),
+            enabled: true,
+        }
+    }
+
+    fn keys(ks: &[&str]) -> Vec<String> {
+        ks.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn hits_backfill_once_and_reset_when_query_changes() {
+        let mut conn = crate::infra::db::test_data_connection();
+        let watch = insert_row(&conn, &input("芙莉莲", Some("(?i)bdrip")), 10).unwrap();
+        assert_eq!(watch.download_subject_id, Some(400602));
+        assert_eq!(list_rows(&conn).unwrap(), vec![watch.clone()]);
+
+        let (backfill, new) = record_hits_row(&mut conn, watch.id, &keys(&["a", "b"]), 10).unwrap();
+        assert!(backfill);
+        assert_eq!(new, keys(&["a", "b"]));
+        let (backfill, new) = record_hits_row(&mut conn, watch.id, &keys(&["b", "c"]), 20).unwrap();
+        assert!(!backfill);
+        assert_eq!(new, keys(&["c"]));
+
+        // 只改名称不影响已记录的资源
+        let mut renamed = input("芙莉莲", Some("(?i)bdrip"));
+        renamed.name = "芙莉莲 BD".into();
+        assert_eq!(
+            update_row(&mut conn, watch.id, &renamed)
+                .unwrap()
+                .unwrap()
+                .name,
+            "芙莉莲 BD"
+        );
+        assert_eq!(
+            record_hits_row(&mut conn, watch.id, &keys(&["c"]), 30).unwrap(),
+            (false, vec![])
+        );
+
+        // 修改关键词后重新回填
+        update_row(&mut conn, watch.id, &input("Frieren", None)).unwrap();
+        assert_eq!(
+            record_hits_row(&mut conn, watch.id, &keys(&["c"]), 40).unwrap(),
+            (true, keys(&["c"]))
+        );
+
+        assert!(update_row(&mut conn, 999, &input("x", None))
+            .unwrap()
+            .is_none());
+        delete_row(&mut conn, watch.id).unwrap();
+        assert!(list_rows(&conn).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,450 @@
+use crate::error::AppError;
+use crate::infra::time::now_secs;
+use crate::models::mikan::{MikanResourceItem, ResourceQuery, ResourceSource};
+use crate::services::mikan::config::SourceSettings;
+use crate::services::mikan::episode_offset;
+use crate::services::mikan::rss::{build_item, RawItem};
+use crate::services::subscriptions;
+use rusqlite::params;
+use std::collections::HashSet;
+
+const DEFAULT_LIMIT: u32 = 500;
+/// 其他站点按关键词搜索，结果未按番组过滤，长期未再出现的不再保留
+const SEARCH_RESOURCE_TTL_SECS: i64 = 30 * 24 * 3600;
+
+/// 条目当前映射到的全部 Mikan 番组（主映射与附加番组）
+const LINKED_MIKAN_IDS: &str = "SELECT mikan_bangumi_id FROM mikan_bangumi_map WHERE bgm_subject_id = resources.subject_id
+     UNION SELECT mikan_bangumi_id FROM mikan_bangumi_links WHERE bgm_subject_id = resources.subject_id";
+
+/// 存档主键：info hash，其次种子链接，最后是页面链接
+pub fn resource_key(item: &MikanResourceItem) -> String {
+    super::info_hash(item)
+        .or_else(|| item.torrent_url.clone())
+        .unwrap_or_else(|| item.page_url.clone())
+}
+
+/// 写入存档的一行；集数保存标题中的原始编号，读取时再换算偏移
+struct ArchiveRow {
+    key: String,
+    source: &'static str,
+    title: String,
+    page_url: String,
+    torrent_url: Option<String>,
+    magnet: Option<String>,
+    pub_date: Option<String>,
+    size_bytes: Option<u64>,
+    group: Option<String>,
+    episode: Option<u32>,
+    range: Option<(u32, u32)>,
+    resolution: Option<u32>,
+    subtitle_lang: Option<String>,
+    subtitle_type: Option<String>,
+    mikan_bangumi_id: Option<u32>,
+}
+
+impl ArchiveRow {
+    fn from_item(item: &MikanResourceItem) -> Self {
+        Self {
+            key: resource_key(item),
+            source: item.source.as_str(),
+            title: item.title.clone(),
+            page_url: item.page_url.clone(),
+            torrent_url: item.torrent_url.clone(),
+            magnet: item.magnet.clone(),
+            pub_date: item.pub_date.clone(),
+            size_bytes: item.size_bytes,
+            group: item.group.clone(),
+            episode: item.release.episode,
+            range: item.release.episode_range.map(|r| (r.start, r.end)),
+            resolution: item.resolution,
+            subtitle_lang: item.subtitle_lang.clone(),
+            subtitle_type: item.subtitle_type.clone(),
+            mikan_bangumi_id: item.mikan_bangumi_id,
+        }
+    }
+}
+
+pub async fn record(subject_id: u32, items: &[MikanResourceItem]) -> Result<(), AppError> {
+    if items.is_empty() {
+        return Ok(());
+    }
+    let rows: Vec<ArchiveRow> = items.iter().map(ArchiveRow::from_item).collect();
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| record_rows(conn, subject_id, &rows, now))
+        .await??;
+    Ok(())
+}
+
+fn record_rows(
+    conn: &mut rusqlite::Connection,
+    subject_id: u32,
+    rows: &[ArchiveRow],
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO resources(subject_id, resource_key, source, title, page_url, torrent_url,
+                 magnet, pub_date, size_bytes, group_name, episode, range_start, range_end,
+                 resolution, subtitle_lang, subtitle_type, mikan_bangumi_id, first_seen, last_seen)
+             VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?18)
+             ON CONFLICT(subject_id, resource_key) DO UPDATE SET
+                 last_seen = excluded.last_seen,
+                 torrent_url = COALESCE(excluded.torrent_url, torrent_url),
+                 magnet = COALESCE(excluded.magnet, magnet),
+                 mikan_bangumi_id = COALESCE(excluded.mikan_bangumi_id, mikan_bangumi_id)",
+        )?;
+        for r in rows {
+            stmt.execute(params![
+                subject_id as i64,
+                r.key,
+                r.source,
+                r.title,
+                r.page_url,
+                r.torrent_url,
+                r.magnet,
+                r.pub_date,
+                r.size_bytes.map(|v| v as i64),
+                r.group,
+                r.episode.map(|v| v as i64),
+                r.range.map(|(s, _)| s as i64),
+                r.range.map(|(_, e)| e as i64),
+                r.resolution.map(|v| v as i64),
+                r.subtitle_lang,
+                r.subtitle_type,
+                r.mikan_bangumi_id.map(|v| v as i64),
+                now,
+            ])?;
+        }
+    }
+    tx.execute(
+        "DELETE FROM resources WHERE subject_id = ?1 AND source != 'mikan' AND last_seen < ?2",
+        params![subject_id as i64, now - SEARCH_RESOURCE_TTL_SECS],
+    )?;
+    tx.commit()
+}
+
+/// 删除来自已不再映射的 Mikan 番组的存档，映射改变后旧番组的资源不再混入
+pub async fn prune_unlinked() -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let removed = conn.interact(|conn| prune_unlinked_rows(conn)).await??;
+    if removed > 0 {
+        tracing::info!(
+            removed,
+            "pruned archived resources of unlinked mikan bangumi"
+        );
+    }
+    Ok(())
+}
+
+fn prune_unlinked_rows(conn: &rusqlite::Connection) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        &format!(
+            "DELETE FROM resources WHERE source = 'mikan'
+               AND (mikan_bangumi_id IS NULL OR mikan_bangumi_id NOT IN ({LINKED_MIKAN_IDS}))"
+        ),
+        [],
+    )
+}
+
+/// 按首次发现时间倒序；同一批写入的保持 feed 原有顺序。
+/// Mikan 资源只返回当前仍映射的番组的。
+fn query_rows(
+    conn: &rusqlite::Connection,
+    query: &ResourceQuery,
+    raw_episode: Option<i64>,
+) -> Result<Vec<MikanResourceItem>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT source, title, page_url, torrent_url, magnet, pub_date, size_bytes,
+                resolution, subtitle_lang, subtitle_type, mikan_bangumi_id
+         FROM resources
+         WHERE subject_id = ?1
+           AND (source != 'mikan' OR mikan_bangumi_id IN ({LINKED_MIKAN_IDS}))
+           AND (?2 IS NULL OR episode = ?2 OR (range_start <= ?2 AND range_end >= ?2))
+           AND (?3 IS NULL OR instr(LOWER(group_name), LOWER(?3)) > 0)
+           AND (?4 IS NULL OR resolution = ?4)
+         ORDER BY first_seen DESC, rowid ASC
+         LIMIT ?5"
+    ))?;
+    let rows = stmt.query_map(
+        params![
+            query.subject_id as i64,
+            raw_episode,
+            query
+                .group
+                .as_deref()
+                .map(str::trim)
+                .filter(|g| !g.is_empty()),
+            query.resolution.map(|v| v as i64),
+            query.limit.unwrap_or(DEFAULT_LIMIT) as i64,
+        ],
+        |row| {
+            let source: String = row.get(0)?;
+            let mut item = build_item(
+                RawItem {
+                    title: row.get(1)?,
+                    page_url: row.get(2)?,
+                    torrent_url: row.get(3)?,
+                    magnet: row.get(4)?,
+                    pub_date: row.get(5)?,
+                    size_bytes: row.get::<_, Option<i64>>(6)?.map(|v| v as u64),
+                    description: None,
+                },
+                ResourceSource::parse(&source).unwrap_or_default(),
+            );
+            // 分辨率与字幕信息可能来自描述，以入库时的结果为准
+            item.resolution = row.get::<_, Option<i64>>(7)?.map(|v| v as u32);
+            item.subtitle_lang = row.get(8)?;
+            item.subtitle_type = row.get(9)?;
+            item.mikan_bangumi_id = row.get::<_, Option<i64>>(10)?.map(|v| v as u32);
+            item.release.resolution = item.resolution;
+            item.release.language = item.subtitle_lang.clone();
+            item.release.subtitle_type = item.subtitle_type.clone();
+            Ok(item)
+        },
+    )?;
+    rows.collect()
+}
+
+async fn query_raw(
+    query: ResourceQuery,
+    raw_episode: Option<i64>,
+) -> Result<Vec<MikanResourceItem>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| query_rows(conn, &query, raw_episode))
+        .await??;
+    Ok(out)
+}
+
+/// 条目的全部存档资源（原始集数）
+pub async fn list(subject_id: u32) -> Result<Vec<MikanResourceItem>, AppError> {
+    query_raw(
+        ResourceQuery {
+            subject_id,
+            ..Default::default()
+        },
+        None,
+    )
+    .await
+}
+
+/// 按条件查询存档；集数按订阅的偏移换算
+pub async fn query(query: ResourceQuery) -> Result<Vec<MikanResourceItem>, AppError> {
+    let offset = subscriptions::get_episode_offset(query.subject_id)
+        .await?
+        .unwrap_or(0);
+    let raw_episode = query.episode.map(|e| e as i64 - offset as i64);
+    let mut items = query_raw(query, raw_episode).await?;
+    episode_offset::apply(&mut items, offset);
+    Ok(items)
+}
+
+/// 在实时结果后补上 feed 中已经消失的存档资源。
+/// 跳过已禁用站点的资源；固定了字幕组时，Mikan 存档只保留实时结果中出现过的字幕组。
+pub fn merge_archived(
+    live: Vec<MikanResourceItem>,
+    archived: Vec<MikanResourceItem>,
+    settings: &SourceSettings,
+    subgroups_pinned: bool,
+) -> Vec<MikanResourceItem> {
+    let mut seen: HashSet<String> = live.iter().map(resource_key).collect();
+    let live_groups: HashSet<Option<String>> = live
+        .iter()
+        .filter(|it| it.source == ResourceSource::Mikan)
+        .map(|it| it.group.clone())
+        .collect();
+    let mut out = live;
+    for item in archived {
+        if !item.source.is_enabled(settings) {
+            continue;
+        }
+        if subgroups_pinned
+            && item.source == ResourceSource::Mikan
+            && !live_groups.contains(&item.group)
+        {
+            continue;
+        }
+        if seen.insert(resource_key(&item)) {
+            out.push(item);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mikan::rss::test_item;
+
+    fn item(title: &str, torrent: &str, source: ResourceSource) -> MikanResourceItem {
+        let mut item = test_item(title);
+        item.page_url = format!("https://example.com/{torrent}");
+        item.torrent_url = Some(format!("https://example.com/{torrent}.torrent"));
+        item.size_bytes = Some(600 << 20);
+        item.source = source;
+        if source == ResourceSource::Mikan {
+            item.mikan_bangumi_id = Some(100);
+        }
+        item
+    }
+
+    fn map_subject(conn: &rusqlite::Connection, subject_id: u32, mikan_id: u32) {
+        conn.execute(
+            "INSERT OR REPLACE INTO mikan_bangumi_map(bgm_subject_id, mikan_bangumi_id, confidence, source, updated_at)
+             VALUES (?1, ?2, 1.0, 'manual', 0)",
+            params![subject_id, mikan_id],
+        )
+        .unwrap();
+    }
+
+    fn keys(conn: &rusqlite::Connection, subject_id: u32) -> Vec<String> {
+        let query = ResourceQuery {
+            subject_id,
+            ..Default::default()
+        };
+        query_rows(conn, &query, None)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.page_url.rsplit('/').next().unwrap().to_string())
+            .collect()
+    }
+
+    fn rows(items: &[MikanResourceItem]) -> Vec<ArchiveRow> {
+        items.iter().map(ArchiveRow::from_item).collect()
+    }
+
+    #[test]
+    fn archive_keeps_first_seen_and_filters() {
+        let mut conn = crate::infra::db::test_data_connection();
+        map_subject(&conn, 7, 100);
+        let first = vec![
+            item(
+                "[ANi] 葬送的芙莉莲 - 02 [1080P]",
+                "b",
+                ResourceSource::Mikan,
+            ),
+            item(
+                "[ANi] 葬送的芙莉莲 - 01 [1080P]",
+                "a",
+                ResourceSource::Mikan,
+            ),
+        ];
+        record_rows(&mut conn, 7, &rows(&first), 100).unwrap();
+        // feed 滚动后只剩新的一集，旧的仍在存档中
+        let second = vec![
+            item(
+                "[LoliHouse] 葬送的芙莉莲 - 03 [720p]",
+                "c",
+                ResourceSource::Mikan,
+            ),
+            item(
+                "[ANi] 葬送的芙莉莲 - 02 [1080P]",
+                "b",
+                ResourceSource::Mikan,
+            ),
+            item(
+                "[LoliHouse] 葬送的芙莉莲 [01-12][1080p]",
+                "d",
+                ResourceSource::Dmhy,
+            ),
+        ];
+        record_rows(&mut conn, 7, &rows(&second), 200).unwrap();
+
+        let all = |q: ResourceQuery, ep: Option<i64>| -> Vec<String> {
+            query_rows(&conn, &q, ep)
+                .unwrap()
+                .into_iter()
+                .map(|i| i.page_url.rsplit('/').next().unwrap().to_string())
+                .collect()
+        };
+        let base = ResourceQuery {
+            subject_id: 7,
+            ..Default::default()
+        };
+        assert_eq!(all(base.clone(), None), vec!["c", "d", "b", "a"]);
+        assert_eq!(all(base.clone(), Some(1)), vec!["d", "a"]);
+        let by_group = ResourceQuery {
+            group: Some("lolihouse".into()),
+            ..base.clone()
+        };
+        assert_eq!(all(by_group, None), vec!["c", "d"]);
+        let by_resolution = ResourceQuery {
+            resolution: Some(720),
+            ..base.clone()
+        };
+        assert_eq!(all(by_resolution, None), vec!["c"]);
+        let other_subject = ResourceQuery {
+            subject_id: 8,
+            ..Default::default()
+        };
+        assert!(all(other_subject, None).is_empty());
+
+        let first_seen: i64 = conn
+            .query_row(
+                "SELECT first_seen FROM resources WHERE subject_id = 7 AND resource_key LIKE '%/b.torrent'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(first_seen, 100);
+    }
+
+    #[test]
+    fn remapped_subject_drops_old_mikan_resources() {
+        let mut conn = crate::infra::db::test_data_connection();
+        map_subject(&conn, 7, 100);
+        let items = vec![
+            item("[ANi] X - 01 [1080P]", "a", ResourceSource::Mikan),
+            item("[ANi] X - 01 [1080P]", "n", ResourceSource::Nyaa),
+        ];
+        record_rows(&mut conn, 7, &rows(&items), 100).unwrap();
+        assert_eq!(keys(&conn, 7), vec!["a", "n"]);
+
+        // 改绑到另一个番组后，旧番组的资源立即不再返回，清理后从存档删除
+        map_subject(&conn, 7, 200);
+        assert_eq!(keys(&conn, 7), vec!["n"]);
+        assert_eq!(prune_unlinked_rows(&conn).unwrap(), 1);
+        let mut linked = item("[ANi] X - 02 [1080P]", "b", ResourceSource::Mikan);
+        linked.mikan_bangumi_id = Some(200);
+        record_rows(&mut conn, 7, &rows(&[linked]), 200).unwrap();
+        assert_eq!(keys(&conn, 7), vec!["b", "n"]);
+        assert_eq!(prune_unlinked_rows(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn stale_search_results_expire() {
+        let mut conn = crate::infra::db::test_data_connection();
+        map_subject(&conn, 7, 100);
+        let first = vec![
+            item("[ANi] X - 01 [1080P]", "a", ResourceSource::Mikan),
+            item("[ANi] X - 01 [1080P]", "n", ResourceSource::Nyaa),
+        ];
+        record_rows(&mut conn, 7, &rows(&first), 100).unwrap();
+        let later = vec![item("[ANi] X - 02 [1080P]", "b", ResourceSource::Mikan)];
+        record_rows(&mut conn, 7, &rows(&later), 101 + SEARCH_RESOURCE_TTL_SECS).unwrap();
+        assert_eq!(keys(&conn, 7), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn merge_appends_only_missing_enabled_items() {
+        let live = vec![item("[ANi] X - 03 [1080P]", "c", ResourceSource::Mikan)];
+        let archived = vec![
+            item("[ANi] X - 03 [1080P]", "c", ResourceSource::Mikan),
+            item("[ANi] X - 01 [1080P]", "a", ResourceSource::Mikan),
+            item("[Other] X - 01 [1080P]", "o", ResourceSource::Mikan),
+            item("[ANi] X - 01 [1080P]", "n", ResourceSource::Nyaa),
+        ];
+        let settings = SourceSettings::default();
+        let merged = merge_archived(live, archived, &settings, true);
+        let keys: Vec<&str> = merged
+            .iter()
+            .map(|i| i.page_url.rsplit('/').next().unwrap())
+            .collect();
+        assert_eq!(keys, vec!["c", "a"]);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::error::AppError;
+use crate::infra::http::CLIENT;
+use crate::models::mikan::{MikanResourceItem, ResourceSource};
+use crate::services::mikan::rss::{build_item, RawItem};
+use std::str::FromStr;
+
+const DMHY_RSS: &str = "https://share.dmhy.org/topics/rss/rss.xml";
+
+pub async fn search(keyword: &str) -> Result<Vec<MikanResourceItem>, AppError> {
+    let key = format!("dmhy:rss:{}", keyword);
+    let req = CLIENT.get(DMHY_RSS).query(&[("keyword", keyword)]);
+    let xml = super::fetch_xml(&key, req).await?;
+    parse_items(&xml)
+}
+
+/// 动漫花园的 enclosure 直接是磁力链接，没有种子文件与体积
+pub(crate) fn parse_items(xml: &str) -> Result<Vec<MikanResourceItem>, AppError> {
+    let channel =
+        rss::Channel::from_str(xml).map_err(|e| AppError::Any(format!("dmhy rss: {e}")))?;
+    Ok(channel
+        .items()
+        .iter()
+        .map(|it| {
+            let magnet = it
+                .enclosure()
+                .map(|e| e.url().to_string())
+                .filter(|u| u.starts_with("magnet:"));
+            build_item(
+                RawItem {
+                    title: it.title().unwrap_or("").trim().to_string(),
+                    page_url: it.link().unwrap_or("").to_string(),
+                    torrent_url: None,
+                    magnet,
+                    pub_date: it.pub_date().map(str::to_string),
+                    size_bytes: None,
+                    description: it.description().map(str::to_string),
+                },
+                ResourceSource::Dmhy,
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/dmhy_rss.xml"
+    ));
+
+    #[test]
+    fn parses_saved_feed() {
+        let items = parse_items(FIXTURE).unwrap();
+        assert_eq!(items.len(), 2);
+
+        let single = &items[0];
+        assert_eq!(single.source, ResourceSource::Dmhy);
+        assert_eq!(single.group.as_deref(), Some("LoliHouse"));
+        assert_eq!(single.episode, Some(27));
+        assert_eq!(single.resolution, Some(1080));
+        assert_eq!(single.subtitle_lang.as_deref(), Some("简繁"));
+        assert!(single
+            .magnet
+            .as_deref()
+            .unwrap()
+            .starts_with("magnet:?xt=urn:btih:6ZSNLBDZ"));
+        assert!(single.torrent_url.is_none());
+        assert!(single.page_url.contains("/topics/view/690001"));
+
+        let batch = &items[1];
+        assert_eq!(batch.group.as_deref(), Some("喵萌奶茶屋"));
+        assert_eq!(batch.episode_range.as_deref(), Some("1-28"));
+    }
+}
@@ -0,0 +1,203 @@
+pub mod archive;
+pub mod dmhy;
+pub mod nyaa;
+
+use crate::error::AppError;
+use crate::infra::cache;
+use crate::infra::http::with_validators;
+use crate::models::bangumi::SubjectResponse;
+use crate::models::mikan::{MikanResourceItem, ResourceSource};
+use crate::services::downloader::client::parse_magnet_btih;
+use crate::services::mikan::config::SourceSettings;
+use crate::services::mikan::links;
+use crate::utils::string::normalize_name;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::header::{ETAG, LAST_MODIFIED};
+use reqwest::{RequestBuilder, StatusCode};
+use std::collections::HashSet;
+use tracing::warn;
+
+const FEED_TTL_SECS: i64 = 3600;
+
+static RE_HEX_HASH: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b([0-9a-f]{40})\b").unwrap());
+
+impl ResourceSource {
+    pub fn is_enabled(self, settings: &SourceSettings) -> bool {
+        match self {
+            ResourceSource::Mikan => settings.mikan,
+            ResourceSource::Dmhy => settings.dmhy,
+            ResourceSource::Nyaa => settings.nyaa,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ResourceSource::Mikan => "mikan",
+            ResourceSource::Dmhy => "dmhy",
+            ResourceSource::Nyaa => "nyaa",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "mikan" => Some(ResourceSource::Mikan),
+            "dmhy" => Some(ResourceSource::Dmhy),
+            "nyaa" => Some(ResourceSource::Nyaa),
+            _ => None,
+        }
+    }
+
+    /// 搜索站点使用的关键词：动漫花园以中文名为主，nyaa 以原名为主
+    fn keyword(self, subject: &SubjectResponse) -> String {
+        match self {
+            ResourceSource::Nyaa if !subject.name.trim().is_empty() => {
+                subject.name.trim().to_string()
+            }
+            _ => normalize_name(&subject.name, &subject.name_cn),
+        }
+    }
+}
+
+/// 需要按关键词搜索的站点（Mikan 通过番组映射直接取 feed）
+const SEARCH_SOURCES: [ResourceSource; 2] = [ResourceSource::Dmhy, ResourceSource::Nyaa];
+
+/// 拉取 RSS 文本，带缓存与条件请求
+async fn fetch_xml(key: &str, req: RequestBuilder) -> Result<String, AppError> {
+    let cached = cache::get_with_stale(key).await.unwrap_or_else(|e| {
+        warn!(error = %e, feed = key, "source rss cache read error");
+        None
+    });
+    if let Some(entry) = cached.as_ref().filter(|e| e.fresh) {
+        return Ok(entry.value.clone());
+    }
+    let stale = cached.filter(|e| e.has_validator());
+    let req = match &stale {
+        Some(e) => with_validators(req, e.etag.as_deref(), e.last_modified.as_deref()),
+        None => req,
+    };
+
+    crate::infra::http::wait_api_limit().await;
+    let resp = req.send().await?;
+    if let (Some(entry), StatusCode::NOT_MODIFIED) = (&stale, resp.status()) {
+        cache::touch_entry(key, FEED_TTL_SECS).await?;
+        return Ok(entry.value.clone());
+    }
+    resp.error_for_status_ref()?;
+    let header = |name| {
+        resp.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+    };
+    let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+    let body = resp.text().await?;
+    cache::set_entry(key, body.clone(), etag, last_modified, FEED_TTL_SECS).await?;
+    Ok(body)
+}
+
+async fn search(source: ResourceSource, keyword: &str) -> Result<Vec<MikanResourceItem>, AppError> {
+    match source {
+        ResourceSource::Mikan => Ok(Vec::new()),
+        ResourceSource::Dmhy => dmhy::search(keyword).await,
+        ResourceSource::Nyaa => nyaa::search(keyword).await,
+    }
+}
+
+/// 汇总各已启用站点的资源。Mikan 在前；其他站点失败时只记录日志，不影响整体结果。
+pub async fn collect(
+    subject_id: u32,
+    mikan: Option<(u32, Vec<u32>)>,
+    settings: &SourceSettings,
+) -> Result<Vec<MikanResourceItem>, AppError> {
+    let mut feeds: Vec<Vec<MikanResourceItem>> = Vec::new();
+    if let Some((mikan_id, subgroups)) = mikan.filter(|_| settings.mikan) {
+        feeds.push(links::fetch_items(subject_id, mikan_id, &subgroups).await?);
+    }
+
+    let enabled: Vec<ResourceSource> = SEARCH_SOURCES
+        .into_iter()
+        .filter(|s| s.is_enabled(settings))
+        .collect();
+    if enabled.is_empty() {
+        return Ok(merge_by_hash(feeds));
+    }
+    // 搜索关键词取自 Bangumi 条目；获取失败时跳过其他站点，保留 Mikan 的结果
+    match crate::services::bangumi::api::fetch_subject(subject_id).await {
+        Ok(subject) => {
+            let searches = enabled.iter().map(|source| {
+                let keyword = source.keyword(&subject);
+                async move { (*source, search(*source, &keyword).await) }
+            });
+            for (source, result) in futures::future::join_all(searches).await {
+                match result {
+                    Ok(items) => feeds.push(items),
+                    Err(e) => warn!(error = %e, ?source, subject_id, "resource source failed"),
+                }
+            }
+        }
+        Err(e) => warn!(error = %e, subject_id, "subject unavailable, skipping search sources"),
+    }
+    Ok(merge_by_hash(feeds))
+}
+
+/// 种子 info hash：优先磁力链接，其次种子链接中的 40 位十六进制
+pub(crate) fn info_hash(item: &MikanResourceItem) -> Option<String> {
+    item.magnet
+        .as_deref()
+        .and_then(parse_magnet_btih)
+        .or_else(|| {
+            item.torrent_url
+                .as_deref()
+                .and_then(|u| RE_HEX_HASH.captures(u))
+                .map(|c| c[1].to_lowercase())
+        })
+}
+
+/// 合并多个站点的结果，同一种子只保留先出现的一条
+fn merge_by_hash(feeds: Vec<Vec<MikanResourceItem>>) -> Vec<MikanResourceItem> {
+    let mut seen: HashSet<String> = HashSet::new();
+    feeds
+        .into_iter()
+        .flatten()
+        .filter(|item| match info_hash(item) {
+            Some(h) => seen.insert(h),
+            None => true,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mikan::rss;
+
+    #[test]
+    fn same_torrent_from_two_sources_is_kept_once() {
+        let mikan = rss::build_item(
+            rss::RawItem {
+                title: "[ANi] 葬送的芙莉莲 - 27 [1080P]".into(),
+                page_url: "https://mikanani.me/Home/Episode/x".into(),
+                torrent_url: Some(
+                    "https://mikanani.me/Download/20251018/C12FE1C06BBA254A9DC9F519B335AA7C1367A88A.torrent"
+                        .into(),
+                ),
+                magnet: None,
+                pub_date: None,
+                size_bytes: None,
+                description: None,
+            },
+            ResourceSource::Mikan,
+        );
+        let dmhy = dmhy::parse_items(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/dmhy_rss.xml"
+        )))
+        .unwrap();
+        let merged = merge_by_hash(vec![vec![mikan], dmhy]);
+        let sources: Vec<ResourceSource> = merged.iter().map(|i| i.source).collect();
+        // 动漫花园第二条与 Mikan 的种子相同
+        assert_eq!(sources, vec![ResourceSource::Mikan, ResourceSource::Dmhy]);
+        assert_eq!(merged[1].group.as_deref(), Some("LoliHouse"));
+    }
+}
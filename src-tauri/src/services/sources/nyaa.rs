@@ -0,0 +1,121 @@
+use crate::error::AppError;
+use crate::infra::http::CLIENT;
+use crate::models::mikan::{MikanResourceItem, ResourceSource};
+use crate::services::downloader::magnet::build_magnet;
+use crate::services::mikan::rss::{build_item, RawItem};
+use std::str::FromStr;
+
+const NYAA_HOST: &str = "https://nyaa.si";
+/// nyaa 官方 tracker，附在生成的磁力链接上
+const NYAA_TRACKER: &str = "http://nyaa.tracker.wf:7777/announce";
+
+pub async fn search(keyword: &str) -> Result<Vec<MikanResourceItem>, AppError> {
+    let key = format!("nyaa:rss:{}", keyword);
+    // c=1_0: Anime 全部子分类；f=0: 不过滤
+    let req =
+        CLIENT
+            .get(NYAA_HOST)
+            .query(&[("page", "rss"), ("q", keyword), ("c", "1_0"), ("f", "0")]);
+    let xml = super::fetch_xml(&key, req).await?;
+    parse_items(&xml)
+}
+
+fn nyaa_field<'a>(item: &'a rss::Item, name: &str) -> Option<&'a str> {
+    item.extensions()
+        .get("nyaa")?
+        .get(name)?
+        .first()?
+        .value()
+        .map(str::trim)
+}
+
+/// 解析 `1.4 GiB` / `700.5 MiB` 这类体积
+fn parse_size(s: &str) -> Option<u64> {
+    let (num, unit) = s.trim().split_once(' ')?;
+    let n: f64 = num.parse().ok()?;
+    let mul: f64 = match unit {
+        "Bytes" | "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((n * mul).round() as u64)
+}
+
+/// nyaa 的 link 是种子下载地址，guid 是详情页；磁力链接由 infoHash 生成
+pub(crate) fn parse_items(xml: &str) -> Result<Vec<MikanResourceItem>, AppError> {
+    let channel =
+        rss::Channel::from_str(xml).map_err(|e| AppError::Any(format!("nyaa rss: {e}")))?;
+    Ok(channel
+        .items()
+        .iter()
+        .map(|it| {
+            let title = it.title().unwrap_or("").trim().to_string();
+            let magnet = nyaa_field(it, "infoHash")
+                .filter(|h| h.len() == 40)
+                .map(|h| build_magnet(h, Some(&title), &[NYAA_TRACKER.to_string()]));
+            let page_url = it
+                .guid()
+                .map(|g| g.value().to_string())
+                .or_else(|| it.link().map(str::to_string))
+                .unwrap_or_default();
+            build_item(
+                RawItem {
+                    title,
+                    page_url,
+                    torrent_url: it.link().map(str::to_string),
+                    magnet,
+                    pub_date: it.pub_date().map(str::to_string),
+                    size_bytes: nyaa_field(it, "size").and_then(parse_size),
+                    description: None,
+                },
+                ResourceSource::Nyaa,
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/nyaa_rss.xml"
+    ));
+
+    #[test]
+    fn parses_saved_feed() {
+        let items = parse_items(FIXTURE).unwrap();
+        assert_eq!(items.len(), 2);
+
+        let first = &items[0];
+        assert_eq!(first.source, ResourceSource::Nyaa);
+        assert_eq!(first.group.as_deref(), Some("SubsPlease"));
+        assert_eq!(first.episode, Some(27));
+        assert_eq!(first.resolution, Some(1080));
+        assert_eq!(first.page_url, "https://nyaa.si/view/1990001");
+        assert_eq!(
+            first.torrent_url.as_deref(),
+            Some("https://nyaa.si/download/1990001.torrent")
+        );
+        assert_eq!(first.size_bytes, Some(1_503_238_554));
+        assert!(first
+            .magnet
+            .as_deref()
+            .unwrap()
+            .starts_with("magnet:?xt=urn:btih:0f3b0e6b7bfa53c92fbd0f1d7e8bb5e2e4d7c9a1&dn="));
+
+        assert_eq!(items[1].resolution, Some(720));
+        assert_eq!(items[1].size_bytes, Some(734_527_488));
+    }
+
+    #[test]
+    fn parses_binary_sizes() {
+        assert_eq!(parse_size("512 KiB"), Some(524_288));
+        assert_eq!(parse_size("12 Bytes"), Some(12));
+        assert_eq!(parse_size("1.5 GB"), None);
+    }
+}
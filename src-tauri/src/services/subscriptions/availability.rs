@@ -0,0 +1,192 @@
+use crate::error::AppError;
+use crate::models::bangumi::{Episode, EpisodeAvailability};
+use crate::models::mikan::MikanResourceItem;
+use crate::services::downloader::lifecycle;
+use crate::services::downloader::repo::{self as downloads, EpisodeCoverage};
+use crate::services::{bangumi, mikan};
+use chrono::{NaiveDate, Utc};
+use tracing::warn;
+
+/// Bangumi 正片类型
+const EP_TYPE_MAIN: u8 = 0;
+const EPISODE_PAGE_LIMIT: u32 = 100;
+
+fn parse_range(range: &str) -> Option<(u32, u32)> {
+    let (start, end) = range.split_once('-')?;
+    Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+}
+
+/// 单集编号或 `start-end` 合集范围是否覆盖第 `sort` 集
+fn covers(episode: Option<u32>, range: Option<&str>, sort: f32) -> bool {
+    if let Some(ep) = episode {
+        return ep as f32 == sort;
+    }
+    range
+        .and_then(parse_range)
+        .is_some_and(|(start, end)| start as f32 <= sort && sort <= end as f32)
+}
+
+fn aired(airdate: &str, today: NaiveDate) -> bool {
+    NaiveDate::parse_from_str(airdate, "%Y-%m-%d").is_ok_and(|d| d <= today)
+}
+
+/// 合并正片列表、资源（已换算为 Bangumi 编号）、下载记录与观看记录。
+/// 特别篇不属于正片编号，不计入。
+pub fn build_matrix(
+    episodes: &[Episode],
+    items: &[MikanResourceItem],
+    coverage: &[EpisodeCoverage],
+    watched: &[u32],
+    today: NaiveDate,
+) -> Vec<EpisodeAvailability> {
+    episodes
+        .iter()
+        .filter(|e| e.item_type == EP_TYPE_MAIN)
+        .map(|e| {
+            let releases: Vec<&MikanResourceItem> = items
+                .iter()
+                .filter(|it| !it.release.special)
+                .filter(|it| covers(it.episode, it.episode_range.as_deref(), e.sort))
+                .collect();
+            let mut groups: Vec<String> = Vec::new();
+            for group in releases.iter().filter_map(|it| it.group.as_ref()) {
+                if !groups.contains(group) {
+                    groups.push(group.clone());
+                }
+            }
+            let mut resolutions: Vec<u32> =
+                releases.iter().filter_map(|it| it.resolution).collect();
+            resolutions.sort_unstable_by(|a, b| b.cmp(a));
+            resolutions.dedup();
+            let downloads: Vec<&EpisodeCoverage> = coverage
+                .iter()
+                .filter(|c| covers(c.episode, c.episode_range.as_deref(), e.sort))
+                .collect();
+            EpisodeAvailability {
+                episode_id: e.id,
+                sort: e.sort,
+                name: e.name.clone(),
+                name_cn: e.name_cn.clone(),
+                airdate: e.airdate.clone(),
+                aired: aired(&e.airdate, today),
+                groups,
+                resolutions,
+                tracked: downloads.iter().any(|c| c.tracked),
+                downloaded: downloads.iter().any(|c| c.completed),
+                watched: e.sort.fract() == 0.0 && watched.contains(&(e.sort as u32)),
+            }
+        })
+        .collect()
+}
+
+async fn main_episodes(subject_id: u32) -> Result<Vec<Episode>, AppError> {
+    let mut out = Vec::new();
+    loop {
+        let page = bangumi::fetch_episodes(
+            subject_id,
+            Some(EP_TYPE_MAIN),
+            Some(EPISODE_PAGE_LIMIT),
+            Some(out.len() as u32),
+        )
+        .await?;
+        let fetched = page.data.len();
+        out.extend(page.data);
+        if fetched == 0 || out.len() as u32 >= page.total {
+            return Ok(out);
+        }
+    }
+}
+
+/// 条目每一集的状态；资源获取失败时仍返回播出、下载与观看状态
+pub async fn episode_availability(subject_id: u32) -> Result<Vec<EpisodeAvailability>, AppError> {
+    let episodes = main_episodes(subject_id).await?;
+    let items = match mikan::get_mikan_resources(subject_id).await {
+        Ok(resp) => resp.items,
+        Err(e) => {
+            warn!(error = %e, subject_id, "resources unavailable for episode matrix");
+            Vec::new()
+        }
+    };
+    // 完成状态来自下载快照，先按实时进度补记刚完成的下载
+    lifecycle::refresh_completions(subject_id).await?;
+    let coverage = downloads::episode_coverage(subject_id).await?;
+    let watched = super::get_watched(subject_id).await?;
+    Ok(build_matrix(
+        &episodes,
+        &items,
+        &coverage,
+        &watched,
+        Utc::now().date_naive(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mikan::rss::test_item;
+
+    fn episode(sort: f32, airdate: &str) -> Episode {
+        Episode {
+            airdate: airdate.to_string(),
+            ..bangumi::test_episode(sort)
+        }
+    }
+
+    #[test]
+    fn matrix_combines_releases_downloads_and_watch_state() {
+        let episodes = vec![
+            episode(1.0, "2026-10-03"),
+            episode(2.0, "2026-10-10"),
+            episode(3.0, "2026-10-17"),
+            episode(4.0, "2026-10-24"),
+        ];
+        let items = vec![
+            test_item("[ANi] 葬送的芙莉莲 - 02 [1080P]"),
+            test_item("[LoliHouse] 葬送的芙莉莲 - 02 [720p]"),
+            test_item("[ANi] 葬送的芙莉莲 - 01 [1080P]"),
+            test_item("[Group] 葬送的芙莉莲 [01-03 合集][2160p]"),
+            test_item("[Group] 葬送的芙莉莲 [SP2][1080P]"),
+        ];
+        let coverage = vec![
+            EpisodeCoverage {
+                episode: Some(1),
+                episode_range: None,
+                tracked: true,
+                completed: true,
+            },
+            EpisodeCoverage {
+                episode: None,
+                episode_range: Some("2-3".into()),
+                tracked: false,
+                completed: true,
+            },
+            EpisodeCoverage {
+                episode: Some(3),
+                episode_range: None,
+                tracked: true,
+                completed: false,
+            },
+        ];
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let matrix = build_matrix(&episodes, &items, &coverage, &[1], today);
+
+        let summary: Vec<(bool, bool, bool, bool)> = matrix
+            .iter()
+            .map(|m| (m.aired, m.tracked, m.downloaded, m.watched))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (true, true, true, true),
+                (true, false, true, false),
+                (true, true, true, false),
+                (false, false, false, false),
+            ]
+        );
+        assert_eq!(matrix[1].groups, vec!["ANi", "LoliHouse", "Group"]);
+        assert_eq!(matrix[1].resolutions, vec![2160, 1080, 720]);
+        // SP2 不算作第 2 集
+        assert_eq!(matrix[2].groups, vec!["Group"]);
+        assert!(matrix[3].groups.is_empty());
+    }
+}
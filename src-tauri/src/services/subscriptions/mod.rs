@@ -1,3 +1,4 @@
+pub mod availability;
 pub mod index_repo;
 pub mod query;
 pub mod repo;
@@ -14,7 +15,10 @@ use crate::models::bangumi::SubjectResponse;
 pub use index_repo::{batch_get_metadata, SubjectMetadata};
 pub use query::{SubscriptionQuery, SubscriptionSort};
 
-pub use repo::{get_last_seen_ep, get_notify, set_notify, update_last_seen_ep};
+pub use repo::{
+    get_episode_offset, get_last_seen_ep, get_notify, get_subgroups, get_watched, mark_seen,
+    set_episode_offset, set_notify, set_subgroups, set_watched, unseen_keys,
+};
 
 pub async fn list_ids() -> Result<Vec<u32>, AppError> {
     repo::list_ids().await
@@ -42,6 +46,14 @@ pub async fn toggle(id: u32, notify: Option<bool>) -> Result<bool, AppError> {
     }
 }
 
+/// 未订阅时以默认通知设置订阅，返回是否新增
+pub async fn ensure_subscribed(id: u32) -> Result<bool, AppError> {
+    if repo::has(id).await? {
+        return Ok(false);
+    }
+    toggle(id, None).await
+}
+
 pub async fn list_full() -> Result<Vec<(u32, i64, bool, SubjectResponse)>, AppError> {
     index_repo::list_full().await
 }
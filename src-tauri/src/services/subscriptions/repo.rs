@@ -1,4 +1,4 @@
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 
 use crate::error::AppError;
 use crate::infra::time::now_secs;
@@ -116,6 +116,10 @@ fn remove_connection(conn: &mut rusqlite::Connection, id: u32) -> Result<(), rus
         "DELETE FROM subjects_index WHERE subject_id = ?1",
         params![id as i64],
     )?;
+    transaction.execute(
+        "DELETE FROM seen_episodes WHERE subject_id = ?1",
+        params![id as i64],
+    )?;
     transaction.commit()
 }
 
@@ -130,6 +134,7 @@ fn clear_connection(conn: &mut rusqlite::Connection) -> Result<(), rusqlite::Err
     let transaction = conn.transaction()?;
     transaction.execute("DELETE FROM subscriptions", [])?;
     transaction.execute("DELETE FROM subjects_index", [])?;
+    transaction.execute("DELETE FROM seen_episodes", [])?;
     transaction.commit()
 }
 
@@ -152,20 +157,77 @@ pub async fn get_last_seen_ep(subject_id: u32) -> Result<u32, AppError> {
     Ok(last_seen_ep)
 }
 
-pub async fn update_last_seen_ep(subject_id: u32, episode: u32) -> Result<(), AppError> {
+/// 对比已见集合，返回此前未见过的键，不写入。
+/// `bool` 为首次记录（回填），此时调用方不应逐条提醒。
+pub async fn unseen_keys(
+    subject_id: u32,
+    keys: Vec<String>,
+) -> Result<(bool, Vec<String>), AppError> {
     let pool = crate::infra::db::data_pool()?;
     let conn = pool.get().await?;
-    conn.interact(move |conn| -> Result<(), rusqlite::Error> {
-        conn.execute(
-            "UPDATE subscriptions SET last_seen_ep = ?1 WHERE subject_id = ?2",
-            params![episode as i64, subject_id as i64],
-        )?;
-        Ok(())
-    })
-    .await??;
+    let out = conn
+        .interact(move |conn| unseen_keys_row(conn, subject_id, &keys))
+        .await??;
+    Ok(out)
+}
+
+fn unseen_keys_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+    keys: &[String],
+) -> Result<(bool, Vec<String>), rusqlite::Error> {
+    let initialized: bool = conn
+        .query_row(
+            "SELECT seen_initialized FROM subscriptions WHERE subject_id = ?1",
+            params![subject_id as i64],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(false);
+    let mut stmt =
+        conn.prepare("SELECT 1 FROM seen_episodes WHERE subject_id = ?1 AND episode_key = ?2")?;
+    let mut new: Vec<String> = Vec::new();
+    for key in keys {
+        if !new.contains(key) && !stmt.exists(params![subject_id as i64, key])? {
+            new.push(key.clone());
+        }
+    }
+    Ok((!initialized, new))
+}
+
+/// 写入已见的集并标记回填完成；应在提醒被接受后调用，失败的提醒下轮重试
+pub async fn mark_seen(subject_id: u32, keys: Vec<String>) -> Result<(), AppError> {
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| mark_seen_row(conn, subject_id, &keys, now))
+        .await??;
     Ok(())
 }
 
+fn mark_seen_row(
+    conn: &mut rusqlite::Connection,
+    subject_id: u32,
+    keys: &[String],
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    let transaction = conn.transaction()?;
+    {
+        let mut stmt = transaction.prepare(
+            "INSERT OR IGNORE INTO seen_episodes(subject_id, episode_key, first_seen)
+             VALUES(?1, ?2, ?3)",
+        )?;
+        for key in keys {
+            stmt.execute(params![subject_id as i64, key, now])?;
+        }
+    }
+    transaction.execute(
+        "UPDATE subscriptions SET seen_initialized = 1 WHERE subject_id = ?1",
+        params![subject_id as i64],
+    )?;
+    transaction.commit()
+}
+
 pub async fn get_notify(subject_id: u32) -> Result<bool, AppError> {
     let pool = crate::infra::db::data_pool()?;
     let conn = pool.get().await?;
@@ -199,6 +261,162 @@ pub async fn set_notify(subject_id: u32, notify: bool) -> Result<(), AppError> {
     Ok(())
 }
 
+/// 订阅固定的 Mikan 字幕组；为空表示不限字幕组
+pub async fn get_subgroups(subject_id: u32) -> Result<Vec<u32>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| get_subgroups_row(conn, subject_id))
+        .await??;
+    Ok(out)
+}
+
+fn get_subgroups_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+) -> Result<Vec<u32>, rusqlite::Error> {
+    let raw: Option<String> = conn
+        .query_row(
+            "SELECT mikan_subgroups FROM subscriptions WHERE subject_id = ?1",
+            params![subject_id as i64],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    Ok(raw
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default())
+}
+
+pub async fn set_subgroups(subject_id: u32, subgroups: Vec<u32>) -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| set_subgroups_row(conn, subject_id, &subgroups))
+        .await??;
+    Ok(())
+}
+
+fn set_subgroups_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+    subgroups: &[u32],
+) -> Result<(), rusqlite::Error> {
+    let mut ids = subgroups.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    let raw = if ids.is_empty() {
+        None
+    } else {
+        serde_json::to_string(&ids).ok()
+    };
+    conn.execute(
+        "UPDATE subscriptions SET mikan_subgroups = ?1 WHERE subject_id = ?2",
+        params![raw, subject_id as i64],
+    )?;
+    Ok(())
+}
+
+/// 集数偏移：`Bangumi sort = Mikan 集数 + offset`；`None` 表示尚未推断
+pub async fn get_episode_offset(subject_id: u32) -> Result<Option<i32>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| get_episode_offset_row(conn, subject_id))
+        .await??;
+    Ok(out)
+}
+
+fn get_episode_offset_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+) -> Result<Option<i32>, rusqlite::Error> {
+    Ok(conn
+        .query_row(
+            "SELECT episode_offset FROM subscriptions WHERE subject_id = ?1",
+            params![subject_id as i64],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten())
+}
+
+pub async fn set_episode_offset(subject_id: u32, offset: Option<i32>) -> Result<(), AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| set_episode_offset_row(conn, subject_id, offset))
+        .await??;
+    Ok(())
+}
+
+/// `last_seen_ep` 按新旧偏移之差一并换算，避免编号切换后重复或漏掉通知
+pub(crate) fn set_episode_offset_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+    offset: Option<i32>,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE subscriptions
+         SET last_seen_ep = CASE WHEN last_seen_ep = 0 THEN 0
+                 ELSE MAX(0, last_seen_ep + ?1 - COALESCE(episode_offset, 0)) END,
+             episode_offset = ?2
+         WHERE subject_id = ?3",
+        params![offset.unwrap_or(0), offset, subject_id as i64],
+    )?;
+    Ok(())
+}
+
+/// 本地记录的已看集数（Bangumi 编号）
+pub async fn get_watched(subject_id: u32) -> Result<Vec<u32>, AppError> {
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    let out = conn
+        .interact(move |conn| get_watched_row(conn, subject_id))
+        .await??;
+    Ok(out)
+}
+
+fn get_watched_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+) -> Result<Vec<u32>, rusqlite::Error> {
+    let mut stmt = conn
+        .prepare("SELECT episode FROM watched_episodes WHERE subject_id = ?1 ORDER BY episode")?;
+    let rows = stmt.query_map(params![subject_id as i64], |row| {
+        Ok(row.get::<_, i64>(0)? as u32)
+    })?;
+    rows.collect()
+}
+
+pub async fn set_watched(subject_id: u32, episode: u32, watched: bool) -> Result<(), AppError> {
+    let now = now_secs();
+    let pool = crate::infra::db::data_pool()?;
+    let conn = pool.get().await?;
+    conn.interact(move |conn| set_watched_row(conn, subject_id, episode, watched, now))
+        .await??;
+    Ok(())
+}
+
+fn set_watched_row(
+    conn: &rusqlite::Connection,
+    subject_id: u32,
+    episode: u32,
+    watched: bool,
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    if watched {
+        conn.execute(
+            "INSERT OR IGNORE INTO watched_episodes(subject_id, episode, watched_at) VALUES(?1, ?2, ?3)",
+            params![subject_id as i64, episode as i64, now],
+        )?;
+    } else {
+        conn.execute(
+            "DELETE FROM watched_episodes WHERE subject_id = ?1 AND episode = ?2",
+            params![subject_id as i64, episode as i64],
+        )?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,6 +427,7 @@ mod tests {
         conn.execute_batch(
             "CREATE TABLE subscriptions (subject_id INTEGER PRIMARY KEY);
              CREATE TABLE subjects_index (subject_id INTEGER PRIMARY KEY);
+             CREATE TABLE seen_episodes (subject_id INTEGER);
              INSERT INTO subscriptions VALUES (1);
              INSERT INTO subjects_index VALUES (1);
              CREATE TRIGGER fail_index_clear BEFORE DELETE ON subjects_index
@@ -226,4 +445,119 @@ mod tests {
             .unwrap();
         assert_eq!((subscriptions, index), (1, 1));
     }
+
+    #[test]
+    fn subgroups_round_trip_and_clear() {
+        let conn = crate::infra::db::test_data_connection();
+        conn.execute(
+            "INSERT INTO subscriptions(subject_id, added_at, notify) VALUES (1, 0, 1)",
+            [],
+        )
+        .unwrap();
+        assert!(get_subgroups_row(&conn, 1).unwrap().is_empty());
+
+        set_subgroups_row(&conn, 1, &[583, 370, 583]).unwrap();
+        assert_eq!(get_subgroups_row(&conn, 1).unwrap(), vec![370, 583]);
+
+        set_subgroups_row(&conn, 1, &[]).unwrap();
+        assert!(get_subgroups_row(&conn, 1).unwrap().is_empty());
+        assert!(get_subgroups_row(&conn, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn episode_offset_rebases_last_seen_ep() {
+        let conn = crate::infra::db::test_data_connection();
+        conn.execute(
+            "INSERT INTO subscriptions(subject_id, added_at, notify, last_seen_ep) VALUES (1, 0, 1, 16), (2, 0, 1, 0)",
+            [],
+        )
+        .unwrap();
+        let last_seen = |id: i64| -> i64 {
+            conn.query_row(
+                "SELECT last_seen_ep FROM subscriptions WHERE subject_id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(get_episode_offset_row(&conn, 1).unwrap(), None);
+
+        set_episode_offset_row(&conn, 1, Some(-12)).unwrap();
+        assert_eq!(get_episode_offset_row(&conn, 1).unwrap(), Some(-12));
+        assert_eq!(last_seen(1), 4);
+
+        // 清除偏移时换算回原始编号
+        set_episode_offset_row(&conn, 1, None).unwrap();
+        assert_eq!(get_episode_offset_row(&conn, 1).unwrap(), None);
+        assert_eq!(last_seen(1), 16);
+
+        // 尚未见过任何一集时保持 0
+        set_episode_offset_row(&conn, 2, Some(12)).unwrap();
+        assert_eq!(last_seen(2), 0);
+        assert_eq!(get_episode_offset_row(&conn, 3).unwrap(), None);
+    }
+
+    #[test]
+    fn seen_set_backfills_once_and_reports_new_keys() {
+        let mut conn = crate::infra::db::test_data_connection();
+        conn.execute(
+            "INSERT INTO subscriptions(subject_id, added_at, notify) VALUES (1, 0, 1)",
+            [],
+        )
+        .unwrap();
+        let keys = |ks: &[&str]| -> Vec<String> { ks.iter().map(|k| k.to_string()).collect() };
+
+        let (backfill, new) = unseen_keys_row(&conn, 1, &keys(&["ep:1", "ep:2", "ep:1"])).unwrap();
+        assert!(backfill);
+        assert_eq!(new, keys(&["ep:1", "ep:2"]));
+        // 只对比不写入：提醒失败时这些键下轮仍是未见
+        assert_eq!(
+            unseen_keys_row(&conn, 1, &keys(&["ep:1", "ep:2"])).unwrap(),
+            (true, new.clone())
+        );
+        mark_seen_row(&mut conn, 1, &new, 10).unwrap();
+
+        let (backfill, new) =
+            unseen_keys_row(&conn, 1, &keys(&["ep:2", "ep:1v2", "sp:1"])).unwrap();
+        assert!(!backfill);
+        assert_eq!(new, keys(&["ep:1v2", "sp:1"]));
+        mark_seen_row(&mut conn, 1, &new, 20).unwrap();
+
+        // 没有资源时也算完成回填，之后的第一集照常提醒
+        conn.execute(
+            "INSERT INTO subscriptions(subject_id, added_at, notify) VALUES (2, 0, 1)",
+            [],
+        )
+        .unwrap();
+        assert_eq!(unseen_keys_row(&conn, 2, &[]).unwrap(), (true, vec![]));
+        mark_seen_row(&mut conn, 2, &[], 10).unwrap();
+        assert_eq!(
+            unseen_keys_row(&conn, 2, &keys(&["ep:1"])).unwrap(),
+            (false, keys(&["ep:1"]))
+        );
+
+        remove_connection(&mut conn, 1).unwrap();
+        let left: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM seen_episodes WHERE subject_id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(left, 0);
+    }
+
+    #[test]
+    fn watched_episodes_round_trip() {
+        let conn = crate::infra::db::test_data_connection();
+        set_watched_row(&conn, 1, 3, true, 10).unwrap();
+        set_watched_row(&conn, 1, 1, true, 10).unwrap();
+        set_watched_row(&conn, 1, 1, true, 20).unwrap();
+        set_watched_row(&conn, 2, 5, true, 10).unwrap();
+        assert_eq!(get_watched_row(&conn, 1).unwrap(), vec![1, 3]);
+
+        set_watched_row(&conn, 1, 3, false, 30).unwrap();
+        assert_eq!(get_watched_row(&conn, 1).unwrap(), vec![1]);
+        assert_eq!(get_watched_row(&conn, 2).unwrap(), vec![5]);
+    }
 }
@@ -7,5 +7,6 @@
 //! - Can be used by any layer
 
 pub mod parser;
+pub mod romaji;
 pub mod round_robin;
 pub mod string;
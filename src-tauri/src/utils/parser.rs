@@ -2,16 +2,33 @@
 //!
 //! Functions for extracting structured data from unstructured text strings.
 
+use crate::models::release::{
+    AudioCodec, Container, EpisodeRange, ReleaseInfo, ReleaseSource, VideoCodec,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
 static RE_RESOLUTION: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?i)\b(2160|1080|720|480)\s*[pP]\b").unwrap());
+    Lazy::new(|| Regex::new(r"(?i)(?:\b|_)(2160|1080|720|480)\s*[pP]\b").unwrap());
 static RE_4K: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b4\s*K\b").unwrap());
+static RE_DIMENSIONS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b\d{3,4}\s*[x×]\s*(2160|1080|720|480)\b").unwrap());
+static RE_SEASON: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?ix)
+        第\s*(?P<cn>[0-9一二三四五六七八九十]+)\s*[季期部]
+        | \bseason\s*(?P<en>\d{1,2})\b
+        | \b(?P<ord>\d{1,2})(?:st|nd|rd|th)\s+season\b
+        | \bS(?P<short>\d{1,2})(?:E\d+)?\b
+        | \s(?P<roman>II|III|IV)\s*$",
+    )
+    .unwrap()
+});
 
 /// Extract video resolution from a filename or title string.
 ///
-/// Supports standard resolution patterns (480P, 720P, 1080P, 2160P) and 4K notation.
+/// Supports standard resolution patterns (480P, 720P, 1080P, 2160P), 4K notation
+/// and frame dimensions such as `1920x1080`.
 /// Case-insensitive and handles whitespace variations.
 ///
 /// # Examples
@@ -34,9 +51,439 @@ pub fn parse_resolution(text: &str) -> Option<u32> {
     if RE_4K.is_match(text) {
         return Some(2160);
     }
+    // Try frame dimensions
+    RE_DIMENSIONS
+        .captures(text)
+        .and_then(|c| c.get(1)?.as_str().parse().ok())
+}
+
+/// Extract a season number from a title.
+///
+/// Recognises `第二季`/`第2期`, `Season 2`, `2nd Season`, `S2` and a trailing
+/// roman numeral such as `II`. Returns `None` when no season marker is present.
+///
+/// # Examples
+///
+/// ```
+/// use animefun_lib::utils::parser::parse_season;
+///
+/// assert_eq!(parse_season("辉夜大小姐想让我告白 第二季"), Some(2));
+/// assert_eq!(parse_season("Mushoku Tensei Season 2"), Some(2));
+/// assert_eq!(parse_season("Overlord III"), Some(3));
+/// assert_eq!(parse_season("葬送的芙莉莲"), None);
+/// ```
+pub fn parse_season(text: &str) -> Option<u32> {
+    let c = RE_SEASON.captures(text)?;
+    if let Some(m) = c.name("cn") {
+        return parse_cn_number(m.as_str());
+    }
+    for name in ["en", "ord", "short"] {
+        if let Some(m) = c.name(name) {
+            return m.as_str().parse().ok();
+        }
+    }
+    c.name("roman").and_then(|m| match m.as_str() {
+        "II" => Some(2),
+        "III" => Some(3),
+        "IV" => Some(4),
+        _ => None,
+    })
+}
+
+/// Parse Arabic digits or a Chinese numeral up to 99 (`十二`, `二十`).
+fn parse_cn_number(s: &str) -> Option<u32> {
+    if let Ok(n) = s.parse() {
+        return Some(n);
+    }
+    let digit = |c: char| {
+        "零一二三四五六七八九"
+            .chars()
+            .position(|d| d == c)
+            .map(|p| p as u32)
+    };
+    let chars: Vec<char> = s.chars().collect();
+    match chars.as_slice() {
+        [c] if *c == '十' => Some(10),
+        [c] => digit(*c),
+        ['十', u] => Some(10 + digit(*u)?),
+        [t, '十'] => Some(digit(*t)? * 10),
+        [t, '十', u] => Some(digit(*t)? * 10 + digit(*u)?),
+        _ => None,
+    }
+}
+
+fn leading_group(text: &str) -> Option<String> {
+    let t = text.trim();
+    let pair = match t.chars().next() {
+        Some('[') => Some(('[', ']')),
+        Some('(') => Some(('(', ')')),
+        Some('{') => Some(('{', '}')),
+        Some('【') => Some(('【', '】')),
+        _ => None,
+    }?;
+    let start = t.chars().next()?.len_utf8();
+    let right = pair.1;
+    if let Some(rel_end) = t[start..].find(right) {
+        let end = start + rel_end;
+        if end > start {
+            let g = t[start..end].trim();
+            if !g.is_empty() && g.len() <= 40 {
+                return Some(g.to_string());
+            }
+        }
+    }
     None
 }
 
+fn any_group(text: &str) -> Option<String> {
+    let t = text;
+    for (i, ch) in t.char_indices() {
+        let right = match ch {
+            '[' => Some(']'),
+            '(' => Some(')'),
+            '{' => Some('}'),
+            '【' => Some('】'),
+            _ => None,
+        };
+        if let Some(r) = right {
+            let start = i + ch.len_utf8();
+            if let Some(rel_end) = t[start..].find(r) {
+                let end = start + rel_end;
+                if end > start {
+                    let g = t[start..end].trim();
+                    if !g.is_empty() && g.len() <= 40 {
+                        return Some(g.to_string());
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+pub fn parse_group(title: &str) -> Option<String> {
+    leading_group(title).or_else(|| any_group(title))
+}
+
+// Episode parsing patterns - ordered by priority
+static RE_EPISODE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:EP|E|第)\s*(\d{1,3})(?:\s*(?:话|話|集))?\b").unwrap());
+static RE_RANGE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\d{1,3})\s*-\s*(\d{1,3})(?:\s*[\[(（]?(?:全集|END|完)[\])）]?)?").unwrap()
+});
+static RE_BRACKET_NUM: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)[\[(【]\s*(?:OAD|OVA|SP|Special|特典)?\s*(\d{1,3})\s*[\])）】]").unwrap()
+});
+static RE_DASH_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)[\s\-]\s*(\d{1,3})\b").unwrap());
+
+pub fn parse_episode_info(title: &str) -> (Option<u32>, Option<EpisodeRange>) {
+    // Try explicit EP/E/第 pattern first
+    if let Some(c) = RE_EPISODE.captures(title) {
+        if let Ok(n) = c.get(1).unwrap().as_str().parse::<u32>() {
+            return (Some(n), None);
+        }
+    }
+
+    // Try range pattern (e.g., "01-12")
+    if let Some(c) = RE_RANGE.captures(title) {
+        if let (Ok(sn), Ok(en)) = (
+            c.get(1).unwrap().as_str().parse::<u32>(),
+            c.get(2).unwrap().as_str().parse::<u32>(),
+        ) {
+            // Check if this is a season indicator like "S2 - 23" (not a range)
+            if is_season_indicator(title, &c, sn) {
+                return (Some(en), None);
+            }
+            return (None, Some(EpisodeRange { start: sn, end: en }));
+        }
+    }
+
+    // Try bracketed number (e.g., "[01]")
+    if let Some(c) = RE_BRACKET_NUM.captures(title) {
+        if let Ok(n) = c.get(1).unwrap().as_str().parse::<u32>() {
+            return (Some(n), None);
+        }
+    }
+
+    // Try standalone dash-separated number
+    if let Some(n) = parse_dash_number(title) {
+        return (Some(n), None);
+    }
+
+    (None, None)
+}
+
+// Check if a range match is actually a season indicator (e.g., "S2 - 23" means episode 23 of season 2)
+fn is_season_indicator(title: &str, c: &regex::Captures, season_num: u32) -> bool {
+    let m = c.get(0).unwrap();
+    let start = m.start();
+    let end = m.end();
+
+    // Check for brackets around the match
+    let prev = title[..start].chars().rev().find(|ch| !ch.is_whitespace());
+    let next = title[end..].chars().find(|ch| !ch.is_whitespace());
+    let bracketed = matches!(prev, Some('[') | Some('(') | Some('（') | Some('【'))
+        || matches!(next, Some(']') | Some(')') | Some('）') | Some('】'));
+
+    // Check for END/全集/完 keywords
+    let has_end_marker = m.as_str().to_lowercase().contains("end")
+        || m.as_str().contains("全集")
+        || m.as_str().contains("完");
+
+    if bracketed || has_end_marker {
+        return false;
+    }
+
+    // Check for "S{N}" or "{N} /" or "{N}-\" patterns that indicate season
+    let season_pattern = format!(r"(?i)\bS{}\b", season_num);
+    let has_season_marker = Regex::new(&season_pattern)
+        .ok()
+        .map(|r| r.is_match(title))
+        .unwrap_or(false)
+        || title.contains(&format!(" {} /", season_num))
+        || title.contains(&format!(" {}-", season_num))
+        || title.contains(&format!(" {} -", season_num));
+
+    has_season_marker
+}
+
+// Parse dash-separated numbers, excluding "1080p"-like patterns
+fn parse_dash_number(title: &str) -> Option<u32> {
+    // Remove parenthesized parts to avoid false matches
+    let main = title
+        .split_once('(')
+        .map(|(a, _)| a)
+        .unwrap_or(title)
+        .split_once('（')
+        .map(|(a, _)| a)
+        .unwrap_or(title);
+
+    RE_DASH_NUM
+        .captures_iter(main)
+        .filter_map(|c| {
+            let m = c.get(1)?;
+            let s = m.as_str();
+            let end = m.end();
+
+            // Skip if followed by 'p' or 'P' (resolution like 1080p)
+            let next = main[end..].chars().next();
+            if matches!(next, Some('p') | Some('P')) {
+                return None;
+            }
+
+            s.parse::<u32>().ok()
+        })
+        .last()
+}
+
+pub fn parse_subtitle_text(text: &str) -> (Option<String>, Option<String>) {
+    let s = text.to_lowercase();
+
+    // Language detection (priority order matters)
+    let lang = if s.contains("简繁日") {
+        Some("简繁日")
+    } else if s.contains("简日") {
+        Some("简日")
+    } else if s.contains("简繁") || s.contains("chs&cht") || s.contains("chs+cht") {
+        Some("简繁")
+    } else if s.contains("简体") || s.contains("chs") || s.contains("gb") {
+        Some("简体")
+    } else if s.contains("繁体") || s.contains("cht") || s.contains("big5") {
+        Some("繁体")
+    } else if s.contains("繁日") {
+        Some("繁日")
+    } else {
+        None
+    };
+
+    // Type detection
+    let typ = if s.contains("外挂") || s.contains("external") {
+        Some("外挂")
+    } else if s.contains("内封")
+        || s.contains("内嵌")
+        || s.contains("内置")
+        || s.contains("softsub")
+    {
+        Some("内封")
+    } else if s.contains("硬字幕") || s.contains("hardsub") {
+        Some("硬字幕")
+    } else {
+        None
+    };
+
+    (lang.map(str::to_string), typ.map(str::to_string))
+}
+
+static RE_VERSION: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)(?:\d|\b)v(\d)\b").unwrap());
+static RE_VERSION_SUFFIX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)(\d)v\d\b").unwrap());
+static RE_LEADING_NOISE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[★☆][^\[【]*").unwrap());
+static RE_TITLE_END: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\s+-\s+(?:\d|第|EP?\s*\d|SP|OVA|OAD)|\s+第\s*\d+\s*[话話集]|\s+EP?\s*\d|\s+S\d{1,2}E\d|[\[【(（]",
+    )
+    .unwrap()
+});
+static RE_BIT_DEPTH: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|[^a-z0-9])(?:(\d{1,2})\s*-?\s*bits?|(?:hi|ma)(10)p)(?:$|[^a-z0-9])")
+        .unwrap()
+});
+static RE_BATCH: Lazy<Regex> = Lazy::new(|| token(r"合集|全集|Batch|BD-?BOX|Complete"));
+static RE_SPECIAL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|[^a-z0-9])(?:OVA|OAD|SP|Specials?)\d{0,3}(?:$|[^a-z0-9])|特典|特别篇|特別篇|番外")
+        .unwrap()
+});
+
+/// 按字母数字边界匹配的标记；允许 `AACx2` 这类多音轨后缀
+fn token(pattern: &str) -> Regex {
+    Regex::new(&format!(
+        r"(?i)(?:^|[^a-z0-9])(?:{pattern})(?:x\d)?(?:$|[^a-z0-9])"
+    ))
+    .unwrap()
+}
+
+/// 按优先级排列：压制类型优先于平台，具体平台优先于泛指的 Web 来源
+static SOURCES: Lazy<Vec<(ReleaseSource, Regex)>> = Lazy::new(|| {
+    vec![
+        (ReleaseSource::BdRip, token(r"BD-?Rip|BD|Blu-?ray|BD-?BOX")),
+        (ReleaseSource::DvdRip, token(r"DVD-?Rip|DVD")),
+        (ReleaseSource::Baha, token(r"Baha|巴哈姆特")),
+        (
+            ReleaseSource::BGlobal,
+            token(r"B-?Global|Bilibili\s*Global"),
+        ),
+        (ReleaseSource::Bilibili, token(r"Bilibili|B站")),
+        (ReleaseSource::Crunchyroll, token(r"CR|Crunchyroll")),
+        (ReleaseSource::WebDl, token(r"WEB-?DL")),
+        (ReleaseSource::WebRip, token(r"WEB-?Rip|WEB")),
+    ]
+});
+static VIDEO_CODECS: Lazy<Vec<(VideoCodec, Regex)>> = Lazy::new(|| {
+    vec![
+        (VideoCodec::Hevc, token(r"HEVC|[xh]\.?265")),
+        (VideoCodec::Av1, token(r"AV1")),
+        (VideoCodec::Avc, token(r"AVC|[xh]\.?264")),
+    ]
+});
+static AUDIO_CODECS: Lazy<Vec<(AudioCodec, Regex)>> = Lazy::new(|| {
+    vec![
+        (AudioCodec::Flac, token(r"FLAC")),
+        (AudioCodec::Eac3, token(r"E-?AC-?3|DDP")),
+        (AudioCodec::Ac3, token(r"AC-?3")),
+        (AudioCodec::Dts, token(r"DTS")),
+        (AudioCodec::Opus, token(r"OPUS")),
+        (AudioCodec::Aac, token(r"AAC")),
+    ]
+});
+static CONTAINERS: Lazy<Vec<(Container, Regex)>> = Lazy::new(|| {
+    vec![
+        (Container::Mkv, token(r"MKV")),
+        (Container::Mp4, token(r"MP4")),
+    ]
+});
+
+fn first_match<T: Copy>(table: &[(T, Regex)], text: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(_, re)| re.is_match(text))
+        .map(|(value, _)| *value)
+}
+
+/// 去掉字幕组与 `★01月新番★` 之类的前缀后，取出作品名并按 `/` 拆分
+fn parse_titles(title: &str, group: Option<&str>) -> Vec<String> {
+    let mut rest = title.trim();
+    if let (Some(g), Some(first)) = (group, rest.chars().next()) {
+        let inner = rest[first.len_utf8()..].trim_start();
+        if matches!(first, '[' | '【' | '(' | '{') && inner.starts_with(g) {
+            let after = &inner[g.len()..];
+            let close = after.chars().next().map_or(0, char::len_utf8);
+            rest = after[close..].trim_start();
+        }
+    }
+    rest = RE_LEADING_NOISE
+        .find(rest)
+        .map_or(rest, |m| &rest[m.end()..])
+        .trim_start();
+
+    // 整个标题都由方括号分段时，第一段就是作品名
+    let name = match rest.chars().next() {
+        Some(open @ ('[' | '【')) => {
+            let close = if open == '[' { ']' } else { '】' };
+            let inner = &rest[open.len_utf8()..];
+            inner.find(close).map_or(inner, |end| &inner[..end])
+        }
+        _ => RE_TITLE_END.find(rest).map_or(rest, |m| &rest[..m.start()]),
+    };
+
+    let mut out: Vec<String> = Vec::new();
+    for part in name.split(['/', '／']) {
+        let part = part.trim().trim_end_matches('-').trim();
+        if !part.is_empty() && !out.iter().any(|t| t == part) {
+            out.push(part.to_string());
+        }
+    }
+    out
+}
+
+/// Parse a release title into a [`ReleaseInfo`].
+///
+/// Combines the group, episode, resolution and subtitle parsers above with
+/// detection of title variants, season, revision (`v2`), release source,
+/// codecs, bit depth, container and batch releases.
+///
+/// # Examples
+///
+/// ```
+/// use animefun_lib::utils::parser::parse_release;
+///
+/// let info = parse_release(
+///     "[Nekomoe kissaten&LoliHouse] Sousou no Frieren - 05v2 [WebRip 1080p HEVC-10bit AAC ASSx2]",
+/// );
+/// assert_eq!(info.group.as_deref(), Some("Nekomoe kissaten&LoliHouse"));
+/// assert_eq!(info.titles, vec!["Sousou no Frieren"]);
+/// assert_eq!((info.episode, info.version), (Some(5), Some(2)));
+/// assert_eq!((info.resolution, info.bit_depth), (Some(1080), Some(10)));
+/// ```
+pub fn parse_release(title: &str) -> ReleaseInfo {
+    let group = parse_group(title);
+    let titles = parse_titles(title, group.as_deref());
+    let season = titles
+        .iter()
+        .find_map(|t| parse_season(t))
+        .or_else(|| parse_season(title));
+    let version = RE_VERSION
+        .captures(title)
+        .and_then(|c| c[1].parse().ok())
+        .filter(|v| *v > 1);
+    // `05v2` 会干扰集数的词边界，`10-bit` 会被当成集数
+    let unversioned = RE_VERSION_SUFFIX.replace_all(title, "$1");
+    let episode_text = RE_BIT_DEPTH.replace_all(&unversioned, " ");
+    let (episode, episode_range) = parse_episode_info(&episode_text);
+    let bit_depth = RE_BIT_DEPTH.captures(title).and_then(|c| {
+        c.get(1)
+            .or_else(|| c.get(2))
+            .and_then(|m| m.as_str().parse().ok())
+    });
+    let (language, subtitle_type) = parse_subtitle_text(title);
+    ReleaseInfo {
+        group,
+        titles,
+        season,
+        episode,
+        episode_range,
+        version,
+        resolution: parse_resolution(title),
+        source: first_match(&SOURCES, title),
+        video_codec: first_match(&VIDEO_CODECS, title),
+        bit_depth,
+        audio_codec: first_match(&AUDIO_CODECS, title),
+        container: first_match(&CONTAINERS, title),
+        batch: episode_range.is_some() || RE_BATCH.is_match(title),
+        special: RE_SPECIAL.is_match(title),
+        language,
+        subtitle_type,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,5 +540,399 @@ mod tests {
             Some(2160)
         );
         assert_eq!(parse_resolution("动漫名称 720p MP4"), Some(720));
+        assert_eq!(
+            parse_resolution("(ABEMA 1920x1080 AVC AAC MP4)"),
+            Some(1080)
+        );
+        assert_eq!(parse_resolution("[Ma10p_1080p][x265_flac]"), Some(1080));
+    }
+
+    #[test]
+    fn test_parse_season() {
+        assert_eq!(parse_season("间谍过家家 第2季"), Some(2));
+        assert_eq!(parse_season("进击的巨人 第三期"), Some(3));
+        assert_eq!(parse_season("某作品 第十二季"), Some(12));
+        assert_eq!(parse_season("Re:Zero 3rd Season"), Some(3));
+        assert_eq!(parse_season("Oshi no Ko S2"), Some(2));
+        assert_eq!(parse_season("Oshi no Ko S02E05"), Some(2));
+        assert_eq!(parse_season("[ANi] Frieren - 01 [1080P]"), None);
+        assert_eq!(parse_season("Sword Art Online"), None);
+    }
+
+    #[test]
+    fn test_parse_group_brackets() {
+        let t = "【动漫国字幕组】★01月新番[恋上换装娃娃 / 更衣人偶坠入爱河][01-12(全集)][1080P][简繁外挂][MKV]";
+        assert_eq!(parse_group(t).as_deref(), Some("动漫国字幕组"));
+    }
+
+    #[test]
+    fn test_parse_group_square() {
+        let t =
+            "[H-Enc] 更衣人偶坠入爱河 / Sono Bisque Doll wa Koi wo Suru (BDRip 1080p HEVC FLAC)";
+        assert_eq!(parse_group(t).as_deref(), Some("H-Enc"));
+    }
+
+    #[test]
+    fn test_parse_episode_range() {
+        let t = "【动漫国字幕组】恋上换装娃娃 [01-12(全集)] 1080P 简繁外挂";
+        let (ep, range) = parse_episode_info(t);
+        assert!(ep.is_none());
+        assert_eq!(range, Some(EpisodeRange { start: 1, end: 12 }));
+    }
+
+    #[test]
+    fn test_parse_episode_single() {
+        let t = "[Lilith-Raws] 更衣人偶坠入爱河 - 第07话 1080p";
+        let (ep, range) = parse_episode_info(t);
+        assert_eq!(ep, Some(7));
+        assert!(range.is_none());
+    }
+
+    #[test]
+    fn test_parse_episode_season_dash_episode() {
+        let t = "[黒ネズミたち] 拥有超常技能的异世界流浪美食家 S2 / Tondemo Skill de Isekai Hourou Meshi 2 - 23 (ABEMA 1920x1080 AVC AAC MP4)";
+        let (ep, range) = parse_episode_info(t);
+        assert_eq!(ep, Some(23));
+        assert!(range.is_none());
+    }
+
+    #[test]
+    fn test_parse_episode_special_oad() {
+        let t = "【DHR动研字幕组】[魔物娘的同居日常_Everyday Life with Monster Girls][OAD2][繁体][720P][MP4]";
+        let (ep, range) = parse_episode_info(t);
+        assert_eq!(ep, Some(2));
+        assert!(range.is_none());
+    }
+
+    #[test]
+    fn test_parse_episode_special_ova() {
+        let t = "[Group] Anime Title [OVA1][1080P]";
+        let (ep, range) = parse_episode_info(t);
+        assert_eq!(ep, Some(1));
+        assert!(range.is_none());
+    }
+
+    #[test]
+    fn test_parse_episode_special_sp() {
+        let t = "【字幕组】作品名 [SP3] 繁体";
+        let (ep, range) = parse_episode_info(t);
+        assert_eq!(ep, Some(3));
+        assert!(range.is_none());
+    }
+
+    #[test]
+    fn test_parse_episode_special_tokuten() {
+        let t = "【字幕组】作品名 【特典5】 1080P";
+        let (ep, range) = parse_episode_info(t);
+        assert_eq!(ep, Some(5));
+        assert!(range.is_none());
+    }
+
+    #[test]
+    fn test_parse_episode_bracket_backward_compatibility() {
+        // 确保原有的纯数字括号还能工作
+        let t = "[Group] Anime Title [12][1080P]";
+        let (ep, range) = parse_episode_info(t);
+        assert_eq!(ep, Some(12));
+        assert!(range.is_none());
+    }
+
+    fn titles(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_release_table() {
+        let cases: Vec<(&str, ReleaseInfo)> = vec![
+            (
+                "[ANi] 葬送的芙莉莲 - 27 [1080P][Baha][WEB-DL][AAC AVC][CHT][MP4]",
+                ReleaseInfo {
+                    group: Some("ANi".into()),
+                    titles: titles(&["葬送的芙莉莲"]),
+                    episode: Some(27),
+                    resolution: Some(1080),
+                    source: Some(ReleaseSource::Baha),
+                    video_codec: Some(VideoCodec::Avc),
+                    audio_codec: Some(AudioCodec::Aac),
+                    container: Some(Container::Mp4),
+                    language: Some("繁体".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[Nekomoe kissaten&LoliHouse] Sousou no Frieren - 05v2 [WebRip 1080p HEVC-10bit AAC ASSx2]",
+                ReleaseInfo {
+                    group: Some("Nekomoe kissaten&LoliHouse".into()),
+                    titles: titles(&["Sousou no Frieren"]),
+                    episode: Some(5),
+                    version: Some(2),
+                    resolution: Some(1080),
+                    source: Some(ReleaseSource::WebRip),
+                    video_codec: Some(VideoCodec::Hevc),
+                    bit_depth: Some(10),
+                    audio_codec: Some(AudioCodec::Aac),
+                    ..Default::default()
+                },
+            ),
+            (
+                "【动漫国字幕组】★01月新番[恋上换装娃娃 / 更衣人偶坠入爱河][01-12(全集)][1080P][简繁外挂][MKV]",
+                ReleaseInfo {
+                    group: Some("动漫国字幕组".into()),
+                    titles: titles(&["恋上换装娃娃", "更衣人偶坠入爱河"]),
+                    episode_range: Some(EpisodeRange { start: 1, end: 12 }),
+                    resolution: Some(1080),
+                    container: Some(Container::Mkv),
+                    batch: true,
+                    language: Some("简繁".into()),
+                    subtitle_type: Some("外挂".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[H-Enc] 更衣人偶坠入爱河 / Sono Bisque Doll wa Koi wo Suru (BDRip 1080p HEVC FLAC)",
+                ReleaseInfo {
+                    group: Some("H-Enc".into()),
+                    titles: titles(&["更衣人偶坠入爱河", "Sono Bisque Doll wa Koi wo Suru"]),
+                    resolution: Some(1080),
+                    source: Some(ReleaseSource::BdRip),
+                    video_codec: Some(VideoCodec::Hevc),
+                    audio_codec: Some(AudioCodec::Flac),
+                    ..Default::default()
+                },
+            ),
+            (
+                "【喵萌奶茶屋】★04月新番★[间谍过家家 / SPY×FAMILY][01][1080p][简日双语][招募翻译]",
+                ReleaseInfo {
+                    group: Some("喵萌奶茶屋".into()),
+                    titles: titles(&["间谍过家家", "SPY×FAMILY"]),
+                    episode: Some(1),
+                    resolution: Some(1080),
+                    language: Some("简日".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[黒ネズミたち] 拥有超常技能的异世界流浪美食家 S2 / Tondemo Skill de Isekai Hourou Meshi 2 - 23 (ABEMA 1920x1080 AVC AAC MP4)",
+                ReleaseInfo {
+                    group: Some("黒ネズミたち".into()),
+                    titles: titles(&[
+                        "拥有超常技能的异世界流浪美食家 S2",
+                        "Tondemo Skill de Isekai Hourou Meshi 2",
+                    ]),
+                    season: Some(2),
+                    episode: Some(23),
+                    resolution: Some(1080),
+                    video_codec: Some(VideoCodec::Avc),
+                    audio_codec: Some(AudioCodec::Aac),
+                    container: Some(Container::Mp4),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[LoliHouse] 药屋少女的呢喃 第二季 / Kusuriya no Hitorigoto 2nd Season - 03 [WebRip 1080p HEVC-10bit AAC][简繁内封字幕]",
+                ReleaseInfo {
+                    group: Some("LoliHouse".into()),
+                    titles: titles(&[
+                        "药屋少女的呢喃 第二季",
+                        "Kusuriya no Hitorigoto 2nd Season",
+                    ]),
+                    season: Some(2),
+                    episode: Some(3),
+                    resolution: Some(1080),
+                    source: Some(ReleaseSource::WebRip),
+                    video_codec: Some(VideoCodec::Hevc),
+                    bit_depth: Some(10),
+                    audio_codec: Some(AudioCodec::Aac),
+                    language: Some("简繁".into()),
+                    subtitle_type: Some("内封".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[Sakurato] Oshi no Ko S02E05 [1080p][B-Global][x264 AAC][CHS]",
+                ReleaseInfo {
+                    group: Some("Sakurato".into()),
+                    titles: titles(&["Oshi no Ko"]),
+                    season: Some(2),
+                    episode: Some(5),
+                    resolution: Some(1080),
+                    source: Some(ReleaseSource::BGlobal),
+                    video_codec: Some(VideoCodec::Avc),
+                    audio_codec: Some(AudioCodec::Aac),
+                    language: Some("简体".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[Lilith-Raws] 更衣人偶坠入爱河 - 第07话 [Bilibili][WEB-DL][1080p][AVC AAC][CHT][MKV]",
+                ReleaseInfo {
+                    group: Some("Lilith-Raws".into()),
+                    titles: titles(&["更衣人偶坠入爱河"]),
+                    episode: Some(7),
+                    resolution: Some(1080),
+                    source: Some(ReleaseSource::Bilibili),
+                    video_codec: Some(VideoCodec::Avc),
+                    audio_codec: Some(AudioCodec::Aac),
+                    container: Some(Container::Mkv),
+                    language: Some("繁体".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[SubsPlease] Dandadan - 12 (1080p) [3C1D2F4A].mkv",
+                ReleaseInfo {
+                    group: Some("SubsPlease".into()),
+                    titles: titles(&["Dandadan"]),
+                    episode: Some(12),
+                    resolution: Some(1080),
+                    container: Some(Container::Mkv),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[Erai-raws] Kaijuu 8-gou - 03 [720p][CR WEB-DL AVC E-AC3][MultiSub]",
+                ReleaseInfo {
+                    group: Some("Erai-raws".into()),
+                    titles: titles(&["Kaijuu 8-gou"]),
+                    episode: Some(3),
+                    resolution: Some(720),
+                    source: Some(ReleaseSource::Crunchyroll),
+                    video_codec: Some(VideoCodec::Avc),
+                    audio_codec: Some(AudioCodec::Eac3),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[VCB-Studio] Bocchi the Rock! [Ma10p_1080p][x265_flac]",
+                ReleaseInfo {
+                    group: Some("VCB-Studio".into()),
+                    titles: titles(&["Bocchi the Rock!"]),
+                    resolution: Some(1080),
+                    video_codec: Some(VideoCodec::Hevc),
+                    bit_depth: Some(10),
+                    audio_codec: Some(AudioCodec::Flac),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[Moozzi2] Cyberpunk Edgerunners [BD-BOX] (BD 1920x1080 x.264 Flac)",
+                ReleaseInfo {
+                    group: Some("Moozzi2".into()),
+                    titles: titles(&["Cyberpunk Edgerunners"]),
+                    resolution: Some(1080),
+                    source: Some(ReleaseSource::BdRip),
+                    video_codec: Some(VideoCodec::Avc),
+                    audio_codec: Some(AudioCodec::Flac),
+                    batch: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "[Kamigami] Made in Abyss S1 [Batch][1080p x265 Opus 10-bit][CHS&CHT]",
+                ReleaseInfo {
+                    group: Some("Kamigami".into()),
+                    titles: titles(&["Made in Abyss S1"]),
+                    season: Some(1),
+                    resolution: Some(1080),
+                    video_codec: Some(VideoCodec::Hevc),
+                    bit_depth: Some(10),
+                    audio_codec: Some(AudioCodec::Opus),
+                    batch: true,
+                    language: Some("简繁".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[DBD-Raws][进击的巨人 最终季/Shingeki no Kyojin The Final Season][01-16TV全集][1080P][BDRip][HEVC-10bit][FLAC][MKV]",
+                ReleaseInfo {
+                    group: Some("DBD-Raws".into()),
+                    titles: titles(&["进击的巨人 最终季", "Shingeki no Kyojin The Final Season"]),
+                    episode_range: Some(EpisodeRange { start: 1, end: 16 }),
+                    resolution: Some(1080),
+                    source: Some(ReleaseSource::BdRip),
+                    video_codec: Some(VideoCodec::Hevc),
+                    bit_depth: Some(10),
+                    audio_codec: Some(AudioCodec::Flac),
+                    container: Some(Container::Mkv),
+                    batch: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "【DHR动研字幕组】[魔物娘的同居日常_Everyday Life with Monster Girls][OAD2][繁体][720P][MP4]",
+                ReleaseInfo {
+                    group: Some("DHR动研字幕组".into()),
+                    titles: titles(&["魔物娘的同居日常_Everyday Life with Monster Girls"]),
+                    episode: Some(2),
+                    resolution: Some(720),
+                    container: Some(Container::Mp4),
+                    special: true,
+                    language: Some("繁体".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[桜都字幕组] 物语系列 第外季&第怪季 / Monogatari Series: Off & Monster Season [08][1080P][简繁内封]",
+                ReleaseInfo {
+                    group: Some("桜都字幕组".into()),
+                    titles: titles(&[
+                        "物语系列 第外季&第怪季",
+                        "Monogatari Series: Off & Monster Season",
+                    ]),
+                    episode: Some(8),
+                    resolution: Some(1080),
+                    language: Some("简繁".into()),
+                    subtitle_type: Some("内封".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "[GJ.Y] 迷宫饭 / Dungeon Meshi - 24 END (Netflix 3840x2160 HEVC DDP)",
+                ReleaseInfo {
+                    group: Some("GJ.Y".into()),
+                    titles: titles(&["迷宫饭", "Dungeon Meshi"]),
+                    episode: Some(24),
+                    resolution: Some(2160),
+                    video_codec: Some(VideoCodec::Hevc),
+                    audio_codec: Some(AudioCodec::Eac3),
+                    ..Default::default()
+                },
+            ),
+            (
+                "无字幕组信息的标题",
+                ReleaseInfo {
+                    titles: titles(&["无字幕组信息的标题"]),
+                    ..Default::default()
+                },
+            ),
+        ];
+        for (title, expected) in cases {
+            assert_eq!(parse_release(title), expected, "title: {title}");
+        }
+    }
+
+    #[test]
+    fn test_parse_release_version_without_episode_suffix() {
+        assert_eq!(
+            parse_release("[Group] Title - 05 [v2][1080p]").version,
+            Some(2)
+        );
+        // v1 视为原始版本
+        assert_eq!(parse_release("[Group] Title - 05v1 [1080p]").version, None);
+        assert_eq!(
+            parse_release("[Group] Title - 05v1 [1080p]").episode,
+            Some(5)
+        );
+        // AV1 不是版本号
+        assert_eq!(parse_release("[Group] Title - 05 [AV1]").version, None);
+    }
+
+    #[test]
+    fn test_parse_release_special() {
+        assert!(parse_release("[Group] Title [OVA1][1080P]").special);
+        assert!(parse_release("【字幕组】作品名 [SP3] 繁体").special);
+        assert!(parse_release("[Group] Title - 特别篇 [1080p]").special);
+        assert!(!parse_release("[喵萌奶茶屋] 间谍过家家 / SPY×FAMILY [01][1080p]").special);
+        assert!(!parse_release("[Group] Title - 05 [1080p]").special);
     }
 }
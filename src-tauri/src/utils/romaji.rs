@@ -0,0 +1,213 @@
+//! Kana romanization
+//!
+//! Converts hiragana / katakana titles to Hepburn-style romaji so that
+//! kana-only Japanese names can be searched on sites indexed by romaji.
+//! Kanji need a dictionary, so titles containing them are not romanized.
+
+const SMALL_VOWELS: &[char] = &['ぁ', 'ぃ', 'ぅ', 'ぇ', 'ぉ'];
+
+fn to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, 'ぁ'..='ゖ' | 'ァ'..='ヺ' | 'ー')
+}
+
+fn kana(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' | 'ゐ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' | 'ゑ' => "e",
+        'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ん' => "n",
+        'ゔ' => "vu",
+        'ヷ' => "va",
+        'ヸ' => "vi",
+        'ヹ' => "ve",
+        'ヺ' => "vo",
+        _ => return None,
+    })
+}
+
+/// Combine a kana with a following small kana: `きゃ` → `kya`, `しゃ` → `sha`,
+/// `ふぁ` → `fa`, `てぃ` → `ti`, `うぃ` → `wi`.
+fn combine(base: &str, small: char) -> Option<String> {
+    let stem = base.strip_suffix(['a', 'i', 'u', 'e', 'o'])?;
+    match small {
+        'ゃ' | 'ゅ' | 'ょ' if base.ends_with('i') && base.len() > 1 => {
+            let vowel = &kana(small)?[1..];
+            Some(match stem {
+                "sh" | "j" | "ch" => format!("{stem}{vowel}"),
+                _ => format!("{stem}y{vowel}"),
+            })
+        }
+        c if SMALL_VOWELS.contains(&c) => {
+            let vowel = kana(c)?;
+            let stem = match (stem, base) {
+                ("", "u") => "w",
+                ("", "i") => "y",
+                ("", _) => return None,
+                (stem, _) => stem,
+            };
+            Some(format!("{stem}{vowel}"))
+        }
+        _ => None,
+    }
+}
+
+/// Romanize a title written in kana. Latin letters and digits are kept,
+/// other symbols become spaces. Returns `None` when the title contains
+/// kanji or other scripts, or has no kana at all.
+///
+/// # Examples
+///
+/// ```
+/// use animefun_lib::utils::romaji::romanize;
+///
+/// assert_eq!(romanize("らき☆すた").as_deref(), Some("raki suta"));
+/// assert_eq!(romanize("ソードアート・オンライン").as_deref(), Some("soodoaato onrain"));
+/// assert_eq!(romanize("葬送のフリーレン"), None);
+/// assert_eq!(romanize("Frieren"), None);
+/// ```
+pub fn romanize(title: &str) -> Option<String> {
+    let chars: Vec<char> = title.chars().map(to_hiragana).collect();
+    let mut out = String::with_capacity(title.len() * 2);
+    let mut saw_kana = false;
+    let mut double_next = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        if c == 'っ' {
+            saw_kana = true;
+            double_next = true;
+            continue;
+        }
+        if c == 'ー' {
+            saw_kana = true;
+            if let Some(v) = out.chars().last().filter(|v| "aiueo".contains(*v)) {
+                out.push(v);
+            }
+            continue;
+        }
+        if let Some(base) = kana(c) {
+            saw_kana = true;
+            let mut syllable = base.to_string();
+            if let Some(combined) = chars.get(i).and_then(|&next| combine(base, next)) {
+                syllable = combined;
+                i += 1;
+            }
+            if std::mem::take(&mut double_next) {
+                let first = if syllable.starts_with("ch") {
+                    't'
+                } else {
+                    syllable.chars().next().unwrap_or_default()
+                };
+                if !"aiueon".contains(first) {
+                    out.push(first);
+                }
+            }
+            out.push_str(&syllable);
+            continue;
+        }
+        double_next = false;
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if c.is_alphanumeric() || is_kana(c) {
+            return None;
+        } else if !out.ends_with(' ') {
+            out.push(' ');
+        }
+    }
+    let out = out.split_whitespace().collect::<Vec<_>>().join(" ");
+    (saw_kana && !out.is_empty()).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn romanizes_digraphs_sokuon_and_long_vowels() {
+        assert_eq!(romanize("きゃしゃ").as_deref(), Some("kyasha"));
+        assert_eq!(romanize("まっちゃ").as_deref(), Some("matcha"));
+        assert_eq!(romanize("ゆるキャン△").as_deref(), Some("yurukyan"));
+        assert_eq!(romanize("ティーンエイジャー").as_deref(), Some("tiineijaa"));
+        assert_eq!(romanize("ファイ ウィッチ").as_deref(), Some("fai witchi"));
+        assert_eq!(romanize("けいおん!! 2").as_deref(), Some("keion 2"));
+    }
+
+    #[test]
+    fn refuses_kanji_and_plain_latin() {
+        assert_eq!(romanize("進撃の巨人"), None);
+        assert_eq!(romanize("SPY×FAMILY"), None);
+        assert_eq!(romanize("！！"), None);
+    }
+}
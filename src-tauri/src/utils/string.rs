@@ -90,6 +90,71 @@ pub fn generate_search_terms_by_stripping(words: &[String]) -> Vec<String> {
     terms
 }
 
+/// Reduce a title to lowercase alphanumeric characters for fuzzy comparison.
+///
+/// # Examples
+///
+/// ```
+/// use animefun_lib::utils::string::normalize_for_match;
+///
+/// assert_eq!(normalize_for_match("Sousou no Frieren!"), "sousounofrieren");
+/// assert_eq!(normalize_for_match("葬送的芙莉莲 第二季"), "葬送的芙莉莲第二季");
+/// ```
+pub fn normalize_for_match(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Levenshtein distance over Unicode scalar values.
+///
+/// # Examples
+///
+/// ```
+/// use animefun_lib::utils::string::edit_distance;
+///
+/// assert_eq!(edit_distance("kitten", "sitting"), 3);
+/// assert_eq!(edit_distance("芙莉莲", "芙莉蓮"), 1);
+/// ```
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+/// Title similarity in `[0, 1]`: one minus the normalized edit distance
+/// between the [`normalize_for_match`] forms of both titles.
+///
+/// # Examples
+///
+/// ```
+/// use animefun_lib::utils::string::title_similarity;
+///
+/// assert_eq!(title_similarity("葬送的芙莉莲", "葬送的芙莉莲！"), 1.0);
+/// assert!(title_similarity("葬送的芙莉莲", "葬送的芙莉蓮") > 0.8);
+/// assert_eq!(title_similarity("", "abc"), 0.0);
+/// ```
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let a = normalize_for_match(a);
+    let b = normalize_for_match(b);
+    let len = a.chars().count().max(b.chars().count());
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    1.0 - edit_distance(&a, &b) as f64 / len as f64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,4 +235,19 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_title_similarity() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(
+            title_similarity("Sousou no Frieren", "sousou-no-frieren"),
+            1.0
+        );
+
+        let close = title_similarity("辉夜大小姐想让我告白", "辉夜大小姐想让我告白 第二季");
+        let far = title_similarity("辉夜大小姐想让我告白", "间谍过家家");
+        assert!(close > 0.6 && close < 1.0);
+        assert!(far < 0.2);
+    }
 }
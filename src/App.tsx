@@ -9,6 +9,7 @@ import { Layout } from "./components/Layout";
 import { ROUTES } from "./constants/routes";
 import { Toaster } from "@/components/ui/sonner";
 import { ConnectionProvider } from "@/hooks/use-connection-state";
+import { useEpisodeRelease } from "@/hooks/use-episode-release";
 import { UpdateDialog, useAutoUpdateCheck } from "@/components/UpdateDialog";
 
 // 从集中模块导入所有懒加载页面和预加载映射表
@@ -25,6 +26,7 @@ import {
 function AppContent() {
   const { updateInfo, showDialog, setShowDialog, checkForUpdates } =
     useAutoUpdateCheck();
+  useEpisodeRelease();
 
   useEffect(() => {
     // 延迟检查更新，避免影响启动速度
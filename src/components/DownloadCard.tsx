@@ -4,13 +4,24 @@ import { Card } from "@/components/ui/card";
 import { Progress } from "@/components/ui/progress";
 import { formatBytes, formatDuration } from "@/lib/utils";
 import { DownloadItem } from "@/types/gen/downloader";
-import { AlertTriangle, Film, Folder, Pause, Play, Trash2 } from "lucide-react";
+import {
+  AlertTriangle,
+  Film,
+  Folder,
+  Megaphone,
+  Pause,
+  Play,
+  ShieldCheck,
+  Trash2,
+} from "lucide-react";
 import { memo } from "react";
 
 interface DownloadCardProps {
   item: DownloadItem;
   onPause: () => void;
   onResume: () => void;
+  onRecheck?: () => void;
+  onReannounce?: () => void;
   onDelete: () => void;
   onOpenFolder?: () => void;
   onCoverClick?: () => void;
@@ -22,6 +33,8 @@ export const DownloadCard = memo<DownloadCardProps>(
     item,
     onPause,
     onResume,
+    onRecheck,
+    onReannounce,
     onDelete,
     onOpenFolder,
     onCoverClick,
@@ -34,13 +47,17 @@ export const DownloadCard = memo<DownloadCardProps>(
       isLive &&
       (liveStatus.toLowerCase().includes("paused") ||
         liveStatus.toLowerCase().includes("stop"));
-    const isCompleted = isLive && item.progress >= 100;
+    const isChecking =
+      item.external_state.kind === "live" && item.external_state.checking;
+    const isCompleted = isLive && !isChecking && item.progress >= 100;
     const stateLabel =
       item.external_state.kind === "stale"
         ? "状态暂不可用"
         : item.external_state.kind === "missing"
           ? "外部任务缺失"
-          : null;
+          : isChecking
+            ? "校验中"
+            : null;
 
     return (
       <Card className="py-2 group overflow-hidden border-border/50 bg-card/50 backdrop-blur-sm transition-all hover:border-primary/50 hover:shadow-md hover:shadow-primary/10">
@@ -124,6 +141,28 @@ export const DownloadCard = memo<DownloadCardProps>(
                     )}
                   </Button>
                 ) : null}
+                {isLive && !isChecking && onRecheck && (
+                  <Button
+                    size="icon"
+                    variant="ghost"
+                    className="h-8 w-8 text-muted-foreground transition-colors hover:text-foreground cursor-pointer"
+                    onClick={onRecheck}
+                    title="强制校验"
+                  >
+                    <ShieldCheck className="h-4 w-4" />
+                  </Button>
+                )}
+                {isLive && !isCompleted && onReannounce && (
+                  <Button
+                    size="icon"
+                    variant="ghost"
+                    className="h-8 w-8 text-muted-foreground transition-colors hover:text-foreground cursor-pointer"
+                    onClick={onReannounce}
+                    title="重新汇报"
+                  >
+                    <Megaphone className="h-4 w-4" />
+                  </Button>
+                )}
                 {isCompleted && item.save_path && onOpenFolder && (
                   <Button
                     size="icon"
@@ -1,4 +1,4 @@
-import React, { useMemo, useState } from "react";
+import React, { useEffect, useMemo, useRef, useState } from "react";
 import { ChevronDown, Loader2 } from "lucide-react";
 import { Button } from "./ui/button";
 import { PaginationBar } from "./PaginationBar";
@@ -19,6 +19,9 @@ interface EpisodesListProps {
   resourcesLoading?: boolean;
   subjectTitle?: string;
   subjectCover?: string | null;
+  /** 需要直接展开资源列表的集数 */
+  focusEpisode?: number | null;
+  onEpisodeFocused?: () => void;
 }
 
 const EpisodesList: React.FC<EpisodesListProps> = ({
@@ -26,6 +29,8 @@ const EpisodesList: React.FC<EpisodesListProps> = ({
   resources,
   resourcesLoading,
   subjectCover,
+  focusEpisode,
+  onEpisodeFocused,
 }) => {
   const {
     data,
@@ -36,6 +41,7 @@ const EpisodesList: React.FC<EpisodesListProps> = ({
     totalPages,
     totalEpisodes,
     jumpToPage,
+    locate,
   } = useEpisodes(subjectId);
 
   const [open, setOpen] = useState(false);
@@ -45,6 +51,19 @@ const EpisodesList: React.FC<EpisodesListProps> = ({
     [data, selectedId],
   );
 
+  // 从更新提醒跳转过来时，翻到对应页并展开该集的资源列表
+  const rootRef = useRef<HTMLDivElement>(null);
+  useEffect(() => {
+    if (focusEpisode == null) return;
+    const target = locate(focusEpisode);
+    if (!target) return;
+    jumpToPage(target.page);
+    setSelectedId(target.id);
+    setOpen(true);
+    rootRef.current?.scrollIntoView({ behavior: "smooth", block: "start" });
+    onEpisodeFocused?.();
+  }, [focusEpisode, locate, jumpToPage, onEpisodeFocused]);
+
   // 发生错误时重新加载
   const handleReload = () => {
     reload();
@@ -73,7 +92,10 @@ const EpisodesList: React.FC<EpisodesListProps> = ({
   }, [totalPages, currentPage, jumpToPage]);
 
   return (
-    <div className="w-full bg-card rounded-xl shadow-sm border border-border/60 overflow-hidden">
+    <div
+      ref={rootRef}
+      className="w-full bg-card rounded-xl shadow-sm border border-border/60 overflow-hidden"
+    >
       {/* 头部 */}
       <div className="px-6 py-4 border-b border-border/60 bg-muted/20">
         <div className="flex items-center justify-between">
@@ -3,11 +3,17 @@ import { Download, WifiOff } from "lucide-react";
 import { FC } from "react";
 import { useNavigate } from "react-router-dom";
 import { formatBytes } from "../lib/utils";
-import type { MikanResourceItem } from "../types/gen/mikan";
+import type { MikanResourceItem, ResourceSource } from "../types/gen/mikan";
 import { Badge } from "./ui/badge";
 import { Button } from "./ui/button";
 import { Spinner } from "./ui/spinner";
 
+const SOURCE_LABELS: Record<ResourceSource, string> = {
+  mikan: "Mikan",
+  dmhy: "动漫花园",
+  nyaa: "Nyaa",
+};
+
 interface ResourceGroupListProps {
   groups: { group: string; items: MikanResourceItem[] }[];
   onDownload: (url: string, title: string, item: MikanResourceItem) => void;
@@ -40,6 +46,9 @@ export const ResourceGroupList: FC<ResourceGroupListProps> = ({
                 <div className="text-sm font-medium">{it.title}</div>
                 <div className="flex items-center justify-between gap-3">
                   <div className="flex flex-wrap items-center gap-2">
+                    {it.source !== "mikan" && (
+                      <Badge variant="outline">{SOURCE_LABELS[it.source]}</Badge>
+                    )}
                     {typeof it.resolution === "number" && (
                       <Badge
                         variant="secondary"
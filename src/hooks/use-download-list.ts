@@ -4,6 +4,8 @@ import {
   getTrackedDownloads,
   pauseDownload,
   playVideo,
+  reannounceDownload,
+  recheckDownload,
   resumeDownload,
 } from "@/lib/api";
 import { DownloadItem } from "@/types/gen/downloader";
@@ -116,6 +118,24 @@ export function useDownloadList() {
     }
   };
 
+  const handleRecheck = async (hash: string) => {
+    try {
+      await recheckDownload(hash);
+      toast.success("已开始校验");
+    } catch {
+      toast.error("校验失败");
+    }
+  };
+
+  const handleReannounce = async (hash: string) => {
+    try {
+      await reannounceDownload(hash);
+      toast.success("已重新汇报");
+    } catch {
+      toast.error("重新汇报失败");
+    }
+  };
+
   const handleDelete = async (hash: string) => {
     try {
       await deleteDownload(hash, true);
@@ -148,6 +168,8 @@ export function useDownloadList() {
     refresh,
     handlePause,
     handleResume,
+    handleRecheck,
+    handleReannounce,
     handleDelete,
     handlePlayVideo,
   };
@@ -0,0 +1,104 @@
+import { useEffect } from "react";
+import { useNavigate } from "react-router-dom";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { toast } from "sonner";
+import { downloadEpisodeRelease } from "@/lib/api";
+import { formatBytes, navigateToEpisodeResources } from "@/lib/utils";
+import type { EpisodeReleaseNotice } from "@/types/gen/mikan";
+
+function describe(notice: EpisodeReleaseNotice) {
+  const parts = [
+    notice.group,
+    notice.resolution ? `${notice.resolution}P` : null,
+    notice.subtitle_lang,
+    notice.size_bytes != null ? formatBytes(notice.size_bytes, 1) : null,
+  ].filter(Boolean);
+  return parts.length > 0 ? parts.join(" · ") : notice.title;
+}
+
+function toastId(notice: EpisodeReleaseNotice) {
+  return `episode-release-${notice.subject_id}-${notice.label}`;
+}
+
+/**
+ * 订阅更新提醒
+ *
+ * 桌面系统通知没有点击回调：后台时发出的提醒由后端暂存，
+ * 主窗口回到前台时通过 `episode-release-open` 打开该集的资源列表。
+ * `episode-release` 的 toast 作为后备，常驻直到用户处理
+ */
+export function useEpisodeRelease() {
+  const navigate = useNavigate();
+
+  useEffect(() => {
+    const unlisteners: UnlistenFn[] = [];
+    let disposed = false;
+
+    const openResources = (notice: EpisodeReleaseNotice) =>
+      navigateToEpisodeResources(
+        navigate,
+        notice.subject_id,
+        notice.episode,
+        notice.episode_range,
+      );
+
+    const download = async (notice: EpisodeReleaseNotice) => {
+      try {
+        await downloadEpisodeRelease(notice);
+        toast.success("已添加到下载列表");
+      } catch (e) {
+        const errorMsg =
+          e && typeof e === "object" && "message" in e
+            ? String(e.message)
+            : String(e);
+        if (
+          errorMsg.includes("torrent_already_exists") ||
+          errorMsg.includes("该任务已在下载列表中")
+        ) {
+          toast.warning("该任务已在下载列表中");
+        } else {
+          toast.error("添加下载失败: " + errorMsg);
+        }
+      }
+      openResources(notice);
+    };
+
+    const register = (promise: Promise<UnlistenFn>) =>
+      promise
+        .then((fn) => {
+          if (disposed) fn();
+          else unlisteners.push(fn);
+        })
+        .catch((error) => {
+          console.error("Failed to listen for episode releases:", error);
+        });
+
+    register(
+      listen<EpisodeReleaseNotice>("episode-release", (event) => {
+        const notice = event.payload;
+        toast(`${notice.anime_name} ${notice.label} 已发布`, {
+          id: toastId(notice),
+          description: describe(notice),
+          duration: Infinity,
+          closeButton: true,
+          action: notice.url
+            ? { label: "下载", onClick: () => download(notice) }
+            : undefined,
+          cancel: { label: "查看", onClick: () => openResources(notice) },
+        });
+      }),
+    );
+
+    register(
+      listen<EpisodeReleaseNotice>("episode-release-open", (event) => {
+        toast.dismiss(toastId(event.payload));
+        openResources(event.payload);
+      }),
+    );
+
+    return () => {
+      disposed = true;
+      unlisteners.forEach((fn) => fn());
+    };
+  }, [navigate]);
+}
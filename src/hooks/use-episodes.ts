@@ -37,6 +37,13 @@ export const useEpisodes = (subjectId: number | undefined) => {
     }
   };
 
+  // 按集数（sort 或 ep）定位剧集及其所在页
+  const locate = (num: number) => {
+    const index = episodes.findIndex((e) => e.sort === num || e.ep === num);
+    if (index < 0) return null;
+    return { id: episodes[index].id, page: Math.floor(index / PAGE_SIZE) };
+  };
+
   const loadNextPage = () => jumpToPage(currentPage + 1);
   const loadPreviousPage = () => jumpToPage(currentPage - 1);
 
@@ -52,5 +59,6 @@ export const useEpisodes = (subjectId: number | undefined) => {
     loadNextPage,
     loadPreviousPage,
     jumpToPage,
+    locate,
   };
 };
@@ -4,16 +4,40 @@ import { check } from "@tauri-apps/plugin-updater";
 import { relaunch } from "@tauri-apps/plugin-process";
 import type { DownloadItem } from "@/types/gen/downloader";
 import type { DownloaderConfig } from "@/types/gen/downloader_config";
+import type { DownloadStats } from "@/types/gen/downloader_stats";
 import type { UserProfile } from "@/types/gen/profile.rs";
 import { TorrentInfo } from "@/types/gen/torrent_info";
 import {
   CalendarDay,
   Anime,
+  EpisodeAvailability,
   PagedEpisode,
   SubjectStatus,
   SubjectStatusCode,
 } from "../types/gen/bangumi";
-import type { MikanResourcesResponse } from "@/types/gen/mikan";
+import type {
+  EpisodeReleaseNotice,
+  MappingConflictPolicy,
+  MappingImportResult,
+  MikanCatalogItem,
+  MikanLink,
+  MikanMapAttempt,
+  MikanMapEntry,
+  MikanMapSuggestion,
+  MikanResourceItem,
+  MikanResourcesResponse,
+  MikanSubgroup,
+  MyBangumiImportResult,
+  MyBangumiPreviewItem,
+  ReleaseCluster,
+  ResourceQuery,
+  ResourceWatch,
+  ResourceWatchInput,
+} from "@/types/gen/mikan";
+import type {
+  MikanConfig,
+  ReleasePreferences,
+} from "@/types/gen/mikan_config";
 import type { SearchResponse } from "@/types/gen/bangumi";
 
 // --- Downloader ---
@@ -33,12 +57,27 @@ export const getTrackedDownloads = () =>
 export const getLiveDownloadInfo = () =>
   invoke<TorrentInfo[]>("get_live_download_info");
 
+export const getDownloadStats = () =>
+  invoke<DownloadStats>("get_download_stats");
+
 export const pauseDownload = (hash: string) =>
   invoke<void>("pause_download", { hash });
 
 export const resumeDownload = (hash: string) =>
   invoke<void>("resume_download", { hash });
 
+export const recheckDownload = (hash: string) =>
+  invoke<void>("recheck_download", { hash });
+
+export const reannounceDownload = (hash: string) =>
+  invoke<void>("reannounce_download", { hash });
+
+export const getDownloadMagnet = (hash: string) =>
+  invoke<string>("get_download_magnet", { hash });
+
+export const exportSubjectMagnets = (subjectId: number) =>
+  invoke<string[]>("export_subject_magnets", { subjectId });
+
 export const deleteDownload = (hash: string, deleteFiles: boolean) =>
   invoke<void>("delete_download", { hash, deleteFiles });
 
@@ -98,6 +137,112 @@ export const searchSubject = (params: {
 export const getMikanResources = (subjectId: number) =>
   invoke<MikanResourcesResponse>("get_mikan_resources", { subjectId });
 
+export const getMikanSubgroups = (subjectId: number) =>
+  invoke<MikanSubgroup[]>("get_mikan_subgroups", { subjectId });
+
+export const getMikanReleaseClusters = (subjectId: number) =>
+  invoke<ReleaseCluster[]>("get_mikan_release_clusters", { subjectId });
+
+export const queryResources = (query: ResourceQuery) =>
+  invoke<MikanResourceItem[]>("query_resources", { query });
+
+export const searchMikan = (keyword: string) =>
+  invoke<MikanCatalogItem[]>("search_mikan", { keyword });
+
+export const getMikanSeason = (
+  year: number,
+  season: "春" | "夏" | "秋" | "冬",
+) => invoke<MikanCatalogItem[]>("get_mikan_season", { year, season });
+
+export const subscribeMikanBangumi = (mikanBangumiId: number) =>
+  invoke<number>("subscribe_mikan_bangumi", { mikanBangumiId });
+
+export const getMikanConfig = () => invoke<MikanConfig>("get_mikan_config");
+
+export const setMikanConfig = (config: MikanConfig) =>
+  invoke<void>("set_mikan_config", { config });
+
+export const getReleasePreferences = () =>
+  invoke<ReleasePreferences>("get_release_preferences");
+
+export const setReleasePreferences = (prefs: ReleasePreferences) =>
+  invoke<void>("set_release_preferences", { prefs });
+
+export const previewMikanMyBangumi = (token: string) =>
+  invoke<MyBangumiPreviewItem[]>("preview_mikan_my_bangumi", { token });
+
+export const importMikanMyBangumi = (
+  token: string,
+  mikanBangumiIds?: number[],
+) =>
+  invoke<MyBangumiImportResult>("import_mikan_my_bangumi", {
+    token,
+    mikanBangumiIds: mikanBangumiIds ?? null,
+  });
+
+export const listResourceWatches = () =>
+  invoke<ResourceWatch[]>("list_resource_watches");
+
+export const addResourceWatch = (watch: ResourceWatchInput) =>
+  invoke<ResourceWatch>("add_resource_watch", { watch });
+
+export const updateResourceWatch = (id: number, watch: ResourceWatchInput) =>
+  invoke<ResourceWatch>("update_resource_watch", { id, watch });
+
+export const deleteResourceWatch = (id: number) =>
+  invoke<void>("delete_resource_watch", { id });
+
+export const downloadEpisodeRelease = (notice: EpisodeReleaseNotice) =>
+  invoke<void>("download_episode_release", { notice });
+
+export const listMikanMappings = () =>
+  invoke<MikanMapEntry[]>("list_mikan_mappings");
+
+export const listMikanUnmapped = () =>
+  invoke<MikanMapAttempt[]>("list_mikan_unmapped");
+
+export const bindMikanMapping = (subjectId: number, mikanBangumiId: number) =>
+  invoke<void>("bind_mikan_mapping", { subjectId, mikanBangumiId });
+
+export const unbindMikanMapping = (subjectId: number) =>
+  invoke<void>("unbind_mikan_mapping", { subjectId });
+
+export const listMikanSuggestions = (subjectId?: number) =>
+  invoke<MikanMapSuggestion[]>("list_mikan_suggestions", {
+    subjectId: subjectId ?? null,
+  });
+
+export const reresolveMikanMapping = (subjectId: number) =>
+  invoke<number | null>("reresolve_mikan_mapping", { subjectId });
+
+export const exportMikanMappings = () =>
+  invoke<string>("export_mikan_mappings");
+
+export const importMikanMappings = (policy: MappingConflictPolicy) =>
+  invoke<MappingImportResult>("import_mikan_mappings", { policy });
+
+export const listMikanLinks = (subjectId: number) =>
+  invoke<MikanLink[]>("list_mikan_links", { subjectId });
+
+export const addMikanLink = (link: MikanLink) =>
+  invoke<void>("add_mikan_link", { link });
+
+export const removeMikanLink = (subjectId: number, mikanBangumiId: number) =>
+  invoke<void>("remove_mikan_link", { subjectId, mikanBangumiId });
+
+export const setMikanLinkRange = (
+  subjectId: number,
+  mikanBangumiId: number,
+  episodeStart: number | null,
+  episodeEnd: number | null,
+) =>
+  invoke<void>("set_mikan_link_range", {
+    subjectId,
+    mikanBangumiId,
+    episodeStart,
+    episodeEnd,
+  });
+
 // --- Subscriptions ---
 
 export const getSubscriptions = async () => {
@@ -126,6 +271,29 @@ export const toggleSubscription = async (id: number) => {
 export const setSubscriptionNotify = (id: number, notify: boolean) =>
   invoke<void>("sub_set_notify", { id, notify });
 
+export const getSubscriptionSubgroups = (id: number) =>
+  invoke<number[]>("sub_get_subgroups", { id });
+
+export const setSubscriptionSubgroups = (id: number, subgroups: number[]) =>
+  invoke<void>("sub_set_subgroups", { id, subgroups });
+
+export const getSubscriptionEpisodeOffset = (id: number) =>
+  invoke<number | null>("sub_get_episode_offset", { id });
+
+export const setSubscriptionEpisodeOffset = (
+  id: number,
+  offset: number | null,
+) => invoke<void>("sub_set_episode_offset", { id, offset });
+
+export const getEpisodeAvailability = (id: number) =>
+  invoke<EpisodeAvailability[]>("sub_get_episode_availability", { id });
+
+export const setEpisodeWatched = (
+  id: number,
+  episode: number,
+  watched: boolean,
+) => invoke<void>("sub_set_episode_watched", { id, episode, watched });
+
 export const clearSubscriptions = () => invoke<void>("sub_clear");
 
 export const sendTestNotification = () =>
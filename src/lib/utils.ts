@@ -119,6 +119,18 @@ export function navigateToAnimeDetail(
   navigate(ROUTES.ANIME_DETAIL.replace(":id", id.toString()));
 }
 
+/** 打开条目详情并展开某一集的资源列表；合集取范围的第一集 */
+export function navigateToEpisodeResources(
+  navigate: NavigateFunction,
+  id: number | string,
+  episode?: number | null,
+  episodeRange?: string | null,
+) {
+  const first = episode ?? Number.parseInt(episodeRange ?? "", 10);
+  const path = ROUTES.ANIME_DETAIL.replace(":id", id.toString());
+  navigate(Number.isFinite(first) ? `${path}?episode=${first}` : path);
+}
+
 export function formatRelativeTime(date: Date | null | undefined): string {
   if (!date) return "从未检测";
   const now = new Date();
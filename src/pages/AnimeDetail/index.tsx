@@ -1,6 +1,6 @@
 import { Bell, Calendar, Film, Star, Tv2Icon } from "lucide-react";
-import { useEffect, useRef, useState } from "react";
-import { useNavigate, useParams } from "react-router-dom";
+import { useCallback, useEffect, useRef, useState } from "react";
+import { useNavigate, useParams, useSearchParams } from "react-router-dom";
 import { AnimeInfoBox } from "../../components/AnimeInfoBox";
 import EpisodesList from "../../components/EpisodesList";
 import { SubscribeButton } from "../../components/SubscribeButton";
@@ -24,6 +24,13 @@ import { useSubscriptions } from "../../hooks/use-subscriptions";
 const AnimeDetailPage = () => {
   const { id } = useParams();
   const navigate = useNavigate();
+  const [searchParams, setSearchParams] = useSearchParams();
+  const focusEpisode = Number.parseInt(searchParams.get("episode") ?? "", 10);
+  // 资源列表展开后移除参数，再次跳转同一集时仍会展开
+  const clearFocusEpisode = useCallback(
+    () => setSearchParams({}, { replace: true }),
+    [setSearchParams],
+  );
   const { data, loading, error, reload } = useAnimeDetail(id);
   const leftPanelRef = useRef<HTMLDivElement>(null);
   const [leftPanelHeight, setLeftPanelHeight] = useState<number>(0);
@@ -352,6 +359,8 @@ const AnimeDetailPage = () => {
             resourcesLoading={mikan.loading}
             subjectTitle={data.name_cn || data.name}
             subjectCover={cachedSrc}
+            focusEpisode={Number.isFinite(focusEpisode) ? focusEpisode : null}
+            onEpisodeFocused={clearFocusEpisode}
           />
         </div>
       </div>
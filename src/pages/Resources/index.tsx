@@ -39,6 +39,8 @@ const ResourcesPage: FC = () => {
     refresh,
     handlePause,
     handleResume,
+    handleRecheck,
+    handleReannounce,
     handleDelete,
     handlePlayVideo,
   } = useDownloadList();
@@ -186,6 +188,8 @@ const ResourcesPage: FC = () => {
             item={item}
             onPause={() => handlePause(item.hash)}
             onResume={() => handleResume(item.hash)}
+            onRecheck={() => handleRecheck(item.hash)}
+            onReannounce={() => handleReannounce(item.hash)}
             onDelete={() => setItemToDelete(item)}
             onOpenFolder={() => handleOpenFolder(item)}
             onCoverClick={() => handleCoverClick(item.subject_id)}
@@ -64,6 +64,26 @@ export type Episode = {
   subject_id?: number;
 };
 
+/**
+ * 订阅条目中一集的播出、资源、下载与观看状态
+ */
+export type EpisodeAvailability = {
+  episode_id: number;
+  sort: number;
+  name: string;
+  name_cn: string;
+  airdate: string;
+  aired: boolean;
+  /**
+   * 已发布该集的字幕组，包括覆盖该集的合集
+   */
+  groups: Array<string>;
+  resolutions: Array<number>;
+  tracked: boolean;
+  downloaded: boolean;
+  watched: boolean;
+};
+
 export type Images = {
   large: string;
   common: string;
@@ -1,7 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export type DownloadExternalState =
-  | { kind: "live"; status: string }
+  | { kind: "live"; status: string; checking: boolean }
   | { kind: "stale" }
   | { kind: "missing" };
 
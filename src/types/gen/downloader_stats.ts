@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DownloadStats = {
+  total_bytes: bigint;
+  season_bytes: bigint;
+  episodes: number;
+  avg_download_secs?: bigint;
+  subjects: Array<SubjectUsage>;
+  groups: Array<GroupUsage>;
+};
+
+export type GroupUsage = {
+  group: string;
+  bytes: bigint;
+  episodes: number;
+  subjects: number;
+};
+
+export type SubjectUsage = {
+  subject_id: number;
+  title: string;
+  bytes: bigint;
+  episodes: number;
+};
@@ -1,4 +1,125 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReleaseInfo } from "./release";
+
+/**
+ * 预热发现新一集时推荐的资源，随 `episode-release` 事件发给前端。
+ * 集数已换算为 Bangumi 编号，可直接用于下载。
+ */
+export type EpisodeReleaseNotice = {
+  subject_id: number;
+  anime_name: string;
+  /**
+   * 通知中的集名，如 `第 5 话`
+   */
+  label: string;
+  title: string;
+  group?: string;
+  resolution?: number;
+  subtitle_lang?: string;
+  size_bytes?: bigint;
+  /**
+   * 种子或磁力链接
+   */
+  url?: string;
+  episode?: number;
+  episode_range?: string;
+};
+
+/**
+ * 导入映射包时与本地映射冲突的处理方式
+ */
+export type MappingConflictPolicy =
+  | "keep_local"
+  | "take_imported"
+  | "higher_confidence";
+
+export type MappingImportResult = {
+  added: number;
+  replaced: number;
+  /**
+   * 与本地完全一致的映射
+   */
+  unchanged: number;
+  /**
+   * 因冲突策略保留本地的映射
+   */
+  skipped: number;
+  /**
+   * 写入了集数偏移的订阅数
+   */
+  offsets: number;
+};
+
+/**
+ * Mikan 搜索结果或季度番组表中的一个番组
+ */
+export type MikanCatalogItem = {
+  mikan_bangumi_id: number;
+  title: string;
+  cover_url?: string;
+  /**
+   * 季度番组表中的放送日：0 为周日，7 为剧场版，8 为 OVA
+   */
+  day_of_week?: number;
+};
+
+/**
+ * 条目映射到的一个 Mikan 番组；集数范围按 feed 中的原始编号过滤资源
+ */
+export type MikanLink = {
+  subject_id: number;
+  mikan_bangumi_id: number;
+  role: MikanLinkRole;
+  episode_start?: number;
+  episode_end?: number;
+};
+
+/**
+ * 条目与某个 Mikan 番组的关系
+ */
+export type MikanLinkRole = "primary" | "split" | "merged" | "extra";
+
+/**
+ * 尚未映射到 Mikan 的订阅及最近一次自动解析的结果；从未尝试过时各字段为空
+ */
+export type MikanMapAttempt = {
+  subject_id: number;
+  name: string;
+  name_cn: string;
+  attempts: number;
+  search_terms: Array<string>;
+  /**
+   * 搜索到的 Mikan 番组 ID
+   */
+  candidates: Array<number>;
+  /**
+   * `no_results` / `no_match` / `low_confidence` / `error`
+   */
+  reason?: string;
+  error?: string;
+  last_attempt?: bigint;
+  next_retry?: bigint;
+};
+
+export type MikanMapEntry = {
+  subject_id: number;
+  mikan_bangumi_id: number;
+  confidence: number;
+  source: string;
+  locked: boolean;
+  updated_at: bigint;
+  name: string;
+  name_cn: string;
+};
+
+export type MikanMapSuggestion = {
+  subject_id: number;
+  mikan_bangumi_id: number;
+  score: number;
+  title: string;
+  name: string;
+  name_cn: string;
+};
 
 export type MikanResourceItem = {
   title: string;
@@ -13,6 +134,15 @@ export type MikanResourceItem = {
   resolution?: number;
   subtitle_lang?: string;
   subtitle_type?: string;
+  /**
+   * 来自哪个 Mikan 番组的 feed；其他站点的资源为空
+   */
+  mikan_bangumi_id?: number;
+  source: ResourceSource;
+  /**
+   * 完整的标题解析结果；上面的扁平字段保留给现有界面使用
+   */
+  release: ReleaseInfo;
 };
 
 export type MikanResourcesResponse = {
@@ -20,3 +150,105 @@ export type MikanResourcesResponse = {
   mikan_bangumi_id?: number;
   items: Array<MikanResourceItem>;
 };
+
+/**
+ * Mikan 番组页上列出的字幕组
+ */
+export type MikanSubgroup = { id: number; name: string };
+
+export type MyBangumiImportResult = {
+  subscribed: number;
+  mapped: number;
+  /**
+   * 无法对应到 Bangumi 条目的 Mikan 番组 id
+   */
+  unresolved: Array<number>;
+  /**
+   * 已手动绑定到其他番组而跳过的 Bangumi 条目 id
+   */
+  conflicts: Array<number>;
+};
+
+/**
+ * 「我的番组」导入预览中的一项
+ */
+export type MyBangumiPreviewItem = {
+  mikan_bangumi_id: number;
+  title: string;
+  /**
+   * Mikan 页面上链接的 Bangumi 条目，缺失时无法导入
+   */
+  subject_id?: number;
+  subscribed: boolean;
+  /**
+   * 该条目当前已有的映射
+   */
+  mapped_mikan_id?: number;
+};
+
+/**
+ * 资源及其按偏好计算的分数
+ */
+export type RankedRelease = { score: number; item: MikanResourceItem };
+
+/**
+ * 同一集（或同一合集范围）的资源，`best` 为首选
+ */
+export type ReleaseCluster = {
+  episode?: number;
+  episode_range?: string;
+  best: RankedRelease;
+  alternatives: Array<RankedRelease>;
+};
+
+/**
+ * 资源存档查询条件；集数为换算偏移后的 Bangumi 编号
+ */
+export type ResourceQuery = {
+  subject_id: number;
+  episode?: number;
+  /**
+   * 字幕组名中包含该文本即匹配，忽略大小写
+   */
+  group?: string;
+  resolution?: number;
+  limit?: number;
+};
+
+/**
+ * 资源来自哪个站点
+ */
+export type ResourceSource = "mikan" | "dmhy" | "nyaa";
+
+/**
+ * 关键词订阅：轮询 Mikan 搜索 feed，标题匹配的新资源会提醒
+ */
+export type ResourceWatch = {
+  id: number;
+  name: string;
+  /**
+   * Mikan 搜索关键词
+   */
+  keyword: string;
+  /**
+   * 标题还需匹配的正则；为空时只按关键词
+   */
+  pattern?: string;
+  /**
+   * 新资源自动下载并归入该条目
+   */
+  download_subject_id?: number;
+  enabled: boolean;
+  created_at: bigint;
+};
+
+/**
+ * 新建或修改关键词订阅
+ */
+export type ResourceWatchInput = {
+  name: string;
+  keyword: string;
+  pattern?: string;
+  download_subject_id?: number;
+  enabled: boolean;
+};
@@ -0,0 +1,50 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Mikan 相关设置，保存在 `mikan.json`；缺失字段取默认值，方便后续扩展
+ */
+export type MikanConfig = {
+  /**
+   * 「我的番组」RSS 的 token
+   */
+  my_bangumi_token?: string;
+  /**
+   * 是否定期按 token 重新同步订阅
+   */
+  my_bangumi_sync: boolean;
+  /**
+   * 镜像列表，按顺序尝试；为空时使用内置列表
+   */
+  hosts: Array<string>;
+  sources: SourceSettings;
+  preferences: ReleasePreferences;
+};
+
+/**
+ * 资源排序偏好；列表按优先级从高到低
+ */
+export type ReleasePreferences = {
+  preferred_groups: Array<string>;
+  /**
+   * 屏蔽的字幕组不会出现在分组结果中
+   */
+  blocked_groups: Array<string>;
+  resolution?: number;
+  /**
+   * 字幕语言，如 `简繁`、`简日`
+   */
+  languages: Array<string>;
+  /**
+   * 字幕形式，如 `内封`、`外挂`
+   */
+  subtitle_types: Array<string>;
+  /**
+   * 单集体积上限，超过时视为异常
+   */
+  max_episode_mb: number;
+};
+
+/**
+ * 各资源站点的开关；默认只启用 Mikan
+ */
+export type SourceSettings = { mikan: boolean; dmhy: boolean; nyaa: boolean };
@@ -0,0 +1,65 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AudioCodec = "flac" | "eac3" | "ac3" | "dts" | "opus" | "aac";
+
+export type Container = "mkv" | "mp4";
+
+/**
+ * 合集的集数范围（闭区间）
+ */
+export type EpisodeRange = { start: number; end: number };
+
+/**
+ * 从发布标题中解析出的结构化信息，见 `utils::parser::parse_release`
+ */
+export type ReleaseInfo = {
+  group?: string;
+  /**
+   * 标题中以 `/` 分隔的各语言名称
+   */
+  titles: Array<string>;
+  season?: number;
+  episode?: number;
+  episode_range?: EpisodeRange;
+  /**
+   * 修正版本号，如 `05v2` 中的 2
+   */
+  version?: number;
+  resolution?: number;
+  source?: ReleaseSource;
+  video_codec?: VideoCodec;
+  bit_depth?: number;
+  audio_codec?: AudioCodec;
+  container?: Container;
+  /**
+   * 合集 / 全集发布
+   */
+  batch: boolean;
+  /**
+   * 特别篇：OVA、OAD、SP 等，集数不属于正片编号
+   */
+  special: boolean;
+  /**
+   * 字幕语言，如 `简繁`、`简日`
+   */
+  language?: string;
+  /**
+   * 字幕形式：外挂、内封或硬字幕
+   */
+  subtitle_type?: string;
+};
+
+/**
+ * 发布来源：压制类型或 Web 平台
+ */
+export type ReleaseSource =
+  | "bdrip"
+  | "dvdrip"
+  | "baha"
+  | "bglobal"
+  | "bilibili"
+  | "crunchyroll"
+  | "webdl"
+  | "webrip";
+
+export type VideoCodec = "avc" | "hevc" | "av1";
@@ -8,4 +8,7 @@ export type TorrentInfo = {
   dlspeed: bigint;
   eta: bigint;
   save_path: string;
+  size: bigint;
+  added_on: bigint;
+  completion_on: bigint;
 };